@@ -0,0 +1,26 @@
+use std::{env, path};
+
+/// Generate the C header for this crate's `extern "C"` functions.
+///
+/// The header used to be hand-written and drifted out of sync with `src/lib.rs` (see
+/// `rockyou_ffi_abi_version` below, added specifically so consumers no longer have to
+/// guess whether their copy is current). It is written into the crate directory,
+/// alongside `Cargo.toml`, so `make build` can pick it up next to the compiled
+/// library the same way it already does for `lib/parse_ffi.h`.
+fn main() {
+    println!("cargo::rerun-if-changed=src/lib.rs");
+    println!("cargo::rerun-if-changed=cbindgen.toml");
+
+    let crate_dir = env::var("CARGO_MANIFEST_DIR")
+        .expect("Failed to get the manifest directory. Please make sure that the environment variable `CARGO_MANIFEST_DIR` is set.");
+
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(
+            cbindgen::Config::from_file(path::Path::new(&crate_dir).join("cbindgen.toml"))
+                .expect("Failed to read cbindgen.toml."),
+        )
+        .generate()
+        .expect("Failed to generate C bindings for parse-ffi.")
+        .write_to_file(path::Path::new(&crate_dir).join("parse_ffi.h"));
+}
@@ -1,7 +1,9 @@
 //! A FFI wrapper around things Go need from Rust.
 //!
-use libc::c_char;
+use libc::{c_char, c_void};
+use std::cell::RefCell;
 use std::ffi::{CStr, CString, NulError};
+use std::panic::{self, AssertUnwindSafe};
 
 use rockyou2024::config;
 use rockyou2024::models::IndexOf;
@@ -11,6 +13,167 @@ use rockyou2024::models::IndexCollection;
 
 const LOG_TARGET: &str = "ffi";
 
+thread_local! {
+    /// The message from the most recent failure on this thread; see
+    /// [`rockyou_last_error_message`].
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Record `message` as this thread's last error, for retrieval through
+/// [`rockyou_last_error_message`].
+///
+/// A `message` containing a NUL byte is replaced with a placeholder, since it cannot
+/// round-trip through a C string.
+fn set_last_error(message: impl std::fmt::Display) {
+    let c_string = CString::new(message.to_string()).unwrap_or_else(|_| {
+        CString::new("<error message contained a NUL byte>")
+            .expect("This literal does not contain a NUL byte.")
+    });
+
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(c_string));
+}
+
+/// Log `message` under [`LOG_TARGET`] and record it as this thread's last error.
+///
+/// This is what every fallible `extern "C"` function in this crate calls in place of a
+/// bare `rockyou2024::warn!`, so a caller that only checked for a null/error return can
+/// still recover *why* through [`rockyou_last_error_message`].
+macro_rules! fail {
+    (target: $target:expr, $($arg:tt)+) => {{
+        let message = format!($($arg)+);
+        set_last_error(&message);
+        rockyou2024::warn!(target: $target, "{message}");
+    }};
+}
+
+#[no_mangle]
+/// Return the message from the most recent failure on the calling thread, or a null
+/// pointer if there has not been one (or it has already been consumed).
+///
+/// Calling this clears the stored message, so a given failure is only reported once;
+/// call it immediately after a `extern "C"` function in this crate returns a
+/// null/error value, before making another call on the same thread.
+///
+/// For use in Go.
+///
+/// # Safety
+///
+/// Go will be responsible for freeing the memory allocated; call `free_string` on the
+/// result, not `C.free`.
+pub unsafe extern "C" fn rockyou_last_error_message() -> *mut c_char {
+    catch_panic("rockyou_last_error_message", || {
+        LAST_ERROR
+            .with(|last_error| last_error.borrow_mut().take())
+            .map(CString::into_raw)
+            .unwrap_or(std::ptr::null_mut())
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+/// Run `f`, catching any panic before it can unwind across the FFI boundary, which is
+/// undefined behaviour.
+///
+/// Every `extern "C"` function in this crate should wrap its entire body in this, so a
+/// panic anywhere in `rockyou2024` (an out-of-bounds slice, an `.unwrap()` on `None`,
+/// and so on) is turned into a logged error, this thread's last error, and a
+/// null/error return instead of corrupting the caller's stack.
+fn catch_panic<T>(fn_name: &str, f: impl FnOnce() -> T) -> Option<T> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .map_err(|payload| {
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("<no panic message>");
+
+            let message = format!("`{fn_name}` panicked: {message}");
+            set_last_error(&message);
+            rockyou2024::error!(target: LOG_TARGET, "{message}");
+        })
+        .ok()
+}
+
+/// The ABI version of this crate's `extern "C"` surface.
+///
+/// Bump this whenever a change to an exported function's signature, an exported
+/// struct's layout, or the meaning of an existing return value would break a
+/// consumer built against the previous version. Purely additive changes (a new
+/// function) do not require a bump.
+const ABI_VERSION: u32 = 1;
+
+#[no_mangle]
+/// Return the ABI version of this crate's `extern "C"` surface.
+///
+/// Consumers (such as the Go bindings in `lib/`) should call this once at startup
+/// and compare it against the version they were built against, so a mismatched
+/// `libparse_ffi` can be reported as an error instead of causing undefined
+/// behaviour further down the line.
+///
+/// Returns `0` (never a real ABI version, which starts at 1) if this panicked.
+pub extern "C" fn rockyou_ffi_abi_version() -> u32 {
+    catch_panic("rockyou_ffi_abi_version", || ABI_VERSION).unwrap_or(0)
+}
+
+#[no_mangle]
+/// Free a single string previously returned by one of this crate's `extern "C"`
+/// functions (`as_search_string`, `rockyou_last_error_message`, or an element of an
+/// array returned by `indices_of`/`find_lines_in_index_collection`/
+/// `index_collection_search`, freed individually rather than through
+/// `free_string_array`).
+///
+/// `C.free` is the wrong call for these: they were allocated by `CString::into_raw`,
+/// which uses Rust's global allocator, not `malloc`. Does nothing if `ptr` is null.
+///
+/// For use in Go.
+///
+/// # Safety
+///
+/// `ptr` must either be null or have been returned by one of the functions listed
+/// above, and must not be freed (through this function or otherwise) more than once.
+pub unsafe extern "C" fn free_string(ptr: *mut c_char) {
+    catch_panic("free_string", || {
+        if ptr.is_null() {
+            return;
+        }
+
+        drop(unsafe { CString::from_raw(ptr) });
+    });
+}
+
+#[no_mangle]
+/// Free a null-terminated array of strings previously returned by `indices_of`,
+/// `find_lines_in_index_collection`, or `index_collection_search`, along with every
+/// string it contains.
+///
+/// `C.free` is the wrong call for these, for the same reason as `free_string`. Does
+/// nothing if `ptr` is null.
+///
+/// For use in Go.
+///
+/// # Safety
+///
+/// `ptr` must either be null or have been returned by one of the functions listed
+/// above, must still have its original null terminator intact, and must not be freed
+/// (through this function or otherwise) more than once.
+pub unsafe extern "C" fn free_string_array(ptr: *mut *mut c_char) {
+    catch_panic("free_string_array", || {
+        if ptr.is_null() {
+            return;
+        }
+
+        let mut len = 0;
+        while !unsafe { *ptr.add(len) }.is_null() {
+            drop(unsafe { CString::from_raw(*ptr.add(len)) });
+            len += 1;
+        }
+
+        // `vec_str_to_mut_mut_c_char!` shrinks the backing `Vec` to fit before handing
+        // out its pointer, so its capacity is exactly `len` strings plus the null
+        // terminator.
+        drop(unsafe { Vec::from_raw_parts(ptr, len + 1, len + 1) });
+    });
+}
+
 macro_rules! vec_str_to_mut_mut_c_char {
     ($vec_str:expr) => {
         match Result::<Vec<_>, _>::from_iter(
@@ -20,6 +183,9 @@ macro_rules! vec_str_to_mut_mut_c_char {
         ) {
             Ok(mut cstrs) => {
                 cstrs.push(std::ptr::null_mut());
+                // `free_string_array` reconstructs this `Vec` from its pointer and
+                // length alone, so its capacity must exactly match its length.
+                cstrs.shrink_to_fit();
 
                 let ctrs_ptr = cstrs.as_mut_ptr();
 
@@ -28,7 +194,7 @@ macro_rules! vec_str_to_mut_mut_c_char {
                 ctrs_ptr
             },
             Err(err) => {
-                rockyou2024::warn!(
+                fail!(
                     target: LOG_TARGET,
                     "Could not convert `{vec_str}` to `CString`: {err}",
                     vec_str=stringify!($vec_str),
@@ -56,32 +222,35 @@ macro_rules! vec_str_to_mut_mut_c_char {
 /// This function is unsafe because it dereferences raw pointers; this is unavoidable if we have to
 /// pass an array of strings to Go.
 ///
-/// Go will be responsible for freeing the memory allocated; please ensure that
-/// `defer C.free(unsafe.Pointer(ptr))` is called for each string in the array.
+/// Go will be responsible for freeing the memory allocated; call `free_string_array`
+/// on the result, not `C.free`.
 pub unsafe extern "C" fn indices_of(input: *const c_char) -> *mut *mut c_char {
-    if input.is_null() {
-        rockyou2024::warn!(
-            target: LOG_TARGET,
-            "Received a null pointer for `input`.",
-        );
-        return std::ptr::null_mut();
-    }
-
-    let c_str = unsafe { CStr::from_ptr(input) };
-    let rust_str = match c_str.to_str() {
-        Ok(s) => s,
-        Err(_) => {
-            rockyou2024::warn!(
+    catch_panic("indices_of", || {
+        if input.is_null() {
+            fail!(
                 target: LOG_TARGET,
-                "Could not convert '{c_str:?}' to a Rust string.",
+                "Received a null pointer for `input`.",
             );
             return std::ptr::null_mut();
         }
-    };
 
-    vec_str_to_mut_mut_c_char!(
-        IndexOf::<{ config::INDEX_LENGTH }, { config::INDEX_DEPTH }>::from(rust_str.as_bytes())
-    )
+        let c_str = unsafe { CStr::from_ptr(input) };
+        let rust_str = match c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        vec_str_to_mut_mut_c_char!(
+            IndexOf::<{ config::INDEX_LENGTH }, { config::INDEX_DEPTH }>::from(rust_str.as_bytes())
+        )
+    })
+    .unwrap_or(std::ptr::null_mut())
 }
 
 #[no_mangle]
@@ -90,72 +259,388 @@ pub unsafe extern "C" fn as_search_string(
     query: *const c_char,
     search_style: *const c_char,
 ) -> *mut c_char {
-    let query_c_str = unsafe { CStr::from_ptr(query) };
-    let query_str = match query_c_str.to_str() {
-        Ok(s) => s,
-        Err(_) => {
-            rockyou2024::warn!(
+    catch_panic("as_search_string", || {
+        let query_c_str = unsafe { CStr::from_ptr(query) };
+        let query_str = match query_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{query_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let search_style_c_str = unsafe { CStr::from_ptr(search_style) };
+        let search_style_str = match search_style_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{search_style_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let search_style = match search_style_str {
+            "strict" => rockyou2024::search::SearchStyle::Strict,
+            "case-insensitive" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: false },
+            "case-insensitive-unicode" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: true },
+            "fuzzy" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: false,
+            },
+            "fuzzy-keyboard" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: true,
+            },
+            "wildcard" => rockyou2024::search::SearchStyle::Wildcard,
+            "exact" => rockyou2024::search::SearchStyle::Exact,
+            "phonetic" => rockyou2024::search::SearchStyle::Phonetic,
+            s if s.starts_with("edit-distance-") => {
+                match s["edit-distance-".len()..].parse::<usize>() {
+                    Ok(max_distance) => {
+                        rockyou2024::search::SearchStyle::edit_distance(max_distance)
+                    }
+                    Err(_) => {
+                        fail!(
+                            target: LOG_TARGET,
+                            "Could not parse the edit distance out of search style '{search_style_str}'.",
+                        );
+                        return std::ptr::null_mut();
+                    }
+                }
+            }
+            _ => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Unknown search style '{search_style_str}'.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let transformed = search_style.transform_query()(&[query_str])
+            .pop()
+            .expect("The transformed query should always have at least one element.");
+
+        match CString::new(transformed) {
+            Ok(c_str) => c_str.into_raw(),
+            Err(err) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert final string to a `CString`: {err}",
+                    err=err,
+                );
+                std::ptr::null_mut()
+            }
+        }
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+#[no_mangle]
+/// Find the lines in the index collection that contain the given query.
+///
+/// This function is a wrapper around the [`IndexCollection::find_lines_containing`] method, which
+/// does not report errors. This function will log any errors and return a null pointer if an error
+/// occurs, including:
+///
+/// - The `dir` pointer is null.
+/// - The path given by `dir` is not a directory.
+/// - The index at `dir` was built with different `LENGTH`/`DEPTH` parameters than
+///   this library was compiled with, which would otherwise silently return no
+///   results for every search through this handle.
+/// - The `query` pointer is null.
+/// - The `search_style` pointer is null.
+/// - The `search_style` is not one of "strict", "case-insensitive", "fuzzy",
+///   "fuzzy-keyboard", "wildcard", "exact", or "edit-distance-N" (where `N` is the
+///   maximum edit distance).
+///
+/// For use in Go.
+///
+/// # Safety
+///
+/// This function is unsafe because it dereferences raw pointers; this is unavoidable if we have to
+/// pass an array of strings to Go.
+///
+/// Go will be responsible for freeing the memory allocated; call `free_string_array`
+/// on the result, not `C.free`.
+pub unsafe extern "C" fn find_lines_in_index_collection(
+    dir: *const c_char,
+    query: *const c_char,
+    search_style: *const c_char,
+) -> *mut *mut c_char {
+    catch_panic("find_lines_in_index_collection", || {
+        // Validate the input.
+        if dir.is_null() {
+            fail!(
                 target: LOG_TARGET,
-                "Could not convert '{query_c_str:?}' to a Rust string.",
+                "Received a null pointer for `dir`.",
             );
             return std::ptr::null_mut();
         }
-    };
 
-    let search_style_c_str = unsafe { CStr::from_ptr(search_style) };
-    let search_style_str = match search_style_c_str.to_str() {
-        Ok(s) => s,
-        Err(_) => {
-            rockyou2024::warn!(
+        let dir_c_str = unsafe { CStr::from_ptr(dir) };
+        let dir_str = match dir_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{dir_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let path = std::path::Path::new(dir_str);
+        if !path.is_dir() {
+            fail!(
                 target: LOG_TARGET,
-                "Could not convert '{search_style_c_str:?}' to a Rust string.",
+                "The path '{path:?}' is not a directory.",
             );
             return std::ptr::null_mut();
         }
-    };
 
-    let search_style = match search_style_str {
-        "strict" => rockyou2024::search::SearchStyle::Strict,
-        "case-insensitive" => rockyou2024::search::SearchStyle::CaseInsensitive,
-        "fuzzy" => rockyou2024::search::SearchStyle::Fuzzy,
-        _ => {
-            rockyou2024::warn!(
+        let query_c_str = unsafe { CStr::from_ptr(query) };
+        let query_str = match query_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{query_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let search_style_c_str = unsafe { CStr::from_ptr(search_style) };
+        let search_style_str = match search_style_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{search_style_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let search_style = match search_style_str {
+            "strict" => rockyou2024::search::SearchStyle::Strict,
+            "case-insensitive" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: false },
+            "case-insensitive-unicode" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: true },
+            "fuzzy" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: false,
+            },
+            "fuzzy-keyboard" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: true,
+            },
+            "wildcard" => rockyou2024::search::SearchStyle::Wildcard,
+            "exact" => rockyou2024::search::SearchStyle::Exact,
+            "phonetic" => rockyou2024::search::SearchStyle::Phonetic,
+            s if s.starts_with("edit-distance-") => {
+                match s["edit-distance-".len()..].parse::<usize>() {
+                    Ok(max_distance) => {
+                        rockyou2024::search::SearchStyle::edit_distance(max_distance)
+                    }
+                    Err(_) => {
+                        fail!(
+                            target: LOG_TARGET,
+                            "Could not parse the edit distance out of search style '{search_style_str}'.",
+                        );
+                        return std::ptr::null_mut();
+                    }
+                }
+            }
+            _ => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Unknown search style '{search_style_str}'.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        // Perform the search.
+        let index_collection = match rockyou2024::models::IndexCollection::<
+            { config::INDEX_LENGTH },
+            { config::INDEX_DEPTH },
+        >::open_validated(path.to_path_buf())
+        {
+            Ok(index_collection) => index_collection,
+            Err(err) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Failed to open the index at '{path:?}': {err}.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let found = index_collection.find_lines_containing(query_str, search_style, None);
+
+        vec_str_to_mut_mut_c_char!(<rockyou2024::models::IndexCollectionResult as Clone>::clone(
+            &found
+        ))
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+#[no_mangle]
+/// Find the lines in the index collection that contain the given query, with control
+/// bytes and the Unicode replacement character escaped as `\xNN`.
+///
+/// This is otherwise identical to [`find_lines_in_index_collection`]; see that
+/// function's docs for the meaning of `dir`, `query`, `search_style`, and the
+/// conditions under which this returns a null pointer. Escaping is applied so that a
+/// non-UTF-8 or control-byte-laden line from the dump cannot corrupt a caller's
+/// terminal, and so it survives round-tripping through `CString` even when it
+/// contains an embedded NUL byte.
+///
+/// For use in Go.
+///
+/// # Safety
+///
+/// This function is unsafe because it dereferences raw pointers; this is unavoidable if we have to
+/// pass an array of strings to Go.
+///
+/// Go will be responsible for freeing the memory allocated; call `free_string_array`
+/// on the result, not `C.free`.
+pub unsafe extern "C" fn find_lines_in_index_collection_hex_escaped(
+    dir: *const c_char,
+    query: *const c_char,
+    search_style: *const c_char,
+) -> *mut *mut c_char {
+    catch_panic("find_lines_in_index_collection_hex_escaped", || {
+        // Validate the input.
+        if dir.is_null() {
+            fail!(
                 target: LOG_TARGET,
-                "Unknown search style '{search_style_str}'.",
+                "Received a null pointer for `dir`.",
             );
             return std::ptr::null_mut();
         }
-    };
 
-    let transformed = search_style.transform_query()(&[query_str])
-        .pop()
-        .expect("The transformed query should always have at least one element.");
+        let dir_c_str = unsafe { CStr::from_ptr(dir) };
+        let dir_str = match dir_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{dir_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
 
-    match CString::new(transformed) {
-        Ok(c_str) => c_str.into_raw(),
-        Err(err) => {
-            rockyou2024::warn!(
+        let path = std::path::Path::new(dir_str);
+        if !path.is_dir() {
+            fail!(
                 target: LOG_TARGET,
-                "Could not convert final string to a `CString`: {err}",
-                err=err,
+                "The path '{path:?}' is not a directory.",
             );
             return std::ptr::null_mut();
         }
-    }
+
+        let query_c_str = unsafe { CStr::from_ptr(query) };
+        let query_str = match query_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{query_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let search_style_c_str = unsafe { CStr::from_ptr(search_style) };
+        let search_style_str = match search_style_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{search_style_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let search_style = match search_style_str {
+            "strict" => rockyou2024::search::SearchStyle::Strict,
+            "case-insensitive" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: false },
+            "case-insensitive-unicode" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: true },
+            "fuzzy" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: false,
+            },
+            "fuzzy-keyboard" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: true,
+            },
+            "wildcard" => rockyou2024::search::SearchStyle::Wildcard,
+            "exact" => rockyou2024::search::SearchStyle::Exact,
+            "phonetic" => rockyou2024::search::SearchStyle::Phonetic,
+            s if s.starts_with("edit-distance-") => {
+                match s["edit-distance-".len()..].parse::<usize>() {
+                    Ok(max_distance) => {
+                        rockyou2024::search::SearchStyle::edit_distance(max_distance)
+                    }
+                    Err(_) => {
+                        fail!(
+                            target: LOG_TARGET,
+                            "Could not parse the edit distance out of search style '{search_style_str}'.",
+                        );
+                        return std::ptr::null_mut();
+                    }
+                }
+            }
+            _ => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Unknown search style '{search_style_str}'.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        // Perform the search.
+        let index_collection = match rockyou2024::models::IndexCollection::<
+            { config::INDEX_LENGTH },
+            { config::INDEX_DEPTH },
+        >::open_validated(path.to_path_buf())
+        {
+            Ok(index_collection) => index_collection,
+            Err(err) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Failed to open the index at '{path:?}': {err}.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let found = index_collection.find_lines_containing(query_str, search_style, None);
+
+        let escaped: Vec<String> = found
+            .iter()
+            .map(|line| rockyou2024::string::hex_escape_unprintable(line))
+            .collect();
+
+        vec_str_to_mut_mut_c_char!(escaped)
+    })
+    .unwrap_or(std::ptr::null_mut())
 }
 
 #[no_mangle]
-/// Find the lines in the index collection that contain the given query.
+/// Find the lines in the index collection that contain the given query and whose
+/// email field matches `domain`, for a collection built with
+/// `--format combo --combo-keep-email`.
 ///
-/// This function is a wrapper around the [`IndexCollection::find_lines_containing`] method, which
-/// does not report errors. This function will log any errors and return a null pointer if an error
-/// occurs, including:
-///
-/// - The `dir` pointer is null.
-/// - The path given by `dir` is not a directory.
-/// - The `query` pointer is null.
-/// - The `search_style` pointer is null.
-/// - The `search_style` is not one of "strict", "case-insensitive", or "fuzzy".
+/// This is otherwise identical to [`find_lines_in_index_collection`]; see that
+/// function's docs for the meaning of `dir`, `query`, `search_style`, and the
+/// conditions under which this returns a null pointer. `domain` is additionally
+/// required to be non-null; a line whose field before `combo_delimiter` does not end
+/// in `@domain` is dropped, including every line from a plain (non-combo) index.
 ///
 /// For use in Go.
 ///
@@ -164,87 +649,964 @@ pub unsafe extern "C" fn as_search_string(
 /// This function is unsafe because it dereferences raw pointers; this is unavoidable if we have to
 /// pass an array of strings to Go.
 ///
-/// Go will be responsible for freeing the memory allocated; please ensure that
-/// `defer C.free(unsafe.Pointer(ptr))` is called for each string in the array.
-pub unsafe extern "C" fn find_lines_in_index_collection(
+/// Go will be responsible for freeing the memory allocated; call `free_string_array`
+/// on the result, not `C.free`.
+pub unsafe extern "C" fn find_lines_in_index_collection_with_domain_filter(
     dir: *const c_char,
     query: *const c_char,
     search_style: *const c_char,
+    combo_delimiter: c_char,
+    domain: *const c_char,
 ) -> *mut *mut c_char {
-    // Validate the input.
-    if dir.is_null() {
-        rockyou2024::warn!(
-            target: LOG_TARGET,
-            "Received a null pointer for `dir`.",
-        );
-        return std::ptr::null_mut();
-    }
+    catch_panic("find_lines_in_index_collection_with_domain_filter", || {
+        // Validate the input.
+        if dir.is_null() {
+            fail!(
+                target: LOG_TARGET,
+                "Received a null pointer for `dir`.",
+            );
+            return std::ptr::null_mut();
+        }
 
-    let dir_c_str = unsafe { CStr::from_ptr(dir) };
-    let dir_str = match dir_c_str.to_str() {
-        Ok(s) => s,
-        Err(_) => {
-            rockyou2024::warn!(
+        let dir_c_str = unsafe { CStr::from_ptr(dir) };
+        let dir_str = match dir_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{dir_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let path = std::path::Path::new(dir_str);
+        if !path.is_dir() {
+            fail!(
                 target: LOG_TARGET,
-                "Could not convert '{dir_c_str:?}' to a Rust string.",
+                "The path '{path:?}' is not a directory.",
             );
             return std::ptr::null_mut();
         }
-    };
 
-    let path = std::path::Path::new(dir_str);
-    if !path.is_dir() {
-        rockyou2024::warn!(
-            target: LOG_TARGET,
-            "The path '{path:?}' is not a directory.",
+        let query_c_str = unsafe { CStr::from_ptr(query) };
+        let query_str = match query_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{query_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let search_style_c_str = unsafe { CStr::from_ptr(search_style) };
+        let search_style_str = match search_style_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{search_style_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let search_style = match search_style_str {
+            "strict" => rockyou2024::search::SearchStyle::Strict,
+            "case-insensitive" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: false },
+            "case-insensitive-unicode" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: true },
+            "fuzzy" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: false,
+            },
+            "fuzzy-keyboard" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: true,
+            },
+            "wildcard" => rockyou2024::search::SearchStyle::Wildcard,
+            "exact" => rockyou2024::search::SearchStyle::Exact,
+            "phonetic" => rockyou2024::search::SearchStyle::Phonetic,
+            s if s.starts_with("edit-distance-") => {
+                match s["edit-distance-".len()..].parse::<usize>() {
+                    Ok(max_distance) => {
+                        rockyou2024::search::SearchStyle::edit_distance(max_distance)
+                    }
+                    Err(_) => {
+                        fail!(
+                            target: LOG_TARGET,
+                            "Could not parse the edit distance out of search style '{search_style_str}'.",
+                        );
+                        return std::ptr::null_mut();
+                    }
+                }
+            }
+            _ => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Unknown search style '{search_style_str}'.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        if domain.is_null() {
+            fail!(
+                target: LOG_TARGET,
+                "Received a null pointer for `domain`.",
+            );
+            return std::ptr::null_mut();
+        }
+
+        let domain_c_str = unsafe { CStr::from_ptr(domain) };
+        let domain_str = match domain_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{domain_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        // Perform the search.
+        let index_collection = match rockyou2024::models::IndexCollection::<
+            { config::INDEX_LENGTH },
+            { config::INDEX_DEPTH },
+        >::open_validated(path.to_path_buf())
+        {
+            Ok(index_collection) => index_collection,
+            Err(err) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Failed to open the index at '{path:?}': {err}.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let found = index_collection.find_lines_containing_with_domain_filter(
+            query_str,
+            search_style,
+            None,
+            combo_delimiter as u8 as char,
+            domain_str,
         );
-        return std::ptr::null_mut();
-    }
 
-    let query_c_str = unsafe { CStr::from_ptr(query) };
-    let query_str = match query_c_str.to_str() {
-        Ok(s) => s,
-        Err(_) => {
-            rockyou2024::warn!(
+        vec_str_to_mut_mut_c_char!(found)
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+/// The most results [`find_lines_in_index_collection_json`] will embed in a single
+/// response, so one broad query cannot serialise an unbounded amount of JSON into a
+/// single C string.
+const MAX_JSON_RESULTS: usize = 1000;
+
+#[no_mangle]
+/// Find the lines in the index collection that contain the given query, returning a
+/// single JSON document instead of a null-terminated array of strings.
+///
+/// This is otherwise identical to [`find_lines_in_index_collection`]; see that
+/// function's docs for the meaning of `dir`, `query`, and `search_style`, and the
+/// conditions under which this returns a null pointer.
+///
+/// The returned document has the shape:
+///
+/// ```json
+/// {"results": ["...", "..."], "total": 2, "returned": 2, "truncated": false}
+/// ```
+///
+/// `total` is the number of lines the search actually matched; `returned` is the
+/// number embedded in `results`, capped at [`MAX_JSON_RESULTS`]; `truncated` is
+/// `true` when `returned` is less than `total`.
+///
+/// For use in Go.
+///
+/// # Safety
+///
+/// This function is unsafe because it dereferences raw pointers.
+///
+/// Go will be responsible for freeing the memory allocated; call `free_string` on
+/// the result, not `C.free`.
+pub unsafe extern "C" fn find_lines_in_index_collection_json(
+    dir: *const c_char,
+    query: *const c_char,
+    search_style: *const c_char,
+) -> *mut c_char {
+    catch_panic("find_lines_in_index_collection_json", || {
+        // Validate the input.
+        if dir.is_null() {
+            fail!(
+                target: LOG_TARGET,
+                "Received a null pointer for `dir`.",
+            );
+            return std::ptr::null_mut();
+        }
+
+        let dir_c_str = unsafe { CStr::from_ptr(dir) };
+        let dir_str = match dir_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{dir_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let path = std::path::Path::new(dir_str);
+        if !path.is_dir() {
+            fail!(
+                target: LOG_TARGET,
+                "The path '{path:?}' is not a directory.",
+            );
+            return std::ptr::null_mut();
+        }
+
+        let query_c_str = unsafe { CStr::from_ptr(query) };
+        let query_str = match query_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{query_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let search_style_c_str = unsafe { CStr::from_ptr(search_style) };
+        let search_style_str = match search_style_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{search_style_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let search_style = match search_style_str {
+            "strict" => rockyou2024::search::SearchStyle::Strict,
+            "case-insensitive" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: false },
+            "case-insensitive-unicode" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: true },
+            "fuzzy" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: false,
+            },
+            "fuzzy-keyboard" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: true,
+            },
+            "wildcard" => rockyou2024::search::SearchStyle::Wildcard,
+            "exact" => rockyou2024::search::SearchStyle::Exact,
+            "phonetic" => rockyou2024::search::SearchStyle::Phonetic,
+            s if s.starts_with("edit-distance-") => {
+                match s["edit-distance-".len()..].parse::<usize>() {
+                    Ok(max_distance) => {
+                        rockyou2024::search::SearchStyle::edit_distance(max_distance)
+                    }
+                    Err(_) => {
+                        fail!(
+                            target: LOG_TARGET,
+                            "Could not parse the edit distance out of search style '{search_style_str}'.",
+                        );
+                        return std::ptr::null_mut();
+                    }
+                }
+            }
+            _ => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Unknown search style '{search_style_str}'.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        // Perform the search.
+        let index_collection = match rockyou2024::models::IndexCollection::<
+            { config::INDEX_LENGTH },
+            { config::INDEX_DEPTH },
+        >::open_validated(path.to_path_buf())
+        {
+            Ok(index_collection) => index_collection,
+            Err(err) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Failed to open the index at '{path:?}': {err}.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let found = index_collection.find_lines_containing(query_str, search_style, None);
+        let total = found.len();
+        let results: Vec<&str> = found.iter().take(MAX_JSON_RESULTS).map(String::as_str).collect();
+        let returned = results.len();
+
+        let document = serde_json::json!({
+            "results": results,
+            "total": total,
+            "returned": returned,
+            "truncated": returned < total,
+        });
+
+        match CString::new(document.to_string()) {
+            Ok(c_str) => c_str.into_raw(),
+            Err(err) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert final JSON document to a `CString`: {err}",
+                    err=err,
+                );
+                std::ptr::null_mut()
+            }
+        }
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+#[no_mangle]
+/// Find the lines in the index collection that contain the given query, aborting the
+/// search once `timeout_ms` milliseconds have elapsed.
+///
+/// This is otherwise identical to [`find_lines_in_index_collection_json`]; see that
+/// function's docs for the meaning of `dir`, `query`, `search_style`, and the returned
+/// document's `results`/`total`/`returned` fields, and the conditions under which this
+/// returns a null pointer. Pass `0` for `timeout_ms` to search without a deadline.
+///
+/// `truncated` is `true` when either the timeout elapsed before every index file had
+/// been scanned, or `returned` is less than `total`; a caller that needs to tell the
+/// two apart should compare `returned` against `total` itself.
+///
+/// For use in Go.
+///
+/// # Safety
+///
+/// This function is unsafe because it dereferences raw pointers.
+///
+/// Go will be responsible for freeing the memory allocated; call `free_string` on
+/// the result, not `C.free`.
+pub unsafe extern "C" fn find_lines_in_index_collection_json_with_timeout(
+    dir: *const c_char,
+    query: *const c_char,
+    search_style: *const c_char,
+    timeout_ms: u64,
+) -> *mut c_char {
+    catch_panic("find_lines_in_index_collection_json_with_timeout", || {
+        // Validate the input.
+        if dir.is_null() {
+            fail!(
+                target: LOG_TARGET,
+                "Received a null pointer for `dir`.",
+            );
+            return std::ptr::null_mut();
+        }
+
+        let dir_c_str = unsafe { CStr::from_ptr(dir) };
+        let dir_str = match dir_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{dir_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let path = std::path::Path::new(dir_str);
+        if !path.is_dir() {
+            fail!(
+                target: LOG_TARGET,
+                "The path '{path:?}' is not a directory.",
+            );
+            return std::ptr::null_mut();
+        }
+
+        let query_c_str = unsafe { CStr::from_ptr(query) };
+        let query_str = match query_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{query_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let search_style_c_str = unsafe { CStr::from_ptr(search_style) };
+        let search_style_str = match search_style_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{search_style_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let search_style = match search_style_str {
+            "strict" => rockyou2024::search::SearchStyle::Strict,
+            "case-insensitive" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: false },
+            "case-insensitive-unicode" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: true },
+            "fuzzy" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: false,
+            },
+            "fuzzy-keyboard" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: true,
+            },
+            "wildcard" => rockyou2024::search::SearchStyle::Wildcard,
+            "exact" => rockyou2024::search::SearchStyle::Exact,
+            "phonetic" => rockyou2024::search::SearchStyle::Phonetic,
+            s if s.starts_with("edit-distance-") => {
+                match s["edit-distance-".len()..].parse::<usize>() {
+                    Ok(max_distance) => {
+                        rockyou2024::search::SearchStyle::edit_distance(max_distance)
+                    }
+                    Err(_) => {
+                        fail!(
+                            target: LOG_TARGET,
+                            "Could not parse the edit distance out of search style '{search_style_str}'.",
+                        );
+                        return std::ptr::null_mut();
+                    }
+                }
+            }
+            _ => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Unknown search style '{search_style_str}'.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let timeout = (timeout_ms > 0).then(|| std::time::Duration::from_millis(timeout_ms));
+
+        // Perform the search.
+        let index_collection = match rockyou2024::models::IndexCollection::<
+            { config::INDEX_LENGTH },
+            { config::INDEX_DEPTH },
+        >::open_validated(path.to_path_buf())
+        {
+            Ok(index_collection) => index_collection,
+            Err(err) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Failed to open the index at '{path:?}': {err}.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let found = index_collection.find_lines_containing_with_timeout(query_str, search_style, timeout);
+        let total = found.lines.len();
+        let results: Vec<&str> =
+            found.lines.iter().take(MAX_JSON_RESULTS).map(String::as_str).collect();
+        let returned = results.len();
+
+        let document = serde_json::json!({
+            "results": results,
+            "total": total,
+            "returned": returned,
+            "truncated": found.truncated || returned < total,
+        });
+
+        match CString::new(document.to_string()) {
+            Ok(c_str) => c_str.into_raw(),
+            Err(err) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert final JSON document to a `CString`: {err}",
+                    err=err,
+                );
+                std::ptr::null_mut()
+            }
+        }
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+/// A callback invoked once per line by [`find_lines_in_index_collection_stream`].
+///
+/// `line` is a NUL-terminated string owned by the callee; it is only valid for the
+/// duration of the call, and must not be retained or freed by the callback. `user_data`
+/// is the pointer of the same name passed in to `find_lines_in_index_collection_stream`,
+/// round-tripped unmodified so the callback can reach caller-owned state.
+///
+/// Return `true` to keep streaming further lines, or `false` to stop early.
+pub type LineCallback = unsafe extern "C" fn(line: *const c_char, user_data: *mut c_void) -> bool;
+
+#[no_mangle]
+/// Find the lines in the index collection that contain the given query, invoking
+/// `callback` once per line as it is found rather than materialising the full result
+/// set up front.
+///
+/// This is built on [`IndexCollection::find_lines_containing_iter`], so unlike
+/// [`find_lines_in_index_collection`] it does not deduplicate matches across index
+/// files and does not participate in the LRU cache; use `find_lines_in_index_collection`
+/// when either matters. `callback` returning `false` stops the search early, without
+/// scanning any remaining index files.
+///
+/// Returns the number of lines passed to `callback`, or `-1` if:
+///
+/// - The `dir` pointer is null.
+/// - The path given by `dir` is not a directory.
+/// - The index at `dir` was built with different `LENGTH`/`DEPTH` parameters than
+///   this library was compiled with, which would otherwise silently return no
+///   results for every search through this handle.
+/// - The `query` pointer is null.
+/// - The `search_style` pointer is null.
+/// - The `search_style` is not one of "strict", "case-insensitive", "fuzzy",
+///   "fuzzy-keyboard", "wildcard", "exact", or "edit-distance-N" (where `N` is the
+///   maximum edit distance).
+///
+/// For use in Go.
+///
+/// # Safety
+///
+/// This function is unsafe because it dereferences raw pointers and invokes a
+/// caller-supplied function pointer; `callback` must tolerate being called with a
+/// valid, NUL-terminated `line` and the `user_data` passed in here, for as many times
+/// as there are matching lines.
+pub unsafe extern "C" fn find_lines_in_index_collection_stream(
+    dir: *const c_char,
+    query: *const c_char,
+    search_style: *const c_char,
+    callback: LineCallback,
+    user_data: *mut c_void,
+) -> i64 {
+    catch_panic("find_lines_in_index_collection_stream", || {
+        // Validate the input.
+        if dir.is_null() {
+            fail!(
+                target: LOG_TARGET,
+                "Received a null pointer for `dir`.",
+            );
+            return -1;
+        }
+
+        let dir_c_str = unsafe { CStr::from_ptr(dir) };
+        let dir_str = match dir_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{dir_c_str:?}' to a Rust string.",
+                );
+                return -1;
+            }
+        };
+
+        let path = std::path::Path::new(dir_str);
+        if !path.is_dir() {
+            fail!(
+                target: LOG_TARGET,
+                "The path '{path:?}' is not a directory.",
+            );
+            return -1;
+        }
+
+        let query_c_str = unsafe { CStr::from_ptr(query) };
+        let query_str = match query_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{query_c_str:?}' to a Rust string.",
+                );
+                return -1;
+            }
+        };
+
+        let search_style_c_str = unsafe { CStr::from_ptr(search_style) };
+        let search_style_str = match search_style_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{search_style_c_str:?}' to a Rust string.",
+                );
+                return -1;
+            }
+        };
+
+        let search_style = match search_style_str {
+            "strict" => rockyou2024::search::SearchStyle::Strict,
+            "case-insensitive" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: false },
+            "case-insensitive-unicode" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: true },
+            "fuzzy" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: false,
+            },
+            "fuzzy-keyboard" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: true,
+            },
+            "wildcard" => rockyou2024::search::SearchStyle::Wildcard,
+            "exact" => rockyou2024::search::SearchStyle::Exact,
+            "phonetic" => rockyou2024::search::SearchStyle::Phonetic,
+            s if s.starts_with("edit-distance-") => {
+                match s["edit-distance-".len()..].parse::<usize>() {
+                    Ok(max_distance) => {
+                        rockyou2024::search::SearchStyle::edit_distance(max_distance)
+                    }
+                    Err(_) => {
+                        fail!(
+                            target: LOG_TARGET,
+                            "Could not parse the edit distance out of search style '{search_style_str}'.",
+                        );
+                        return -1;
+                    }
+                }
+            }
+            _ => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Unknown search style '{search_style_str}'.",
+                );
+                return -1;
+            }
+        };
+
+        // Perform the search.
+        let index_collection = match rockyou2024::models::IndexCollection::<
+            { config::INDEX_LENGTH },
+            { config::INDEX_DEPTH },
+        >::open_validated(path.to_path_buf())
+        {
+            Ok(index_collection) => index_collection,
+            Err(err) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Failed to open the index at '{path:?}': {err}.",
+                );
+                return -1;
+            }
+        };
+
+        let mut count = 0i64;
+        for line in index_collection.find_lines_containing_iter(query_str, search_style) {
+            let c_line = match CString::new(line) {
+                Ok(c_line) => c_line,
+                Err(err) => {
+                    fail!(
+                        target: LOG_TARGET,
+                        "Skipping a line that could not be converted to a `CString`: {err}",
+                        err = err,
+                    );
+                    continue;
+                }
+            };
+
+            count += 1;
+            if !unsafe { callback(c_line.as_ptr(), user_data) } {
+                break;
+            }
+        }
+
+        count
+    })
+    .unwrap_or(-1)
+}
+
+/// An opaque, long-lived handle to an [`IndexCollection`].
+///
+/// `find_lines_in_index_collection` re-creates the [`IndexCollection`] (and, with it,
+/// its LRU cache) on every call, which is wasteful when Go is issuing many searches
+/// against the same index directory. Open a handle once with `index_collection_open`,
+/// reuse it for as many `index_collection_search` calls as needed, and dispose of it
+/// with `index_collection_close`.
+pub struct IndexCollectionHandle(
+    rockyou2024::models::IndexCollection<{ config::INDEX_LENGTH }, { config::INDEX_DEPTH }>,
+);
+
+#[no_mangle]
+/// Open an [`IndexCollection`] at `dir` and return an opaque handle to it.
+///
+/// The returned handle keeps the collection (and its LRU cache) alive across calls;
+/// it must be released with `index_collection_close` once it is no longer needed.
+///
+/// Returns a null pointer if:
+///
+/// - The `dir` pointer is null.
+/// - The path given by `dir` is not a directory.
+/// - The index at `dir` was built with different `LENGTH`/`DEPTH` parameters than
+///   this library was compiled with, which would otherwise silently return no
+///   results for every search through this handle.
+///
+/// For use in Go.
+///
+/// # Safety
+///
+/// This function is unsafe because it dereferences a raw pointer.
+pub unsafe extern "C" fn index_collection_open(dir: *const c_char) -> *mut IndexCollectionHandle {
+    catch_panic("index_collection_open", || {
+        if dir.is_null() {
+            fail!(
                 target: LOG_TARGET,
-                "Could not convert '{query_c_str:?}' to a Rust string.",
+                "Received a null pointer for `dir`.",
             );
             return std::ptr::null_mut();
         }
-    };
 
-    let search_style_c_str = unsafe { CStr::from_ptr(search_style) };
-    let search_style_str = match search_style_c_str.to_str() {
-        Ok(s) => s,
-        Err(_) => {
-            rockyou2024::warn!(
+        let dir_c_str = unsafe { CStr::from_ptr(dir) };
+        let dir_str = match dir_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{dir_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let path = std::path::Path::new(dir_str);
+        if !path.is_dir() {
+            fail!(
                 target: LOG_TARGET,
-                "Could not convert '{search_style_c_str:?}' to a Rust string.",
+                "The path '{path:?}' is not a directory.",
             );
             return std::ptr::null_mut();
         }
-    };
 
-    let search_style = match search_style_str {
-        "strict" => rockyou2024::search::SearchStyle::Strict,
-        "case-insensitive" => rockyou2024::search::SearchStyle::CaseInsensitive,
-        "fuzzy" => rockyou2024::search::SearchStyle::Fuzzy,
-        _ => {
-            rockyou2024::warn!(
+        let collection = match rockyou2024::models::IndexCollection::<
+            { config::INDEX_LENGTH },
+            { config::INDEX_DEPTH },
+        >::open_validated(path.to_path_buf())
+        {
+            Ok(collection) => collection,
+            Err(err) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Failed to open the index at '{path:?}': {err}.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        Box::into_raw(Box::new(IndexCollectionHandle(collection)))
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+#[no_mangle]
+/// Find the lines in a handle opened with `index_collection_open` that contain the given
+/// query.
+///
+/// This is the handle-based equivalent of `find_lines_in_index_collection`; the collection
+/// backing `handle` is reused, so its LRU cache stays warm across calls.
+///
+/// Returns a null pointer if:
+///
+/// - The `handle` pointer is null.
+/// - The `query` pointer is null.
+/// - The `search_style` pointer is null.
+/// - The `search_style` is not one of "strict", "case-insensitive", "fuzzy",
+///   "fuzzy-keyboard", "wildcard", "exact", or "edit-distance-N" (where `N` is the
+///   maximum edit distance).
+///
+/// For use in Go.
+///
+/// # Safety
+///
+/// This function is unsafe because it dereferences raw pointers; `handle` must have been
+/// returned by `index_collection_open` and must not have been passed to
+/// `index_collection_close` yet.
+///
+/// Go will be responsible for freeing the memory allocated; call `free_string_array`
+/// on the result, not `C.free`.
+pub unsafe extern "C" fn index_collection_search(
+    handle: *const IndexCollectionHandle,
+    query: *const c_char,
+    search_style: *const c_char,
+) -> *mut *mut c_char {
+    catch_panic("index_collection_search", || {
+        if handle.is_null() {
+            fail!(
                 target: LOG_TARGET,
-                "Unknown search style '{search_style_str}'.",
+                "Received a null pointer for `handle`.",
             );
             return std::ptr::null_mut();
         }
-    };
 
-    // Perform the search.
-    let index_collection = rockyou2024::models::IndexCollection::<
-        { config::INDEX_LENGTH },
-        { config::INDEX_DEPTH },
-    >::new(path.to_path_buf());
+        let query_c_str = unsafe { CStr::from_ptr(query) };
+        let query_str = match query_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{query_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
 
-    let found = index_collection.find_lines_containing(query_str, search_style);
+        let search_style_c_str = unsafe { CStr::from_ptr(search_style) };
+        let search_style_str = match search_style_c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Could not convert '{search_style_c_str:?}' to a Rust string.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
 
-    vec_str_to_mut_mut_c_char!(<rockyou2024::models::IndexCollectionResult as Clone>::clone(&found))
+        let search_style = match search_style_str {
+            "strict" => rockyou2024::search::SearchStyle::Strict,
+            "case-insensitive" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: false },
+            "case-insensitive-unicode" => rockyou2024::search::SearchStyle::CaseInsensitive { unicode: true },
+            "fuzzy" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: false,
+            },
+            "fuzzy-keyboard" => rockyou2024::search::SearchStyle::Fuzzy {
+                keyboard_adjacent: true,
+            },
+            "wildcard" => rockyou2024::search::SearchStyle::Wildcard,
+            "exact" => rockyou2024::search::SearchStyle::Exact,
+            "phonetic" => rockyou2024::search::SearchStyle::Phonetic,
+            s if s.starts_with("edit-distance-") => {
+                match s["edit-distance-".len()..].parse::<usize>() {
+                    Ok(max_distance) => {
+                        rockyou2024::search::SearchStyle::edit_distance(max_distance)
+                    }
+                    Err(_) => {
+                        fail!(
+                            target: LOG_TARGET,
+                            "Could not parse the edit distance out of search style '{search_style_str}'.",
+                        );
+                        return std::ptr::null_mut();
+                    }
+                }
+            }
+            _ => {
+                fail!(
+                    target: LOG_TARGET,
+                    "Unknown search style '{search_style_str}'.",
+                );
+                return std::ptr::null_mut();
+            }
+        };
+
+        let handle = unsafe { &*handle };
+        let found = handle.0.find_lines_containing(query_str, search_style, None);
+
+        vec_str_to_mut_mut_c_char!(<rockyou2024::models::IndexCollectionResult as Clone>::clone(
+            &found
+        ))
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+#[no_mangle]
+/// Close a handle opened with `index_collection_open`, freeing the underlying
+/// [`IndexCollection`].
+///
+/// Does nothing if `handle` is null.
+///
+/// For use in Go.
+///
+/// # Safety
+///
+/// This function is unsafe because it takes ownership of a raw pointer; `handle` must
+/// have been returned by `index_collection_open` and must not be used again after this
+/// call, including by another call to `index_collection_close`.
+pub unsafe extern "C" fn index_collection_close(handle: *mut IndexCollectionHandle) {
+    catch_panic("index_collection_close", || {
+        if handle.is_null() {
+            return;
+        }
+
+        drop(unsafe { Box::from_raw(handle) });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Call [`as_search_string`] with `query`/`search_style`, returning its result as
+    /// an owned [`String`] (or `None` for a null return), and freeing the C string
+    /// `as_search_string` allocated either way.
+    ///
+    /// This exercises the same inline search-style parsing (accepted style names, and
+    /// the `edit-distance-N` clamp) that every other `extern "C"` search function in
+    /// this crate duplicates.
+    fn call_as_search_string(query: &str, search_style: &str) -> Option<String> {
+        let query = CString::new(query).unwrap();
+        let search_style = CString::new(search_style).unwrap();
+
+        let result = unsafe { as_search_string(query.as_ptr(), search_style.as_ptr()) };
+        if result.is_null() {
+            return None;
+        }
+
+        let owned = unsafe { CStr::from_ptr(result) }.to_str().unwrap().to_owned();
+        unsafe { free_string(result) };
+        Some(owned)
+    }
+
+    /// Take this thread's last error message, for asserting on the reason a call
+    /// above returned null.
+    fn take_last_error() -> String {
+        let ptr = unsafe { rockyou_last_error_message() };
+        assert!(!ptr.is_null(), "Expected a last error message to have been set.");
+
+        let message = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_owned();
+        unsafe { free_string(ptr) };
+        message
+    }
+
+    #[test]
+    fn as_search_string_accepts_every_documented_style() {
+        for style in [
+            "strict",
+            "case-insensitive",
+            "case-insensitive-unicode",
+            "fuzzy",
+            "fuzzy-keyboard",
+            "wildcard",
+            "exact",
+            "phonetic",
+            "edit-distance-1",
+        ] {
+            assert!(
+                call_as_search_string("password", style).is_some(),
+                "Failed to parse '{style}'."
+            );
+        }
+    }
+
+    #[test]
+    fn as_search_string_lowercases_for_case_insensitive() {
+        let transformed = call_as_search_string("PassWord", "case-insensitive")
+            .expect("Failed to transform the query.");
+        assert_eq!(transformed, "password");
+    }
+
+    #[test]
+    fn as_search_string_rejects_an_unknown_style() {
+        assert!(call_as_search_string("password", "not-a-real-style").is_none());
+        assert!(take_last_error().contains("Unknown search style"));
+    }
+
+    #[test]
+    fn as_search_string_rejects_a_non_numeric_edit_distance() {
+        assert!(call_as_search_string("password", "edit-distance-abc").is_none());
+        assert!(take_last_error().contains("Could not parse the edit distance"));
+    }
+
+    #[test]
+    fn as_search_string_accepts_an_edit_distance_far_beyond_the_clamp() {
+        // `SearchStyle::edit_distance` (which this parsing routes every `edit-distance-N`
+        // style through - see `rockyou2024::search::MAX_EDIT_DISTANCE`) clamps the
+        // distance rather than rejecting an oversized one, so this should still succeed.
+        assert!(call_as_search_string("password", "edit-distance-9999").is_some());
+    }
 }
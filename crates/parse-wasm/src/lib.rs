@@ -0,0 +1,115 @@
+//! WASM bindings for client-side query normalisation.
+//!
+use wasm_bindgen::prelude::*;
+
+use rockyou2024::config;
+use rockyou2024::models::IndexOf;
+
+fn parse_search_style(search_style: &str) -> Result<rockyou2024::search::SearchStyle, JsError> {
+    match search_style {
+        "strict" => Ok(rockyou2024::search::SearchStyle::Strict),
+        "case-insensitive" => Ok(rockyou2024::search::SearchStyle::CaseInsensitive { unicode: false }),
+        "case-insensitive-unicode" => Ok(rockyou2024::search::SearchStyle::CaseInsensitive { unicode: true }),
+        "fuzzy" => Ok(rockyou2024::search::SearchStyle::Fuzzy {
+            keyboard_adjacent: false,
+        }),
+        "fuzzy-keyboard" => Ok(rockyou2024::search::SearchStyle::Fuzzy {
+            keyboard_adjacent: true,
+        }),
+        "wildcard" => Ok(rockyou2024::search::SearchStyle::Wildcard),
+        "exact" => Ok(rockyou2024::search::SearchStyle::Exact),
+        "phonetic" => Ok(rockyou2024::search::SearchStyle::Phonetic),
+        s if s.starts_with("edit-distance-") => {
+            s["edit-distance-".len()..]
+                .parse::<usize>()
+                .map(rockyou2024::search::SearchStyle::edit_distance)
+                .map_err(|_| {
+                    JsError::new(&format!(
+                        "Could not parse the edit distance out of search style '{search_style}'."
+                    ))
+                })
+        }
+        _ => Err(JsError::new(&format!(
+            "Unknown search style '{search_style}'."
+        ))),
+    }
+}
+
+/// Clean the query using the specified search style.
+///
+/// `search_style` is one of "strict", "case-insensitive", "case-insensitive-unicode",
+/// "fuzzy", "fuzzy-keyboard", "wildcard", "exact", or "edit-distance-N" (where `N` is
+/// the maximum edit distance).
+#[wasm_bindgen]
+pub fn as_search_string(query: &str, search_style: &str) -> Result<String, JsError> {
+    let search_style = parse_search_style(search_style)?;
+
+    Ok(search_style
+        .transform_query()(&[query])
+        .pop()
+        .expect("The transformed query should always have at least one element."))
+}
+
+/// Get the indices of the given input.
+///
+/// The length and depth of the index always default to the values in the
+/// configuration, since WASM bindings do not support generics either; this matches
+/// the indices `crates/parse-ffi`'s `indices_of` produces.
+#[wasm_bindgen]
+pub fn indices_of(input: &str) -> Vec<String> {
+    IndexOf::<{ config::INDEX_LENGTH }, { config::INDEX_DEPTH }>::from(input.as_bytes()).collect()
+}
+
+// `JsError::new` calls into a `__wbindgen_error_new` import that only exists once
+// this crate is actually linked into a wasm module, so it panics if exercised
+// outside a real wasm-bindgen host; the error branches of `parse_search_style`
+// (and, transitively, `as_search_string`) cannot be unit tested from a native
+// `cargo test` run. The success paths below don't touch that import.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_search_style_accepts_every_documented_style() {
+        for style in [
+            "strict",
+            "case-insensitive",
+            "case-insensitive-unicode",
+            "fuzzy",
+            "fuzzy-keyboard",
+            "wildcard",
+            "exact",
+            "phonetic",
+        ] {
+            assert!(parse_search_style(style).is_ok(), "Failed to parse '{style}'.");
+        }
+    }
+
+    #[test]
+    fn parse_search_style_clamps_edit_distance_to_a_small_bounded_maximum() {
+        let style = parse_search_style("edit-distance-9999").expect("Failed to parse.");
+        assert_eq!(
+            style,
+            rockyou2024::search::SearchStyle::EditDistance {
+                max_distance: rockyou2024::search::MAX_EDIT_DISTANCE,
+            },
+            "parse_search_style must route a caller-supplied distance through \
+             SearchStyle::edit_distance so it is clamped, not build EditDistance directly."
+        );
+    }
+
+    #[test]
+    fn as_search_string_transforms_the_query_for_a_valid_style() {
+        let transformed =
+            as_search_string("PassWord", "case-insensitive").expect("Failed to transform the query.");
+        assert_eq!(transformed, "password");
+    }
+
+    #[test]
+    fn indices_of_matches_the_underlying_index_of_iterator() {
+        let expected: Vec<String> =
+            IndexOf::<{ config::INDEX_LENGTH }, { config::INDEX_DEPTH }>::from(b"password".as_slice())
+                .collect();
+        assert_eq!(indices_of("password"), expected);
+    }
+}
@@ -0,0 +1,78 @@
+//! A multi-file [`io::Read`] that presents several sources as one logical stream.
+
+use std::{collections::VecDeque, fs, io, path};
+
+/// Concatenates several [`io::Read`]s into a single stream, inserting `separator`
+/// between two sources whenever the first didn't already end on one, so a caller
+/// splitting the stream on `separator` never sees a line straddling a file boundary.
+///
+/// Implements [`io::Read`] itself, so it plugs into [`crate::FixedMemoryReader`] the
+/// same way [`crate::ThrottledReader`] or a decompressing reader would.
+pub struct ChainedReader<R: io::Read> {
+    readers: VecDeque<R>,
+    separator: u8,
+    /// Whether the byte most recently emitted from the previous source was already
+    /// `separator`, so a synthetic one is only inserted when it's actually needed.
+    last_byte_was_separator: bool,
+}
+
+impl<R: io::Read> ChainedReader<R> {
+    /// Chain `readers` together in order, treating `separator` as the boundary a
+    /// downstream chunker splits lines on.
+    pub fn new(readers: impl IntoIterator<Item = R>, separator: u8) -> Self {
+        Self {
+            readers: readers.into_iter().collect(),
+            separator,
+            // Nothing has been read yet, so there is nothing to terminate; treat the
+            // very first source as if it followed a separator, to avoid inserting a
+            // spurious one before it.
+            last_byte_was_separator: true,
+        }
+    }
+}
+
+impl<R: io::Read> io::Read for ChainedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            let Some(reader) = self.readers.front_mut() else {
+                return Ok(0);
+            };
+
+            let read = reader.read(buf)?;
+
+            if read == 0 {
+                self.readers.pop_front();
+
+                if !self.last_byte_was_separator && !self.readers.is_empty() {
+                    buf[0] = self.separator;
+                    self.last_byte_was_separator = true;
+                    return Ok(1);
+                }
+
+                continue;
+            }
+
+            self.last_byte_was_separator = buf[read - 1] == self.separator;
+            return Ok(read);
+        }
+    }
+}
+
+impl ChainedReader<fs::File> {
+    /// Open every path in `paths`, in order, and chain them together.
+    pub fn from_paths(
+        paths: impl IntoIterator<Item = impl AsRef<path::Path>>,
+        separator: u8,
+    ) -> io::Result<Self> {
+        let readers = paths
+            .into_iter()
+            .map(|path| fs::File::open(path))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        Ok(Self::new(readers, separator))
+    }
+}
@@ -0,0 +1,78 @@
+//! Per-chunk checksumming [`io::Read`] wrapper.
+
+use std::{hash::Hasher, io};
+
+/// A running, updatable checksum, fed one chunk at a time as a file streams through;
+/// see [`ChecksummingReader`].
+pub trait Checksum: Default {
+    /// Fold `bytes` into the running checksum.
+    fn update(&mut self, bytes: &[u8]);
+
+    /// The digest of every byte seen so far, as a lowercase hex string.
+    fn hex_digest(&self) -> String;
+}
+
+/// CRC-32, for a cheap sanity check against corruption rather than a cryptographic
+/// guarantee.
+#[derive(Default, Clone)]
+pub struct Crc32(crc32fast::Hasher);
+
+impl Checksum for Crc32 {
+    fn update(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn hex_digest(&self) -> String {
+        format!("{:08x}", self.0.clone().finalize())
+    }
+}
+
+/// 64-bit xxHash, faster than CRC-32 on large files at the cost of a bigger digest.
+#[derive(Default)]
+pub struct XxHash64(twox_hash::XxHash64);
+
+impl Checksum for XxHash64 {
+    fn update(&mut self, bytes: &[u8]) {
+        self.0.write(bytes);
+    }
+
+    fn hex_digest(&self) -> String {
+        format!("{:016x}", self.0.finish())
+    }
+}
+
+/// Wraps any [`io::Read`] with a running checksum `C` (see [`Crc32`], [`XxHash64`])
+/// updated as each chunk is read, so a caller streaming a file through for some other
+/// purpose (e.g. indexing) gets a whole-file digest for free at the end, instead of
+/// having to make a second pass over the file just to compute one.
+///
+/// Implements [`io::Read`] itself, so it plugs into [`crate::FixedMemoryReader`] the
+/// same way [`crate::ThrottledReader`] or a decompressing reader would.
+pub struct ChecksummingReader<R: io::Read, C: Checksum> {
+    inner: R,
+    checksum: C,
+}
+
+impl<R: io::Read, C: Checksum> ChecksummingReader<R, C> {
+    /// Wrap `inner`, accumulating a `C` checksum as it is read.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            checksum: C::default(),
+        }
+    }
+
+    /// The digest of every byte read through this wrapper so far; call this once
+    /// reading is finished for the whole-file digest.
+    pub fn hex_digest(&self) -> String {
+        self.checksum.hex_digest()
+    }
+}
+
+impl<R: io::Read, C: Checksum> io::Read for ChecksummingReader<R, C> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.checksum.update(&buf[..read]);
+        Ok(read)
+    }
+}
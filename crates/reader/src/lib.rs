@@ -1,8 +1,39 @@
 //! The reader coroutine.
 
 pub mod config;
+mod chain;
+#[cfg(feature = "checksum")]
+mod checksum;
+mod mmap;
+mod ordered_parallel;
+mod range;
+mod read_ahead;
 mod sync;
+mod throttle;
+
+#[cfg(feature = "tokio")]
+mod nonblocking;
+
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+mod uring;
 
 pub mod utils;
 
-pub use sync::{ChunkSize, FixedMemoryReader, IterFixedMemoryReader};
+pub use chain::ChainedReader;
+#[cfg(feature = "checksum")]
+pub use checksum::{Checksum, ChecksummingReader, Crc32, XxHash64};
+pub use mmap::{IterMmapChunks, IterMmapReader, IterMmapReaderRuntime, MmapReader};
+pub use ordered_parallel::{into_ordered_parallel, OrderedParallelIter};
+pub use range::RangeReader;
+pub use read_ahead::ReadAheadReader;
+pub use sync::{
+    ChunkSize, FixedMemoryReader, IntoIterFixedMemoryReader, IntoIterFixedMemoryReaderRuntime,
+    IterFixedMemoryReader, IterFixedMemoryReaderRuntime,
+};
+pub use throttle::ThrottledReader;
+
+#[cfg(feature = "tokio")]
+pub use nonblocking::AsyncFixedMemoryReader;
+
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+pub use uring::UringFile;
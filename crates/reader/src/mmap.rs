@@ -0,0 +1,178 @@
+//! Memory-mapped implementation of the reader.
+
+use crate::{config, sync::ChunkSize};
+
+use std::{fs, io, path};
+
+/// Memory-mapped file reader, chunking the file without copying its bytes into a
+/// fresh buffer for every chunk the way [`crate::FixedMemoryReader`] does.
+///
+/// The whole file is mapped up front via [`memmap::Mmap`], so this suits a file that
+/// already fits comfortably in the OS's page cache; every chunk yielded by
+/// [`Self::with_chunks`]/[`Self::iter`]/[`Self::iter_with_separator`] is a borrowed
+/// slice into that mapping rather than an owned, freshly-allocated buffer.
+pub struct MmapReader {
+    mmap: memmap::Mmap,
+    chunk_size: usize,
+}
+
+impl MmapReader {
+    /// Memory-map the file at `path`, with the default chunk size (see
+    /// [`config::CHUNK_SIZE`]).
+    pub fn from_path(path: impl AsRef<path::Path>) -> io::Result<Self> {
+        Self::from_file(fs::File::open(path)?)
+    }
+
+    /// Memory-map an already-open [`fs::File`], with the default chunk size.
+    pub fn from_file(file: fs::File) -> io::Result<Self> {
+        // Safe as long as `file` is not concurrently truncated by another process
+        // while it is mapped; the same caveat as every other `memmap` user.
+        let mmap = unsafe { memmap::Mmap::map(&file)? };
+
+        Ok(Self { mmap, chunk_size: config::CHUNK_SIZE })
+    }
+
+    /// Set the chunk size used by [`Self::with_chunks`]/[`Self::iter`]/
+    /// [`Self::iter_with_separator`].
+    pub fn with_chunk_size(mut self, chunk_size: impl Into<ChunkSize>) -> Self {
+        self.chunk_size = chunk_size.into().into();
+        self
+    }
+
+    /// Size of the memory-mapped file, in bytes.
+    pub fn size(&self) -> usize {
+        self.mmap.len()
+    }
+
+    /// Iterate over fixed-size chunks of the file, each up to [`Self::chunk_size`]
+    /// bytes wide - without regard to where a separator falls, so a chunk may split
+    /// a line in half. See [`Self::iter`]/[`Self::iter_with_separator`] for a
+    /// separator-aware alternative.
+    pub fn with_chunks(&self) -> IterMmapChunks<'_> {
+        IterMmapChunks { data: &self.mmap, chunk_size: self.chunk_size, pos: 0 }
+    }
+
+    /// Iterate over the chunks of the memory-mapped file, extending each one to the
+    /// next occurrence of `SEP` so a chunk boundary never falls in the middle of a
+    /// record; like [`crate::FixedMemoryReader::iter`].
+    pub fn iter<const SEP: u8>(&self) -> IterMmapReader<'_, SEP> {
+        IterMmapReader { data: &self.mmap, chunk_size: self.chunk_size, pos: 0 }
+    }
+
+    /// Iterate over the chunks like [`Self::iter`], but with the separator chosen at
+    /// runtime instead of compiled in as a const generic; like
+    /// [`crate::FixedMemoryReader::iter_with_separator`].
+    pub fn iter_with_separator(&self, separator: u8) -> IterMmapReaderRuntime<'_> {
+        IterMmapReaderRuntime { data: &self.mmap, chunk_size: self.chunk_size, pos: 0, separator }
+    }
+}
+
+#[cfg(feature = "unix")]
+impl MmapReader {
+    /// Hint to the kernel that this mapping is about to be read sequentially from
+    /// start to end, and that it should be paged in ahead of time; see `madvise(2)`'s
+    /// `MADV_SEQUENTIAL` and `MADV_WILLNEED`. Purely a throughput hint for a large,
+    /// cold-cache file - the mapping remains fully usable even if the kernel rejects
+    /// it, which is why this is a separate opt-in call rather than something
+    /// [`Self::from_file`] applies unconditionally.
+    pub fn advise_sequential(&self) -> io::Result<()> {
+        madvise(self.mmap.as_ptr(), self.mmap.len(), libc::MADV_SEQUENTIAL)?;
+        madvise(self.mmap.as_ptr(), self.mmap.len(), libc::MADV_WILLNEED)
+    }
+}
+
+#[cfg(feature = "unix")]
+fn madvise(ptr: *const u8, len: usize, advice: libc::c_int) -> io::Result<()> {
+    let result = unsafe { libc::madvise(ptr as *mut libc::c_void, len, advice) };
+
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Advance `pos` past the next chunk of `data`, extending it past `chunk_size` up to
+/// the next occurrence of `separator` (inclusive) so the chunk never ends mid-record;
+/// returns `None` once `pos` has reached the end of `data`.
+fn next_separated_chunk<'m>(
+    data: &'m [u8],
+    pos: &mut usize,
+    chunk_size: usize,
+    separator: u8,
+) -> Option<&'m [u8]> {
+    if *pos >= data.len() {
+        return None;
+    }
+
+    let target = (*pos + chunk_size).min(data.len());
+    let end = if target == data.len() {
+        target
+    } else {
+        match data[target..].iter().position(|&byte| byte == separator) {
+            Some(offset) => target + offset + 1,
+            None => data.len(),
+        }
+    };
+
+    let chunk = &data[*pos..end];
+    *pos = end;
+    Some(chunk)
+}
+
+/// An iterator over fixed-size, separator-oblivious chunks of a [`MmapReader`]; see
+/// [`MmapReader::with_chunks`].
+pub struct IterMmapChunks<'m> {
+    data: &'m [u8],
+    chunk_size: usize,
+    pos: usize,
+}
+
+impl<'m> Iterator for IterMmapChunks<'m> {
+    type Item = &'m [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.data.len() {
+            return None;
+        }
+
+        let end = (self.pos + self.chunk_size).min(self.data.len());
+        let chunk = &self.data[self.pos..end];
+        self.pos = end;
+        Some(chunk)
+    }
+}
+
+/// An iterator over the separator-aware chunks of a [`MmapReader`]; see
+/// [`MmapReader::iter`].
+pub struct IterMmapReader<'m, const SEP: u8> {
+    data: &'m [u8],
+    chunk_size: usize,
+    pos: usize,
+}
+
+impl<'m, const SEP: u8> Iterator for IterMmapReader<'m, SEP> {
+    type Item = &'m [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_separated_chunk(self.data, &mut self.pos, self.chunk_size, SEP)
+    }
+}
+
+/// An iterator over the separator-aware chunks of a [`MmapReader`], like
+/// [`IterMmapReader`] but with the separator chosen at runtime instead of compiled
+/// in as a const generic; see [`MmapReader::iter_with_separator`].
+pub struct IterMmapReaderRuntime<'m> {
+    data: &'m [u8],
+    chunk_size: usize,
+    pos: usize,
+    separator: u8,
+}
+
+impl<'m> Iterator for IterMmapReaderRuntime<'m> {
+    type Item = &'m [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_separated_chunk(self.data, &mut self.pos, self.chunk_size, self.separator)
+    }
+}
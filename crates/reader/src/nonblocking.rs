@@ -0,0 +1,169 @@
+//! Asynchronous, `tokio`-backed implementation of the reader.
+//!
+use crate::{config, sync::ChunkSize, utils, FixedMemoryReader};
+
+use std::io;
+
+use futures_core::Stream;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
+
+/// Buffered file reader, reading the file in chunks, backed by `tokio`.
+///
+/// This mirrors [`crate::FixedMemoryReader`]'s `take_until` semantics, but reads
+/// asynchronously so that the indexer can overlap I/O with CPU-bound work.
+pub struct AsyncFixedMemoryReader<R: AsyncRead + Unpin, const ML: usize = { config::MAX_SENTENCE_LENGTH }>
+{
+    inner: BufReader<R>,
+    /// Internally, we use a usize to store the chunk size.
+    pub chunk_size: usize,
+    pub overflow: Vec<u8>,
+    /// Pointer to where the buffer is currently writing to.
+    pub overflow_pointer: usize,
+}
+
+impl<R: AsyncRead + Unpin, const ML: usize> AsyncFixedMemoryReader<R, ML> {
+    /// Create a new instance of the [`AsyncFixedMemoryReader`] using the provided
+    /// [`BufReader`] instance.
+    pub fn new(inner: BufReader<R>, chunk_size: impl Into<ChunkSize>) -> Self {
+        let chunk_size: usize = chunk_size.into().into();
+        Self {
+            inner,
+            chunk_size,
+            overflow: utils::new_buffer(ML),
+            overflow_pointer: 0,
+        }
+    }
+
+    /// Create a new instance of the [`AsyncFixedMemoryReader`] using the provided
+    /// object that implements [`AsyncRead`].
+    pub fn from_read(reader: R, chunk_size: impl Into<ChunkSize>) -> Self {
+        let inner = BufReader::with_capacity(config::CHUNK_SIZE, reader);
+
+        Self::new(inner, chunk_size)
+    }
+
+    /// Read from the reader to try and fill the buffer, but only up to the last
+    /// occurrence of the provided byte within the buffer size.
+    pub async fn take_until(&mut self, byte: u8, buffer: &mut [u8]) -> io::Result<usize> {
+        if buffer.len() < self.chunk_size {
+            panic!(
+                "Buffer size ({buffer_size}) must be at least the chunk size ({chunk_size}).",
+                buffer_size = buffer.len(),
+                chunk_size = self.chunk_size,
+            );
+        }
+        // We may already have some bytes in the internal buffer, so we need to
+        // make sure we count them in the total length to read.
+        let mut read = 0;
+
+        // If we have some overflow from the last read, we need to copy it to the buffer.
+        buffer[..self.overflow_pointer].copy_from_slice(&self.overflow[..self.overflow_pointer]);
+        read += self.overflow_pointer;
+        self.overflow.clear();
+        self.overflow_pointer = 0;
+
+        // Read a chunk of bytes regardless of separators.
+        read += self
+            .inner
+            .read(&mut buffer[read..self.chunk_size.saturating_sub(ML)])
+            .await?;
+
+        // Now we try to find the last occurrence of the separator in the buffer.
+        loop {
+            let overflow_pointer = self.inner.read_until(byte, &mut self.overflow).await?;
+
+            if read + overflow_pointer >= self.chunk_size || overflow_pointer == 0 {
+                self.overflow_pointer = overflow_pointer;
+                return Ok(read);
+            }
+
+            // If our buffer is not full, we can copy the overflow to the buffer.
+            buffer[read..read + overflow_pointer]
+                .copy_from_slice(&self.overflow[..overflow_pointer]);
+            self.overflow.clear();
+            read += overflow_pointer;
+        }
+    }
+
+    /// Turn this reader into a [`Stream`] of chunks, split on `SEP`.
+    ///
+    /// This is the asynchronous equivalent of [`crate::IterFixedMemoryReader`]; it
+    /// consumes the reader so that I/O for the next chunk can be issued while the
+    /// current chunk is still being processed by the caller.
+    pub fn chunks<const SEP: u8>(mut self) -> impl Stream<Item = io::Result<Vec<u8>>> {
+        async_stream::stream! {
+            loop {
+                let mut buffer = utils::new_buffer(self.chunk_size);
+
+                match self.take_until(SEP, &mut buffer).await {
+                    Ok(0) => break,
+                    Ok(bytes_read) => {
+                        buffer.truncate(bytes_read);
+                        yield Ok(buffer);
+                    }
+                    Err(err) => {
+                        yield Err(err);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<R: io::Read + Send + 'static, const ML: usize> FixedMemoryReader<R, ML> {
+    /// Turn this blocking reader into a [`Stream`] of chunks, split on `SEP`, without
+    /// requiring `R` to implement [`tokio::io::AsyncRead`] the way
+    /// [`AsyncFixedMemoryReader::chunks`] does.
+    ///
+    /// The blocking [`Self::take_until`] loop runs on a `tokio` blocking-pool thread
+    /// via [`tokio::task::spawn_blocking`], so polling this stream never blocks the
+    /// calling runtime thread - this is what lets a blocking source such as
+    /// [`crate::MmapReader`] or [`crate::UringFile`] be consumed from an async
+    /// server without giving up a whole runtime thread to it for the duration of
+    /// the read.
+    pub fn into_stream<const SEP: u8>(mut self) -> impl Stream<Item = io::Result<Vec<u8>>> {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+
+        tokio::task::spawn_blocking(move || loop {
+            let mut buffer = utils::new_buffer(self.chunk_size);
+
+            let message = match self.take_until(SEP, &mut buffer) {
+                Ok(0) => break,
+                Ok(bytes_read) => {
+                    buffer.truncate(bytes_read);
+                    Ok(buffer)
+                }
+                Err(err) => Err(err),
+            };
+
+            let is_err = message.is_err();
+            if sender.blocking_send(message).is_err() || is_err {
+                break;
+            }
+        });
+
+        async_stream::stream! {
+            while let Some(message) = receiver.recv().await {
+                yield message;
+            }
+        }
+    }
+}
+
+impl<const ML: usize> AsyncFixedMemoryReader<tokio::fs::File, ML> {
+    /// Read the provided [`tokio::fs::File`] using [`AsyncFixedMemoryReader`].
+    pub fn from_file(file: tokio::fs::File, chunk_size: impl Into<ChunkSize>) -> Self {
+        Self::from_read(file, chunk_size)
+    }
+
+    /// Read the file at the given path using [`AsyncFixedMemoryReader`].
+    pub async fn from_path(
+        path: impl AsRef<std::path::Path>,
+        chunk_size: impl Into<ChunkSize>,
+    ) -> io::Result<Self> {
+        tokio::fs::File::open(path)
+            .await
+            .map(|file| Self::from_file(file, chunk_size))
+    }
+}
@@ -0,0 +1,153 @@
+//! Ordered parallel chunk processing.
+
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    panic,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+/// Process `chunks` across `worker_count` threads running `process`, yielding the
+/// results in the same order `chunks` produced them - even though `process` may
+/// complete them in a different order across the worker threads.
+///
+/// This is the parallel counterpart to [`crate::IntoIterFixedMemoryReader`]: feed it
+/// the chunks read off a file, and get back an iterator over the processed chunks in
+/// the same order they were read, suitable for a deterministic export that has to
+/// write its output in the same order as its input despite processing chunks out of
+/// order across threads.
+pub fn into_ordered_parallel<T, U, F>(
+    chunks: impl IntoIterator<Item = T> + Send + 'static,
+    worker_count: usize,
+    process: F,
+) -> OrderedParallelIter<U>
+where
+    T: Send + 'static,
+    U: Send + 'static,
+    F: Fn(T) -> U + Send + Sync + 'static,
+{
+    assert!(worker_count > 0, "worker_count must be at least 1.");
+
+    let (input_sender, input_receiver) = mpsc::channel::<(usize, T)>();
+    let input_receiver = Arc::new(Mutex::new(input_receiver));
+    let (output_sender, output_receiver) = mpsc::channel::<(usize, U)>();
+    let process = Arc::new(process);
+    // Set by whichever worker panics first, so `OrderedParallelIter::next` can resume
+    // the panic on the consumer's thread instead of just quietly running dry.
+    let panic: Arc<Mutex<Option<Box<dyn Any + Send + 'static>>>> = Arc::new(Mutex::new(None));
+
+    // Tags each chunk with its position in `chunks` before handing it to whichever
+    // worker thread picks it up next.
+    let feeder = thread::spawn(move || {
+        for (sequence, value) in chunks.into_iter().enumerate() {
+            if input_sender.send((sequence, value)).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut handles: Vec<thread::JoinHandle<()>> = (0..worker_count)
+        .map(|_| {
+            let input_receiver = Arc::clone(&input_receiver);
+            let output_sender = output_sender.clone();
+            let process = Arc::clone(&process);
+            let panic = Arc::clone(&panic);
+
+            thread::spawn(move || loop {
+                let next = input_receiver
+                    .lock()
+                    .expect("a worker thread panicked while holding the input lock")
+                    .recv();
+
+                match next {
+                    Ok((sequence, value)) => {
+                        match panic::catch_unwind(panic::AssertUnwindSafe(|| process(value))) {
+                            Ok(output) => {
+                                if output_sender.send((sequence, output)).is_err() {
+                                    break;
+                                }
+                            }
+                            // Record the panic and stop, rather than silently dropping
+                            // this chunk and everything after it: the caller resumes
+                            // it once the output channel runs dry.
+                            Err(payload) => {
+                                *panic.lock().expect("a worker thread panicked while holding the panic lock") = Some(payload);
+                                break;
+                            }
+                        }
+                    }
+                    Err(_) => break,
+                }
+            })
+        })
+        .collect();
+
+    handles.push(feeder);
+
+    OrderedParallelIter {
+        receiver: output_receiver,
+        pending: BTreeMap::new(),
+        next_sequence: 0,
+        handles: Some(handles),
+        panic,
+    }
+}
+
+/// The combiner returned by [`into_ordered_parallel`]; restores the submission order
+/// of chunks that were processed out of order across worker threads.
+pub struct OrderedParallelIter<U> {
+    receiver: mpsc::Receiver<(usize, U)>,
+    /// Results that arrived ahead of `next_sequence`, held until it's their turn.
+    pending: BTreeMap<usize, U>,
+    next_sequence: usize,
+    handles: Option<Vec<thread::JoinHandle<()>>>,
+    /// Set by a worker that panicked while processing a chunk; resumed on this
+    /// thread once the output channel runs dry, so a panicking `process` crashes the
+    /// caller instead of silently truncating a "deterministic" export.
+    panic: Arc<Mutex<Option<Box<dyn Any + Send + 'static>>>>,
+}
+
+impl<U> Iterator for OrderedParallelIter<U> {
+    type Item = U;
+
+    fn next(&mut self) -> Option<U> {
+        loop {
+            if let Some(value) = self.pending.remove(&self.next_sequence) {
+                self.next_sequence += 1;
+                return Some(value);
+            }
+
+            match self.receiver.recv() {
+                Ok((sequence, value)) => {
+                    self.pending.insert(sequence, value);
+                }
+                // Every worker has exited; if one of them panicked, resume that
+                // panic here rather than pretending the sequence simply ended.
+                Err(_) => {
+                    if let Some(payload) =
+                        self.panic.lock().expect("the panic lock was poisoned").take()
+                    {
+                        panic::resume_unwind(payload);
+                    }
+
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+impl<U> Drop for OrderedParallelIter<U> {
+    fn drop(&mut self) {
+        // The feeder/worker threads never block on `output_sender.send` (the channel
+        // is unbounded), so this always completes even if the caller stops iterating
+        // partway through - it just drains the rest of `chunks` in the background
+        // first.
+        if let Some(handles) = self.handles.take() {
+            for handle in handles {
+                let _ = handle.join();
+            }
+        }
+    }
+}
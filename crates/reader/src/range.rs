@@ -0,0 +1,106 @@
+//! A line-aligned byte-range [`io::Read`] over a single seekable source.
+
+use std::io;
+
+/// Reads a `[start, end)` byte range of a seekable source, snapped to line
+/// boundaries: the first partial line after `start` is discarded (some other range
+/// starting earlier owns it), and reading continues past `end` up to and including
+/// the next `separator`, so the last line in the range is never truncated.
+///
+/// This lets multiple machines or processes each index a disjoint byte range of one
+/// huge file without any two of them double-counting or dropping the line that
+/// straddles their shared boundary.
+///
+/// Implements [`io::Read`] itself, so it plugs into [`crate::FixedMemoryReader`] the
+/// same way [`crate::ThrottledReader`] or a decompressing reader would.
+pub struct RangeReader<R: io::Read + io::Seek> {
+    inner: R,
+    separator: u8,
+    /// Byte offset, in the underlying source, at which this range should stop once
+    /// the line straddling it has also been consumed.
+    end: u64,
+    /// Byte offset, in the underlying source, of the next byte [`Self::inner`] will
+    /// yield.
+    position: u64,
+    finished: bool,
+}
+
+impl<R: io::Read + io::Seek> RangeReader<R> {
+    /// Seek `inner` to `start` and prepare to read up to the first `separator` at or
+    /// after `end`, discarding whatever partial line precedes the first `separator`
+    /// at or after `start`.
+    ///
+    /// `start` landing exactly on the byte after a separator (as it will for every
+    /// range but the first, when ranges are chosen as a contiguous partition of the
+    /// file) is treated as already aligned - nothing is discarded - so the line
+    /// starting there isn't dropped by both this range and the one before it.
+    pub fn new(mut inner: R, separator: u8, start: u64, end: u64) -> io::Result<Self> {
+        let already_aligned = if start == 0 {
+            true
+        } else {
+            inner.seek(io::SeekFrom::Start(start - 1))?;
+            let mut byte = [0u8; 1];
+            inner.read(&mut byte)? == 1 && byte[0] == separator
+        };
+
+        inner.seek(io::SeekFrom::Start(start))?;
+
+        let mut reader = Self { inner, separator, end, position: start, finished: false };
+
+        if !already_aligned {
+            reader.discard_until_separator()?;
+        }
+
+        Ok(reader)
+    }
+
+    /// Consume bytes one at a time until `separator` has been read (and discarded)
+    /// or the source is exhausted. The discarded prefix is normally at most one
+    /// line long, so reading it a byte at a time rather than in bulk is not worth
+    /// the extra bookkeeping.
+    fn discard_until_separator(&mut self) -> io::Result<()> {
+        let mut byte = [0u8; 1];
+
+        loop {
+            if self.inner.read(&mut byte)? == 0 {
+                self.finished = true;
+                return Ok(());
+            }
+
+            self.position += 1;
+
+            if byte[0] == self.separator {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<R: io::Read + io::Seek> io::Read for RangeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.finished || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let read = self.inner.read(buf)?;
+        if read == 0 {
+            self.finished = true;
+            return Ok(0);
+        }
+
+        // Stop at the first separator that ends a line whose *next* line would start
+        // at or past `end` - not merely one whose own position is past `end` - so a
+        // range whose `end` happens to land exactly on a line boundary doesn't pull
+        // in one extra line that the following range's `start` already owns.
+        if let Some(index) = buf[..read].iter().enumerate().position(|(index, &byte)| {
+            byte == self.separator && self.position + index as u64 + 1 >= self.end
+        }) {
+            self.position += index as u64 + 1;
+            self.finished = true;
+            return Ok(index + 1);
+        }
+
+        self.position += read as u64;
+        Ok(read)
+    }
+}
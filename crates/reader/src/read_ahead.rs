@@ -0,0 +1,113 @@
+//! A background-thread, read-ahead [`io::Read`] wrapper.
+
+use crate::config;
+
+use std::{io, sync::mpsc, thread};
+
+/// Number of chunks buffered between the background reader thread and the caller:
+/// one being filled by the background thread while the other is held by the caller -
+/// hence "double-buffered", rather than a deeper read-ahead queue.
+const CHANNEL_CAPACITY: usize = 1;
+
+/// Wraps any [`io::Read`] with a background thread that reads the next chunk ahead of
+/// time into a bounded channel, so the latency of that read overlaps with whatever
+/// CPU work the caller is doing on the current chunk - without pulling in an async
+/// runtime the way [`crate::AsyncFixedMemoryReader`] does.
+///
+/// Implements [`io::Read`] itself, so it plugs into [`crate::FixedMemoryReader`] the
+/// same way [`crate::UringFile`] or a decompressing reader would.
+pub struct ReadAheadReader {
+    receiver: Option<mpsc::Receiver<io::Result<Vec<u8>>>>,
+    handle: Option<thread::JoinHandle<()>>,
+    current: Vec<u8>,
+    current_pos: usize,
+    done: bool,
+}
+
+impl ReadAheadReader {
+    /// Wrap `inner` with a background read-ahead thread, using the default chunk
+    /// size (see [`config::CHUNK_SIZE`]).
+    pub fn new<R: io::Read + Send + 'static>(inner: R) -> Self {
+        Self::with_chunk_size(inner, config::CHUNK_SIZE)
+    }
+
+    /// Wrap `inner` with a background read-ahead thread, reading `chunk_size` bytes
+    /// at a time.
+    pub fn with_chunk_size<R: io::Read + Send + 'static>(mut inner: R, chunk_size: usize) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(CHANNEL_CAPACITY);
+
+        let handle = thread::spawn(move || loop {
+            let mut buffer = vec![0u8; chunk_size];
+
+            let message = match inner.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => {
+                    buffer.truncate(read);
+                    Ok(buffer)
+                }
+                Err(err) => Err(err),
+            };
+
+            let is_err = message.is_err();
+            if sender.send(message).is_err() || is_err {
+                break;
+            }
+        });
+
+        Self {
+            receiver: Some(receiver),
+            handle: Some(handle),
+            current: Vec::new(),
+            current_pos: 0,
+            done: false,
+        }
+    }
+}
+
+impl io::Read for ReadAheadReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.current_pos >= self.current.len() {
+            if self.done {
+                return Ok(0);
+            }
+
+            let receiver = self.receiver.as_ref().expect("receiver is only taken in `Drop`");
+            match receiver.recv() {
+                Ok(Ok(chunk)) => {
+                    self.current = chunk;
+                    self.current_pos = 0;
+                }
+                Ok(Err(err)) => {
+                    self.done = true;
+                    return Err(err);
+                }
+                // The background thread has exited, meaning it reached the end of
+                // `inner`.
+                Err(mpsc::RecvError) => {
+                    self.done = true;
+                    return Ok(0);
+                }
+            }
+        }
+
+        let available = &self.current[self.current_pos..];
+        let read = available.len().min(buf.len());
+        buf[..read].copy_from_slice(&available[..read]);
+        self.current_pos += read;
+
+        Ok(read)
+    }
+}
+
+impl Drop for ReadAheadReader {
+    fn drop(&mut self) {
+        // Drop the receiver first so a background thread blocked on `sender.send`
+        // wakes up immediately with a disconnected channel, instead of `join`
+        // blocking on a thread that is waiting for a reader that will never arrive.
+        drop(self.receiver.take());
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -48,6 +48,11 @@ pub struct FixedMemoryReader<R: io::Read, const ML: usize = { config::MAX_SENTEN
     pub overflow: Vec<u8>,
     /// Pointer to where the buffer is currently writing to.
     pub overflow_pointer: usize,
+    /// Cumulative count of bytes yielded by [`Self::take_until`] so far, reported to
+    /// `progress` alongside the total set by [`Self::on_progress`].
+    bytes_read: usize,
+    progress: Option<Box<dyn FnMut(usize, usize) + Send>>,
+    progress_total: usize,
 }
 
 impl<R: io::Read, const ML: usize> FixedMemoryReader<R, ML> {
@@ -60,9 +65,33 @@ impl<R: io::Read, const ML: usize> FixedMemoryReader<R, ML> {
             chunk_size,
             overflow: utils::new_buffer(ML),
             overflow_pointer: 0,
+            bytes_read: 0,
+            progress: None,
+            progress_total: 0,
         }
     }
 
+    /// Register a callback to be invoked with `(bytes_read, total)` every time
+    /// [`Self::take_until`] yields a chunk, so a caller (the index binary, server
+    /// ingestion, ...) can report progress without wrapping every call itself.
+    ///
+    /// `total` is a fixed value supplied by the caller up front - for a file-backed
+    /// reader this is typically [`Self::size`] on the [`fs::File`] specialisation.
+    pub fn on_progress<F>(mut self, total: usize, callback: F) -> Self
+    where
+        F: FnMut(usize, usize) + Send + 'static,
+    {
+        self.progress = Some(Box::new(callback));
+        self.progress_total = total;
+        self
+    }
+
+    /// The underlying reader, e.g. to read out a [`crate::ChecksummingReader`]'s
+    /// digest once reading has finished.
+    pub fn get_ref(&self) -> &R {
+        self.inner.get_ref()
+    }
+
     /// Create a new instance of the [`FixedMemoryReader`] using the provided
     /// object that implements [`io::Read`].
     pub fn from_read(reader: R, chunk_size: impl Into<ChunkSize>) -> Self {
@@ -102,6 +131,14 @@ impl<R: io::Read, const ML: usize> FixedMemoryReader<R, ML> {
 
             if read + overflow_pointer >= self.chunk_size || overflow_pointer == 0 {
                 self.overflow_pointer = overflow_pointer;
+
+                if read > 0 {
+                    self.bytes_read += read;
+                    if let Some(progress) = self.progress.as_mut() {
+                        progress(self.bytes_read, self.progress_total);
+                    }
+                }
+
                 return Ok(read);
             }
 
@@ -117,6 +154,28 @@ impl<R: io::Read, const ML: usize> FixedMemoryReader<R, ML> {
     pub fn iter<const SEP: u8>(&mut self) -> IterFixedMemoryReader<'_, SEP, R, ML> {
         IterFixedMemoryReader { reader: self }
     }
+
+    /// Iterate over the chunks of bytes in the memory-mapped file, like [`Self::iter`]
+    /// but with the separator chosen at runtime instead of compiled in as a const
+    /// generic, for callers that only know it once the program starts (e.g. from a
+    /// command line flag).
+    pub fn iter_with_separator(&mut self, separator: u8) -> IterFixedMemoryReaderRuntime<'_, R, ML> {
+        IterFixedMemoryReaderRuntime { reader: self, separator }
+    }
+
+    /// Consume `self` into an iterator over its chunks, like [`Self::iter`], but the
+    /// iterator owns the reader instead of borrowing it - so it can be moved into a
+    /// thread that outlives the loop that would otherwise have held the borrow.
+    pub fn into_chunks<const SEP: u8>(self) -> IntoIterFixedMemoryReader<SEP, R, ML> {
+        IntoIterFixedMemoryReader { reader: self }
+    }
+
+    /// Consume `self` into an iterator over its chunks, like [`Self::into_chunks`],
+    /// but with the separator chosen at runtime instead of compiled in as a const
+    /// generic; the owned counterpart of [`Self::iter_with_separator`].
+    pub fn into_chunks_with_separator(self, separator: u8) -> IntoIterFixedMemoryReaderRuntime<R, ML> {
+        IntoIterFixedMemoryReaderRuntime { reader: self, separator }
+    }
 }
 
 impl<const ML: usize> FixedMemoryReader<fs::File, ML> {
@@ -139,6 +198,155 @@ impl<const ML: usize> FixedMemoryReader<fs::File, ML> {
     }
 }
 
+impl<const ML: usize> FixedMemoryReader<io::Stdin, ML> {
+    /// Read from standard input using [`FixedMemoryReader`], so a dump can be piped
+    /// through a decompressor or filter without touching disk twice.
+    pub fn from_stdin(chunk_size: impl Into<ChunkSize>) -> Self {
+        Self::from_read(io::stdin(), chunk_size)
+    }
+}
+
+#[cfg(feature = "unix")]
+impl<const ML: usize> FixedMemoryReader<fs::File, ML> {
+    /// Hint to the kernel that this file is about to be read sequentially from start
+    /// to end, and that it should be paged in ahead of time; see `posix_fadvise(2)`'s
+    /// `POSIX_FADV_SEQUENTIAL` and `POSIX_FADV_WILLNEED`. Purely a throughput hint for
+    /// a large, cold-cache file - the reader remains fully usable even if the kernel
+    /// rejects it, which is why this is a separate opt-in call rather than something
+    /// [`Self::from_file`] applies unconditionally.
+    pub fn advise_sequential(&self) -> io::Result<()> {
+        use std::os::unix::io::AsRawFd;
+
+        let fd = self.inner.get_ref().as_raw_fd();
+        fadvise(fd, libc::POSIX_FADV_SEQUENTIAL)?;
+        fadvise(fd, libc::POSIX_FADV_WILLNEED)
+    }
+}
+
+#[cfg(feature = "unix")]
+fn fadvise(fd: std::os::unix::io::RawFd, advice: libc::c_int) -> io::Result<()> {
+    let result = unsafe { libc::posix_fadvise(fd, 0, 0, advice) };
+
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::from_raw_os_error(result))
+    }
+}
+
+#[cfg(feature = "compression")]
+impl<const ML: usize> FixedMemoryReader<Box<dyn io::Read + Send>, ML> {
+    /// Read the file at the given path, transparently decompressing it based on its
+    /// extension.
+    ///
+    /// `.gz`, `.zst`, and `.xz` are decompressed on the fly as the file is read, so
+    /// the fixed-memory chunking guarantees of [`FixedMemoryReader`] still hold; any
+    /// other extension is read as-is.
+    pub fn from_compressed_path(
+        path: impl AsRef<std::path::Path>,
+        chunk_size: impl Into<ChunkSize>,
+    ) -> io::Result<Self> {
+        let path = path.as_ref();
+        let file = fs::File::open(path)?;
+
+        let reader: Box<dyn io::Read + Send> =
+            match path.extension().and_then(|extension| extension.to_str()) {
+                Some("gz") => Box::new(flate2::read::GzDecoder::new(file)),
+                Some("zst") => Box::new(zstd::stream::read::Decoder::new(file)?),
+                Some("xz") => Box::new(xz2::read::XzDecoder::new(file)),
+                _ => Box::new(file),
+            };
+
+        Ok(Self::from_read(reader, chunk_size))
+    }
+}
+
+impl<const ML: usize> FixedMemoryReader<crate::ReadAheadReader, ML> {
+    /// Read the file at the given path via a [`crate::ReadAheadReader`], so the next
+    /// chunk is being read on a background thread while the caller processes the
+    /// current one, instead of blocking fully on each read like [`Self::from_path`].
+    pub fn from_read_ahead_path(
+        path: impl AsRef<std::path::Path>,
+        chunk_size: impl Into<ChunkSize>,
+    ) -> io::Result<Self> {
+        let chunk_size: usize = chunk_size.into().into();
+        let file = fs::File::open(path)?;
+
+        Ok(Self::from_read(
+            crate::ReadAheadReader::with_chunk_size(file, chunk_size),
+            chunk_size,
+        ))
+    }
+}
+
+impl<const ML: usize> FixedMemoryReader<crate::ThrottledReader<fs::File>, ML> {
+    /// Read the file at the given path via a [`crate::ThrottledReader`], capping
+    /// throughput at `bytes_per_second` so a background re-index job doesn't
+    /// saturate the disk on a shared host; `bytes_per_second = 0` disables the cap.
+    pub fn from_throttled_path(
+        path: impl AsRef<std::path::Path>,
+        chunk_size: impl Into<ChunkSize>,
+        bytes_per_second: usize,
+    ) -> io::Result<Self> {
+        let file = fs::File::open(path)?;
+
+        Ok(Self::from_read(
+            crate::ThrottledReader::new(file, bytes_per_second),
+            chunk_size,
+        ))
+    }
+}
+
+impl<const ML: usize> FixedMemoryReader<crate::ChainedReader<fs::File>, ML> {
+    /// Read `paths` in order via a [`crate::ChainedReader`], as if they were one
+    /// file, so multiple source files can be fed through the same indexing loop as a
+    /// single logical stream.
+    pub fn from_chained_paths(
+        paths: impl IntoIterator<Item = impl AsRef<std::path::Path>>,
+        chunk_size: impl Into<ChunkSize>,
+        separator: u8,
+    ) -> io::Result<Self> {
+        Ok(Self::from_read(
+            crate::ChainedReader::from_paths(paths, separator)?,
+            chunk_size,
+        ))
+    }
+}
+
+impl<const ML: usize> FixedMemoryReader<crate::RangeReader<fs::File>, ML> {
+    /// Read only the line-aligned byte range `[start, end)` of the file at `path`,
+    /// via a [`crate::RangeReader`], so multiple machines or processes can each
+    /// index a disjoint slice of one huge file without dropping or double-counting
+    /// the line that straddles their shared boundary.
+    pub fn from_path_range(
+        path: impl AsRef<std::path::Path>,
+        chunk_size: impl Into<ChunkSize>,
+        separator: u8,
+        start: u64,
+        end: u64,
+    ) -> io::Result<Self> {
+        let file = fs::File::open(path)?;
+
+        Ok(Self::from_read(
+            crate::RangeReader::new(file, separator, start, end)?,
+            chunk_size,
+        ))
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+impl<const ML: usize> FixedMemoryReader<crate::UringFile, ML> {
+    /// Read the file at the given path via [`crate::UringFile`], overlapping
+    /// submission and completion of the underlying reads instead of blocking fully
+    /// on each one like [`Self::from_path`].
+    pub fn from_uring_path(
+        path: impl AsRef<std::path::Path>,
+        chunk_size: impl Into<ChunkSize>,
+    ) -> io::Result<Self> {
+        Ok(Self::from_read(crate::UringFile::from_path(path)?, chunk_size))
+    }
+}
+
 /// An iterator over the chunks of bytes in the reader.
 pub struct IterFixedMemoryReader<'m, const SEP: u8, R: io::Read, const ML: usize> {
     reader: &'m mut FixedMemoryReader<R, ML>,
@@ -161,6 +369,90 @@ impl<'m, const SEP: u8, R: io::Read, const ML: usize> Iterator
             .take_until(SEP, &mut buffer)
             .expect("Failed to read from the internal reader.");
 
-        (bytes_read > 0).then_some(buffer)
+        (bytes_read > 0).then(|| {
+            buffer.truncate(bytes_read);
+            buffer
+        })
+    }
+}
+
+/// An iterator over the chunks of bytes in the reader, like [`IterFixedMemoryReader`]
+/// but with the separator chosen at runtime instead of compiled in as a const generic.
+pub struct IterFixedMemoryReaderRuntime<'m, R: io::Read, const ML: usize> {
+    reader: &'m mut FixedMemoryReader<R, ML>,
+    separator: u8,
+}
+
+impl<'m, R: io::Read, const ML: usize> Iterator for IterFixedMemoryReaderRuntime<'m, R, ML> {
+    type Item = Vec<u8>;
+
+    /// Read the next chunk of bytes from the reader.
+    ///
+    /// This is a simple wrapper around the [`FixedMemoryReader::take_until`] method, however
+    /// this is less efficient as it creates a new buffer for each chunk.
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buffer = utils::new_buffer(self.reader.chunk_size);
+
+        let bytes_read = self
+            .reader
+            .take_until(self.separator, &mut buffer)
+            .expect("Failed to read from the internal reader.");
+
+        (bytes_read > 0).then(|| {
+            buffer.truncate(bytes_read);
+            buffer
+        })
+    }
+}
+
+/// An owned iterator over the chunks of bytes in a [`FixedMemoryReader`], like
+/// [`IterFixedMemoryReader`] but holding the reader itself rather than borrowing it;
+/// see [`FixedMemoryReader::into_chunks`].
+pub struct IntoIterFixedMemoryReader<const SEP: u8, R: io::Read, const ML: usize> {
+    reader: FixedMemoryReader<R, ML>,
+}
+
+impl<const SEP: u8, R: io::Read, const ML: usize> Iterator for IntoIterFixedMemoryReader<SEP, R, ML> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buffer = utils::new_buffer(self.reader.chunk_size);
+
+        let bytes_read = self
+            .reader
+            .take_until(SEP, &mut buffer)
+            .expect("Failed to read from the internal reader.");
+
+        (bytes_read > 0).then(|| {
+            buffer.truncate(bytes_read);
+            buffer
+        })
+    }
+}
+
+/// An owned iterator over the chunks of bytes in a [`FixedMemoryReader`], like
+/// [`IntoIterFixedMemoryReader`] but with the separator chosen at runtime instead of
+/// compiled in as a const generic; see
+/// [`FixedMemoryReader::into_chunks_with_separator`].
+pub struct IntoIterFixedMemoryReaderRuntime<R: io::Read, const ML: usize> {
+    reader: FixedMemoryReader<R, ML>,
+    separator: u8,
+}
+
+impl<R: io::Read, const ML: usize> Iterator for IntoIterFixedMemoryReaderRuntime<R, ML> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buffer = utils::new_buffer(self.reader.chunk_size);
+
+        let bytes_read = self
+            .reader
+            .take_until(self.separator, &mut buffer)
+            .expect("Failed to read from the internal reader.");
+
+        (bytes_read > 0).then(|| {
+            buffer.truncate(bytes_read);
+            buffer
+        })
     }
 }
@@ -0,0 +1,61 @@
+//! A bytes-per-second throttled [`io::Read`] wrapper.
+
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+
+/// Wraps any [`io::Read`] with a bytes-per-second cap, sleeping as needed after each
+/// read so a background re-index job on a shared host doesn't saturate the disk.
+///
+/// Implements [`io::Read`] itself, so it plugs into [`crate::FixedMemoryReader`] the
+/// same way [`crate::ReadAheadReader`] or a decompressing reader would.
+pub struct ThrottledReader<R: io::Read> {
+    inner: R,
+    /// Cap in bytes per second; `0` disables throttling entirely.
+    bytes_per_second: usize,
+    window_start: Instant,
+    window_bytes: usize,
+}
+
+impl<R: io::Read> ThrottledReader<R> {
+    /// Wrap `inner`, capping throughput at `bytes_per_second` bytes per second. A
+    /// cap of `0` disables throttling, so a caller can wire this in unconditionally
+    /// and toggle it off with a config value rather than branching on `Option`.
+    pub fn new(inner: R, bytes_per_second: usize) -> Self {
+        Self {
+            inner,
+            bytes_per_second,
+            window_start: Instant::now(),
+            window_bytes: 0,
+        }
+    }
+}
+
+impl<R: io::Read> io::Read for ThrottledReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+
+        if self.bytes_per_second == 0 || read == 0 {
+            return Ok(read);
+        }
+
+        self.window_bytes += read;
+
+        let elapsed = self.window_start.elapsed();
+        let allowed = Duration::from_secs_f64(self.window_bytes as f64 / self.bytes_per_second as f64);
+
+        if let Some(remaining) = allowed.checked_sub(elapsed) {
+            std::thread::sleep(remaining);
+        }
+
+        // Reset the accounting window once we're caught up, so a reader that pauses
+        // between reads doesn't build up an allowance and burst once it resumes.
+        if elapsed >= allowed {
+            self.window_start = Instant::now();
+            self.window_bytes = 0;
+        }
+
+        Ok(read)
+    }
+}
@@ -0,0 +1,200 @@
+//! An `io_uring`-backed [`io::Read`] implementation, available only on Linux behind
+//! the `io-uring` feature.
+//!
+//! [`UringFile`] keeps several reads of the underlying file in flight at once, so the
+//! kernel can be filling the next chunk while the caller is still consuming the
+//! previous one - unlike a plain [`fs::File`], which blocks fully on every read.
+//! Plug it into [`crate::FixedMemoryReader::from_read`] to get the exact same
+//! chunk/separator semantics as every other reader in this crate.
+
+use crate::config;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs, io,
+    os::unix::io::AsRawFd,
+    path,
+};
+
+use io_uring::{opcode, types, IoUring};
+
+/// Number of reads kept in flight at once by default; see
+/// [`UringFile::with_queue_depth`].
+const DEFAULT_QUEUE_DEPTH: usize = 4;
+
+pub struct UringFile {
+    file: fs::File,
+    ring: IoUring,
+    file_len: u64,
+    read_size: usize,
+    queue_depth: usize,
+    /// Offset of the next read still to be submitted.
+    next_offset: u64,
+    /// Sequence number of the next read still to be submitted.
+    next_submit_seq: u64,
+    /// Sequence number of the next read still to be handed to the caller.
+    next_ready_seq: u64,
+    /// Reads submitted but not yet completed, keyed by sequence number.
+    inflight: HashMap<u64, Vec<u8>>,
+    /// Reads completed but not yet handed to the caller, keyed by sequence number -
+    /// a `BTreeMap` so they can be drained back out in submission order regardless of
+    /// the order the kernel actually completed them in.
+    completed: BTreeMap<u64, Vec<u8>>,
+    /// The oldest completed read not yet fully consumed by [`io::Read::read`].
+    ready: Vec<u8>,
+    ready_pos: usize,
+}
+
+impl UringFile {
+    /// Open the file at `path` for `io_uring`-backed reading, with the default chunk
+    /// size (see [`config::CHUNK_SIZE`]) and queue depth.
+    pub fn from_path(path: impl AsRef<path::Path>) -> io::Result<Self> {
+        Self::from_file(fs::File::open(path)?)
+    }
+
+    /// Wrap an already-open [`fs::File`] for `io_uring`-backed reading, with the
+    /// default chunk size and queue depth.
+    pub fn from_file(file: fs::File) -> io::Result<Self> {
+        let file_len = file.metadata()?.len();
+        let queue_depth = DEFAULT_QUEUE_DEPTH;
+
+        Ok(Self {
+            file,
+            ring: IoUring::new(queue_depth as u32)?,
+            file_len,
+            read_size: config::CHUNK_SIZE,
+            queue_depth,
+            next_offset: 0,
+            next_submit_seq: 0,
+            next_ready_seq: 0,
+            inflight: HashMap::with_capacity(queue_depth),
+            completed: BTreeMap::new(),
+            ready: Vec::new(),
+            ready_pos: 0,
+        })
+    }
+
+    /// Size, in bytes, of each individual `io_uring` read submitted.
+    pub fn with_chunk_size(mut self, read_size: usize) -> Self {
+        self.read_size = read_size;
+        self
+    }
+
+    /// Number of reads submitted ahead of where the caller has consumed to. A deeper
+    /// queue overlaps more of the kernel's completion latency at the cost of holding
+    /// more buffers in flight; rebuilds the underlying ring to the new depth.
+    pub fn with_queue_depth(mut self, queue_depth: usize) -> io::Result<Self> {
+        self.ring = IoUring::new(queue_depth as u32)?;
+        self.queue_depth = queue_depth;
+        Ok(self)
+    }
+
+    /// Submit reads until [`Self::queue_depth`] are in flight, or the whole file has
+    /// been submitted.
+    fn fill_queue(&mut self) -> io::Result<()> {
+        let mut submitted = false;
+
+        while self.inflight.len() < self.queue_depth && self.next_offset < self.file_len {
+            let len = (self.file_len - self.next_offset).min(self.read_size as u64) as usize;
+            let mut buffer = vec![0u8; len];
+            let seq = self.next_submit_seq;
+
+            let entry = opcode::Read::new(
+                types::Fd(self.file.as_raw_fd()),
+                buffer.as_mut_ptr(),
+                len as _,
+            )
+            .offset(self.next_offset)
+            .build()
+            .user_data(seq);
+
+            // Safe because `buffer` is kept alive in `self.inflight` until its
+            // matching completion has been consumed by `drain_completions`.
+            unsafe {
+                self.ring.submission().push(&entry).map_err(|_| {
+                    io::Error::other("the io_uring submission queue is full")
+                })?;
+            }
+
+            self.inflight.insert(seq, buffer);
+            self.next_offset += len as u64;
+            self.next_submit_seq += 1;
+            submitted = true;
+        }
+
+        if submitted {
+            self.ring.submit()?;
+        }
+
+        Ok(())
+    }
+
+    /// Move every currently-completed entry from [`Self::inflight`] into
+    /// [`Self::completed`], failing on the first read that came back with an error.
+    fn drain_completions(&mut self) -> io::Result<()> {
+        let cqes: Vec<_> =
+            self.ring.completion().map(|cqe| (cqe.user_data(), cqe.result())).collect();
+
+        for (seq, result) in cqes {
+            let mut buffer = self.inflight.remove(&seq).ok_or_else(|| {
+                io::Error::other("io_uring completed an unrecognised request")
+            })?;
+
+            if result < 0 {
+                return Err(io::Error::from_raw_os_error(-result));
+            }
+
+            buffer.truncate(result as usize);
+            self.completed.insert(seq, buffer);
+        }
+
+        Ok(())
+    }
+
+    /// Ensure the next chunk the caller is due (in submission order) is available in
+    /// [`Self::completed`], submitting and waiting on the ring as needed.
+    fn pump(&mut self) -> io::Result<()> {
+        self.fill_queue()?;
+
+        while !self.completed.contains_key(&self.next_ready_seq) {
+            if self.inflight.is_empty() {
+                // Nothing left to submit and nothing left in flight: genuine EOF.
+                return Ok(());
+            }
+
+            self.ring.submit_and_wait(1)?;
+            self.drain_completions()?;
+            self.fill_queue()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl io::Read for UringFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.ready_pos >= self.ready.len() {
+            self.pump()?;
+
+            match self.completed.remove(&self.next_ready_seq) {
+                Some(chunk) => {
+                    self.next_ready_seq += 1;
+                    self.ready = chunk;
+                    self.ready_pos = 0;
+                }
+                None => return Ok(0),
+            }
+
+            if self.ready.is_empty() {
+                return Ok(0);
+            }
+        }
+
+        let available = &self.ready[self.ready_pos..];
+        let read = available.len().min(buf.len());
+        buf[..read].copy_from_slice(&available[..read]);
+        self.ready_pos += read;
+
+        Ok(read)
+    }
+}
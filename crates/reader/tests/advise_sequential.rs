@@ -0,0 +1,62 @@
+#![cfg(feature = "unix")]
+
+const TEST_DIR: &str = "./.tests";
+const TEST_FILE: &str = "test_document.txt";
+
+use std::{fs, io};
+
+fn get_test_document_path() -> io::Result<std::path::PathBuf> {
+    let path = fs::canonicalize(TEST_DIR).unwrap_or_else(|_| {
+        panic!(
+            "Failed to canonicalize the test directory path at '{path}'; does it exist?",
+            path = TEST_DIR
+        )
+    });
+
+    Ok(path.join(TEST_FILE))
+}
+
+#[test]
+fn mmap_reader_advise_sequential_does_not_disturb_subsequent_reads() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+    let reader = reader::MmapReader::from_path(path).expect("Failed to memory-map the test document.");
+
+    reader
+        .advise_sequential()
+        .expect("Failed to advise the kernel of the mapping's access pattern.");
+
+    let total_lines = reader
+        .iter::<b'\n'>()
+        .flat_map(|chunk| chunk.split(|&byte| byte == b'\n').map(<[u8]>::to_vec).collect::<Vec<_>>())
+        .filter(|line| !line.is_empty())
+        .count();
+
+    assert_eq!(total_lines, 200);
+}
+
+#[test]
+fn fixed_memory_reader_advise_sequential_does_not_disturb_subsequent_reads() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+    let file = fs::File::open(&path).expect("Failed to open the test document.");
+    let reader = reader::FixedMemoryReader::<_, 24>::from_file(file, 128usize);
+
+    reader
+        .advise_sequential()
+        .expect("Failed to advise the kernel of the file's access pattern.");
+
+    let mut reader = reader;
+    let mut buffer = reader::utils::new_buffer(128usize);
+    let mut total_lines = 0;
+    loop {
+        match reader.take_until(b'\n', &mut buffer) {
+            Ok(0) => break,
+            Ok(len) => {
+                let chunk = std::str::from_utf8(&buffer[..len]).expect("Chunk was not valid UTF-8.");
+                chunk.split_whitespace().for_each(|_| total_lines += 1);
+            }
+            Err(err) => panic!("Failed to read from the test document: {}", err),
+        }
+    }
+
+    assert_eq!(total_lines, 200);
+}
@@ -0,0 +1,56 @@
+use std::io::Read;
+
+#[test]
+fn chained_reader_concatenates_multiple_sources_in_order() {
+    let mut reader = reader::ChainedReader::new(
+        vec!["one\ntwo".as_bytes(), "three\nfour\n".as_bytes(), "five".as_bytes()],
+        b'\n',
+    );
+
+    let mut out = Vec::new();
+    reader.read_to_end(&mut out).expect("Failed to read from the chained reader.");
+
+    assert_eq!(out, b"one\ntwo\nthree\nfour\nfive");
+}
+
+#[test]
+fn chained_reader_inserts_a_separator_between_sources_that_dont_end_on_one() {
+    let mut reader = reader::ChainedReader::new(vec!["one".as_bytes(), "two".as_bytes()], b'\n');
+
+    let mut out = Vec::new();
+    reader.read_to_end(&mut out).expect("Failed to read from the chained reader.");
+
+    assert_eq!(out, b"one\ntwo");
+}
+
+#[test]
+fn chained_reader_does_not_insert_a_separator_when_the_source_already_ended_on_one() {
+    let mut reader = reader::ChainedReader::new(vec!["one\n".as_bytes(), "two".as_bytes()], b'\n');
+
+    let mut out = Vec::new();
+    reader.read_to_end(&mut out).expect("Failed to read from the chained reader.");
+
+    assert_eq!(out, b"one\ntwo");
+}
+
+#[test]
+fn from_chained_paths_reassembles_two_copies_of_the_test_document_with_a_boundary_separator() {
+    let path = std::fs::canonicalize("./.tests")
+        .expect("Failed to canonicalize the test directory path.")
+        .join("test_document.txt");
+
+    let chunks: Vec<Vec<u8>> = reader::FixedMemoryReader::<_, 24>::from_chained_paths([&path, &path], 128usize, b'\n')
+        .expect("Failed to open the test document.")
+        .into_chunks::<b'\n'>()
+        .collect();
+
+    let reassembled: Vec<u8> = chunks.into_iter().flatten().collect();
+
+    let single = std::fs::read(&path).expect("Failed to read the test document directly.");
+    let mut expected = single.clone();
+    // The test document already ends on a `\n`, so no synthetic separator is needed
+    // between the two copies.
+    expected.extend_from_slice(&single);
+
+    assert_eq!(reassembled, expected);
+}
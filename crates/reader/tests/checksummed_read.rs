@@ -0,0 +1,78 @@
+#![cfg(feature = "checksum")]
+
+use std::{fs, io};
+
+use reader::{ChecksummingReader, Crc32, XxHash64};
+
+const TEST_DIR: &str = "./.tests";
+const TEST_FILE: &str = "test_document.txt";
+
+fn get_test_document_path() -> io::Result<std::path::PathBuf> {
+    let path = fs::canonicalize(TEST_DIR).unwrap_or_else(|_| {
+        panic!(
+            "Failed to canonicalize the test directory path at '{path}'; does it exist?",
+            path = TEST_DIR
+        )
+    });
+
+    Ok(path.join(TEST_FILE))
+}
+
+#[test]
+fn checksumming_reader_matches_whether_read_in_one_go_or_in_small_chunks() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+
+    let mut whole = ChecksummingReader::<_, Crc32>::new(fs::File::open(&path).unwrap());
+    io::copy(&mut whole, &mut io::sink()).expect("Failed to read the whole file at once.");
+
+    let mut reader = reader::FixedMemoryReader::<_, 24>::from_read(
+        ChecksummingReader::<_, Crc32>::new(fs::File::open(&path).unwrap()),
+        64usize,
+    );
+    for _ in reader.iter::<b'\n'>() {}
+
+    assert_eq!(whole.hex_digest(), reader.get_ref().hex_digest());
+}
+
+#[test]
+fn checksumming_reader_notices_a_single_flipped_byte() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+    let mut original = fs::read(&path).expect("Failed to read the test document.");
+
+    let mut baseline = ChecksummingReader::<_, Crc32>::new(original.as_slice());
+    io::copy(&mut baseline, &mut io::sink()).unwrap();
+    let baseline_digest = baseline.hex_digest();
+
+    original[0] ^= 0xFF;
+    let mut tampered = ChecksummingReader::<_, Crc32>::new(original.as_slice());
+    io::copy(&mut tampered, &mut io::sink()).unwrap();
+
+    assert_ne!(baseline_digest, tampered.hex_digest());
+}
+
+#[test]
+fn xxhash64_and_crc32_disagree_on_digest_shape_but_both_detect_corruption() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+    let mut data = fs::read(&path).expect("Failed to read the test document.");
+
+    let mut crc = ChecksummingReader::<_, Crc32>::new(data.as_slice());
+    io::copy(&mut crc, &mut io::sink()).unwrap();
+    let mut xx = ChecksummingReader::<_, XxHash64>::new(data.as_slice());
+    io::copy(&mut xx, &mut io::sink()).unwrap();
+
+    assert_eq!(crc.hex_digest().len(), 8);
+    assert_eq!(xx.hex_digest().len(), 16);
+
+    let crc_before = crc.hex_digest();
+    let xx_before = xx.hex_digest();
+
+    let last = data.len() - 1;
+    data[last] ^= 0x01;
+    let mut crc_after = ChecksummingReader::<_, Crc32>::new(data.as_slice());
+    io::copy(&mut crc_after, &mut io::sink()).unwrap();
+    let mut xx_after = ChecksummingReader::<_, XxHash64>::new(data.as_slice());
+    io::copy(&mut xx_after, &mut io::sink()).unwrap();
+
+    assert_ne!(crc_before, crc_after.hex_digest());
+    assert_ne!(xx_before, xx_after.hex_digest());
+}
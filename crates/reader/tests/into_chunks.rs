@@ -0,0 +1,56 @@
+const TEST_DIR: &str = "./.tests";
+const TEST_FILE: &str = "test_document.txt";
+
+use std::{fs, io, thread};
+
+fn get_test_document_path() -> io::Result<std::path::PathBuf> {
+    let path = fs::canonicalize(TEST_DIR).unwrap_or_else(|_| {
+        panic!(
+            "Failed to canonicalize the test directory path at '{path}'; does it exist?",
+            path = TEST_DIR
+        )
+    });
+
+    Ok(path.join(TEST_FILE))
+}
+
+#[test]
+fn into_chunks_can_be_moved_into_a_thread() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+    let reader = reader::FixedMemoryReader::<_, 24>::from_path(path, 128usize)
+        .expect("Failed to open the test document.");
+
+    // The whole point of an owned iterator is that it can outlive the scope it was
+    // created in; moving it into a spawned thread is the sharpest test of that.
+    let total_lines = thread::spawn(move || {
+        reader
+            .into_chunks::<b'\n'>()
+            .map(|chunk| {
+                let chunk = std::str::from_utf8(&chunk).expect("Chunk was not valid UTF-8.").to_owned();
+                assert!(chunk.ends_with('\n'), "Chunk does not end with a newline: {:?}", chunk);
+                chunk.split_whitespace().count()
+            })
+            .sum::<usize>()
+    })
+    .join()
+    .expect("The reader thread panicked.");
+
+    assert_eq!(total_lines, 200);
+}
+
+#[test]
+fn into_chunks_with_separator_matches_the_const_generic_variant() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+
+    let by_const = reader::FixedMemoryReader::<_, 24>::from_path(&path, 64usize)
+        .expect("Failed to open the test document.")
+        .into_chunks::<b'\n'>()
+        .collect::<Vec<_>>();
+
+    let by_runtime = reader::FixedMemoryReader::<_, 24>::from_path(&path, 64usize)
+        .expect("Failed to open the test document.")
+        .into_chunks_with_separator(b'\n')
+        .collect::<Vec<_>>();
+
+    assert_eq!(by_const, by_runtime);
+}
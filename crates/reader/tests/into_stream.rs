@@ -0,0 +1,70 @@
+#![cfg(feature = "tokio")]
+
+const TEST_DIR: &str = "./.tests";
+const TEST_FILE: &str = "test_document.txt";
+
+use std::{fs, io};
+
+use futures_core::Stream;
+
+fn get_test_document_path() -> io::Result<std::path::PathBuf> {
+    let path = fs::canonicalize(TEST_DIR).unwrap_or_else(|_| {
+        panic!(
+            "Failed to canonicalize the test directory path at '{path}'; does it exist?",
+            path = TEST_DIR
+        )
+    });
+
+    Ok(path.join(TEST_FILE))
+}
+
+/// Poll a [`Stream`] to completion without pulling in a `StreamExt` dependency just
+/// for these tests.
+async fn collect<S: Stream<Item = io::Result<Vec<u8>>>>(stream: S) -> io::Result<Vec<Vec<u8>>> {
+    tokio::pin!(stream);
+
+    let mut chunks = Vec::new();
+    while let Some(chunk) = std::future::poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+        chunks.push(chunk?);
+    }
+
+    Ok(chunks)
+}
+
+#[tokio::test]
+async fn into_stream_yields_every_line_of_the_test_document() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+    let reader = reader::FixedMemoryReader::<_, 24>::from_path(path, 128usize)
+        .expect("Failed to open the test document.");
+
+    let chunks = collect(reader.into_stream::<b'\n'>())
+        .await
+        .expect("Failed to read from the stream.");
+
+    let total_lines: usize = chunks
+        .iter()
+        .map(|chunk| {
+            let chunk = std::str::from_utf8(chunk).expect("Chunk was not valid UTF-8.");
+            assert!(chunk.ends_with('\n'), "Chunk does not end with a newline: {:?}", chunk);
+            chunk.split_whitespace().count()
+        })
+        .sum();
+
+    assert_eq!(total_lines, 200);
+}
+
+#[tokio::test]
+async fn into_stream_reassembles_to_the_exact_file_contents() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+    let reader = reader::FixedMemoryReader::<_, 24>::from_path(&path, 37usize)
+        .expect("Failed to open the test document.");
+
+    let chunks = collect(reader.into_stream::<b'\n'>())
+        .await
+        .expect("Failed to read from the stream.");
+
+    let reassembled: Vec<u8> = chunks.into_iter().flatten().collect();
+    let expected = fs::read(&path).expect("Failed to read the test document directly.");
+
+    assert_eq!(reassembled, expected);
+}
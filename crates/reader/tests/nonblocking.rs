@@ -0,0 +1,144 @@
+#![cfg(feature = "tokio")]
+
+const TEST_DIR: &str = "./.tests";
+const TEST_FILE: &str = "test_document.txt";
+
+use std::{fs, io};
+
+use futures_core::Stream;
+
+fn get_test_document_path() -> io::Result<std::path::PathBuf> {
+    let path = fs::canonicalize(TEST_DIR).unwrap_or_else(|_| {
+        panic!(
+            "Failed to canonicalize the test directory path at '{path}'; does it exist?",
+            path = TEST_DIR
+        )
+    });
+
+    Ok(path.join(TEST_FILE))
+}
+
+/// Poll a [`Stream`] to completion without pulling in a `StreamExt` dependency just
+/// for these tests.
+async fn collect<S: Stream<Item = io::Result<Vec<u8>>>>(stream: S) -> io::Result<Vec<Vec<u8>>> {
+    tokio::pin!(stream);
+
+    let mut chunks = Vec::new();
+    while let Some(chunk) = std::future::poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+        chunks.push(chunk?);
+    }
+
+    Ok(chunks)
+}
+
+macro_rules! create_take_until_test {
+    (
+        $name:ident,
+        $chunk_size:literal,
+        $max_line:literal
+        $(,)?
+    ) => {
+        #[tokio::test]
+        async fn $name() {
+            let path = get_test_document_path().expect("Failed to get the test document.");
+
+            const CHUNK_SIZE: usize = $chunk_size;
+            assert!(
+                $max_line <= CHUNK_SIZE,
+                "The maximum line length is greater than the chunk size."
+            );
+            assert!(
+                $max_line > 11,
+                "The maximum line length is inappropriately small."
+            );
+            let mut reader = reader::AsyncFixedMemoryReader::<_, $max_line>::from_path(
+                path, CHUNK_SIZE,
+            )
+            .await
+            .expect("Failed to open the test document.");
+
+            let mut buffer = reader::utils::new_buffer(CHUNK_SIZE);
+
+            let mut total_lines = 0;
+            loop {
+                match reader.take_until(b'\n', &mut buffer).await {
+                    Ok(0) => break,
+                    Ok(len) => {
+                        let chunk = unsafe { String::from_utf8_unchecked(buffer[..len].to_vec()) };
+                        assert!(
+                            chunk.len() <= CHUNK_SIZE,
+                            "Line is longer than the chunk size: {:?}",
+                            chunk
+                        );
+                        assert!(
+                            chunk.ends_with('\n'),
+                            "Line does not end with a newline character: {:?}",
+                            chunk
+                        );
+                        chunk.split_whitespace().for_each(|line| {
+                            assert_eq!(line, "0123456789");
+                            total_lines += 1;
+                        })
+                    }
+                    Err(err) => {
+                        panic!("Failed to read from the test document: {}", err);
+                    }
+                }
+            }
+
+            assert_eq!(
+                total_lines, 200,
+                "Total lines read does not match the expected value."
+            );
+        }
+    };
+}
+
+// Chunk sizes that force `take_until` to carry an overflowing partial line across
+// more than one `read` from the underlying `AsyncRead`, mirroring the sync coverage
+// in `read_test_document.rs`.
+create_take_until_test!(take_until_64_12, 64, 12);
+create_take_until_test!(take_until_128_24, 128, 24);
+create_take_until_test!(take_until_256_48, 256, 48);
+create_take_until_test!(take_until_512_96, 512, 96);
+create_take_until_test!(take_until_4096_12, 4096, 12);
+
+#[tokio::test]
+async fn chunks_yields_every_line_of_the_test_document() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+    let reader = reader::AsyncFixedMemoryReader::<_, 24>::from_path(path, 128usize)
+        .await
+        .expect("Failed to open the test document.");
+
+    let chunks = collect(reader.chunks::<b'\n'>())
+        .await
+        .expect("Failed to read from the stream.");
+
+    let total_lines: usize = chunks
+        .iter()
+        .map(|chunk| {
+            let chunk = std::str::from_utf8(chunk).expect("Chunk was not valid UTF-8.");
+            assert!(chunk.ends_with('\n'), "Chunk does not end with a newline: {:?}", chunk);
+            chunk.split_whitespace().count()
+        })
+        .sum();
+
+    assert_eq!(total_lines, 200);
+}
+
+#[tokio::test]
+async fn chunks_reassembles_to_the_exact_file_contents() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+    let reader = reader::AsyncFixedMemoryReader::<_, 24>::from_path(&path, 37usize)
+        .await
+        .expect("Failed to open the test document.");
+
+    let chunks = collect(reader.chunks::<b'\n'>())
+        .await
+        .expect("Failed to read from the stream.");
+
+    let reassembled: Vec<u8> = chunks.into_iter().flatten().collect();
+    let expected = fs::read(&path).expect("Failed to read the test document directly.");
+
+    assert_eq!(reassembled, expected);
+}
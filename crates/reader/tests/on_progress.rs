@@ -0,0 +1,43 @@
+const TEST_DIR: &str = "./.tests";
+const TEST_FILE: &str = "test_document.txt";
+
+use std::{
+    fs, io,
+    sync::{Arc, Mutex},
+};
+
+fn get_test_document_path() -> io::Result<std::path::PathBuf> {
+    let path = fs::canonicalize(TEST_DIR).unwrap_or_else(|_| {
+        panic!(
+            "Failed to canonicalize the test directory path at '{path}'; does it exist?",
+            path = TEST_DIR
+        )
+    });
+
+    Ok(path.join(TEST_FILE))
+}
+
+#[test]
+fn on_progress_reports_cumulative_bytes_read_up_to_the_total_size() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+    let reader = reader::FixedMemoryReader::<_, 24>::from_path(&path, 64usize)
+        .expect("Failed to open the test document.");
+    let total = reader.size().expect("Failed to read the file's size.");
+
+    let reports: Arc<Mutex<Vec<(usize, usize)>>> = Arc::new(Mutex::new(Vec::new()));
+    let reports_for_callback = Arc::clone(&reports);
+
+    let mut reader = reader.on_progress(total, move |bytes_read, total| {
+        reports_for_callback.lock().unwrap().push((bytes_read, total));
+    });
+
+    let chunk_count = reader.iter::<b'\n'>().count();
+    let reports = reports.lock().unwrap();
+
+    assert_eq!(reports.len(), chunk_count);
+    assert!(reports.iter().all(|&(_, reported_total)| reported_total == total));
+
+    let bytes_read: Vec<usize> = reports.iter().map(|&(bytes_read, _)| bytes_read).collect();
+    assert!(bytes_read.windows(2).all(|pair| pair[0] < pair[1]), "bytes_read should strictly increase");
+    assert_eq!(*bytes_read.last().unwrap(), total);
+}
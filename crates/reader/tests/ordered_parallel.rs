@@ -0,0 +1,98 @@
+const TEST_DIR: &str = "./.tests";
+const TEST_FILE: &str = "test_document.txt";
+
+use std::{
+    fs, io,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+fn get_test_document_path() -> io::Result<std::path::PathBuf> {
+    let path = fs::canonicalize(TEST_DIR).unwrap_or_else(|_| {
+        panic!(
+            "Failed to canonicalize the test directory path at '{path}'; does it exist?",
+            path = TEST_DIR
+        )
+    });
+
+    Ok(path.join(TEST_FILE))
+}
+
+#[test]
+fn ordered_parallel_restores_order_of_chunks_read_from_the_test_document() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+    let reader = reader::FixedMemoryReader::<_, 24>::from_path(path, 128usize)
+        .expect("Failed to open the test document.");
+
+    let expected: Vec<Vec<u8>> = reader::FixedMemoryReader::<_, 24>::from_path(
+        get_test_document_path().expect("Failed to get the test document."),
+        128usize,
+    )
+    .expect("Failed to open the test document.")
+    .into_chunks::<b'\n'>()
+    .collect();
+
+    // Deliberately make earlier-numbered chunks take longer than later ones, so the
+    // worker threads finish them out of order; the combiner still has to hand them
+    // back in submission order.
+    let processed: Vec<Vec<u8>> = reader::into_ordered_parallel(reader.into_chunks::<b'\n'>(), 4, |chunk| {
+        thread::sleep(Duration::from_micros((200 - chunk.len() as u64 % 200) * 20));
+        chunk
+    })
+    .collect();
+
+    assert_eq!(processed, expected);
+}
+
+#[test]
+fn ordered_parallel_runs_work_across_more_than_one_thread() {
+    let seen_threads: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+    let seen_threads_for_workers = Arc::clone(&seen_threads);
+
+    let items: Vec<usize> = (0..64).collect();
+    let results: Vec<usize> = reader::into_ordered_parallel(items.clone(), 8, move |value| {
+        seen_threads_for_workers.fetch_add(1, Ordering::Relaxed);
+        thread::sleep(Duration::from_micros((64 - value as u64) * 50));
+        value * 2
+    })
+    .collect();
+
+    let expected: Vec<usize> = items.iter().map(|value| value * 2).collect();
+    assert_eq!(results, expected);
+    assert_eq!(seen_threads.load(Ordering::Relaxed), items.len());
+}
+
+#[test]
+fn dropping_an_ordered_parallel_iterator_mid_stream_does_not_hang() {
+    let items: Vec<usize> = (0..32).collect();
+    let mut iter = reader::into_ordered_parallel(items, 4, |value| {
+        thread::sleep(Duration::from_millis(1));
+        value
+    });
+
+    // Only pull a couple of values before dropping; the feeder/worker threads must
+    // still wind down cleanly rather than blocking `drop` forever.
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next(), Some(1));
+    drop(iter);
+}
+
+#[test]
+#[should_panic(expected = "the chunk processor panicked")]
+fn a_panic_in_process_is_resumed_on_the_consumer_instead_of_truncating_the_output() {
+    let items: Vec<usize> = (0..16).collect();
+    let iter = reader::into_ordered_parallel(items, 4, |value| {
+        if value == 15 {
+            panic!("the chunk processor panicked");
+        }
+        value
+    });
+
+    // Silently ending early would look like a successful, if short, export; the
+    // panic must reach this thread instead.
+    let _: Vec<usize> = iter.collect();
+}
@@ -0,0 +1,73 @@
+use std::io::{Cursor, Read};
+
+#[test]
+fn range_reader_snaps_a_misaligned_range_to_whole_lines() {
+    let data = b"one\ntwo\nthree\nfour\nfive\n".to_vec();
+    // `start` (5) lands inside "two"; `end` (15) lands inside "three". The range
+    // should discard the partial "wo" and extend past "three" to the end of "four".
+    let mut reader = reader::RangeReader::new(Cursor::new(data), b'\n', 5, 15).unwrap();
+
+    let mut out = Vec::new();
+    reader.read_to_end(&mut out).expect("Failed to read from the range reader.");
+
+    assert_eq!(out, b"three\nfour\n");
+}
+
+#[test]
+fn range_reader_starting_at_zero_keeps_the_first_line_intact() {
+    let data = b"one\ntwo\nthree\nfour\nfive\n".to_vec();
+    // `end` (6) lands inside "two", so the range should extend through it.
+    let mut reader = reader::RangeReader::new(Cursor::new(data), b'\n', 0, 6).unwrap();
+
+    let mut out = Vec::new();
+    reader.read_to_end(&mut out).expect("Failed to read from the range reader.");
+
+    assert_eq!(out, b"one\ntwo\n");
+}
+
+#[test]
+fn range_reader_stops_at_eof_when_end_exceeds_the_source_length() {
+    let data = b"one\ntwo\nthree\n".to_vec();
+    let mut reader = reader::RangeReader::new(Cursor::new(data), b'\n', 4, 1_000).unwrap();
+
+    let mut out = Vec::new();
+    reader.read_to_end(&mut out).expect("Failed to read from the range reader.");
+
+    assert_eq!(out, b"two\nthree\n");
+}
+
+#[test]
+fn adjacent_ranges_that_split_exactly_on_a_line_boundary_do_not_drop_or_duplicate_a_line() {
+    let data = b"one\ntwo\nthree\nfour\n".to_vec();
+    // "one\ntwo\n" is 8 bytes; splitting exactly there must not drop "three" (if the
+    // second range wrongly discards it as a partial prefix) nor duplicate it (if the
+    // first range wrongly re-reads it).
+    let mut first = reader::RangeReader::new(Cursor::new(data.clone()), b'\n', 0, 8).unwrap();
+    let mut second = reader::RangeReader::new(Cursor::new(data), b'\n', 8, 19).unwrap();
+
+    let mut first_out = Vec::new();
+    first.read_to_end(&mut first_out).expect("Failed to read the first range.");
+    let mut second_out = Vec::new();
+    second.read_to_end(&mut second_out).expect("Failed to read the second range.");
+
+    assert_eq!(first_out, b"one\ntwo\n");
+    assert_eq!(second_out, b"three\nfour\n");
+}
+
+#[test]
+fn from_path_range_reads_only_the_requested_lines_of_the_test_document() {
+    let path = std::fs::canonicalize("./.tests")
+        .expect("Failed to canonicalize the test directory path.")
+        .join("test_document.txt");
+
+    // Each line is "0123456789\n" (11 bytes); request a range landing mid-line-2
+    // (25) through mid-line-4 (50), which should snap to exactly lines 3 and 4.
+    let chunks: Vec<Vec<u8>> = reader::FixedMemoryReader::<_, 24>::from_path_range(&path, 128usize, b'\n', 25, 50)
+        .expect("Failed to open the test document.")
+        .into_chunks::<b'\n'>()
+        .collect();
+
+    let reassembled: Vec<u8> = chunks.into_iter().flatten().collect();
+
+    assert_eq!(reassembled, b"0123456789\n0123456789\n");
+}
@@ -0,0 +1,92 @@
+const TEST_DIR: &str = "./.tests";
+const TEST_FILE: &str = "test_document.txt";
+
+use std::{fs, io};
+
+fn get_test_document_path() -> io::Result<std::path::PathBuf> {
+    let path = fs::canonicalize(TEST_DIR).unwrap_or_else(|_| {
+        panic!(
+            "Failed to canonicalize the test directory path at '{path}'; does it exist?",
+            path = TEST_DIR
+        )
+    });
+
+    Ok(path.join(TEST_FILE))
+}
+
+macro_rules! create_test {
+    (
+        $name:ident,
+        $chunk_size:literal,
+        $max_line:literal
+        $(,)?
+    ) => {
+        #[test]
+        fn $name() {
+            let path = get_test_document_path().expect("Failed to get the test document.");
+
+            const CHUNK_SIZE: usize = $chunk_size;
+            let mut reader =
+                reader::FixedMemoryReader::<_, $max_line>::from_read_ahead_path(path, CHUNK_SIZE)
+                    .expect("Failed to open the test document.");
+
+            let mut buffer = reader::utils::new_buffer(CHUNK_SIZE);
+
+            let mut total_lines = 0;
+            loop {
+                match reader.take_until(b'\n', &mut buffer) {
+                    Ok(0) => break,
+                    Ok(len) => {
+                        let chunk = std::str::from_utf8(&buffer[..len]).expect("Chunk was not valid UTF-8.");
+                        assert!(
+                            chunk.ends_with('\n'),
+                            "Line does not end with a newline character: {:?}",
+                            chunk
+                        );
+                        chunk.split_whitespace().for_each(|line| {
+                            assert_eq!(line, "0123456789");
+                            total_lines += 1;
+                        })
+                    }
+                    Err(err) => panic!("Failed to read from the test document: {}", err),
+                }
+            }
+
+            assert_eq!(
+                total_lines, 200,
+                "Total lines read does not match the expected value."
+            );
+        }
+    };
+}
+
+create_test!(read_ahead_document_64_12, 64, 12);
+create_test!(read_ahead_document_37_12, 37, 12);
+create_test!(read_ahead_document_4096_12, 4096, 12);
+
+#[test]
+fn dropping_a_reader_mid_stream_does_not_hang() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+    let file = fs::File::open(path).expect("Failed to open the test document.");
+
+    let mut reader = reader::ReadAheadReader::with_chunk_size(file, 16);
+    let mut buffer = [0u8; 4];
+
+    // Read a handful of bytes, then drop the reader while the background thread is
+    // very likely still blocked trying to send its next chunk; `Drop` must not hang.
+    io::Read::read(&mut reader, &mut buffer).expect("Failed to read from the reader.");
+    drop(reader);
+}
+
+#[test]
+fn yields_the_exact_bytes_of_the_file() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+    let file = fs::File::open(&path).expect("Failed to open the test document.");
+
+    let mut reader = reader::ReadAheadReader::with_chunk_size(file, 17);
+    let mut collected = Vec::new();
+    io::Read::read_to_end(&mut reader, &mut collected).expect("Failed to read from the reader.");
+
+    let expected = fs::read(&path).expect("Failed to read the test document directly.");
+    assert_eq!(collected, expected);
+}
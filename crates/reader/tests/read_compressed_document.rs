@@ -0,0 +1,83 @@
+#![cfg(feature = "compression")]
+
+const TEST_DIR: &str = "./.tests";
+const TEST_FILE: &str = "test_document.txt.gz";
+
+use std::{fs, io};
+
+/// Path to the gzip-compressed copy of the plain test document.
+fn get_compressed_document_path() -> io::Result<std::path::PathBuf> {
+    let path = fs::canonicalize(TEST_DIR).unwrap_or_else(|_| {
+        panic!(
+            "Failed to canonicalize the test directory path at '{path}'; does it exist?",
+            path = TEST_DIR
+        )
+    });
+
+    Ok(path.join(TEST_FILE))
+}
+
+#[test]
+fn read_compressed_document_is_decompressed_transparently() {
+    const CHUNK_SIZE: usize = 128;
+    const MAX_LINE: usize = 24;
+
+    let path = get_compressed_document_path().expect("Failed to get the compressed test document.");
+    let mut reader = reader::FixedMemoryReader::<_, MAX_LINE>::from_compressed_path(path, CHUNK_SIZE)
+        .expect("Failed to open the compressed test document.");
+
+    let mut buffer = reader::utils::new_buffer(CHUNK_SIZE);
+
+    let mut total_lines = 0;
+    loop {
+        match reader.take_until(b'\n', &mut buffer) {
+            Ok(0) => break,
+            Ok(len) => {
+                let chunk = unsafe { String::from_utf8_unchecked(buffer[..len].to_vec()) };
+                chunk.split_whitespace().for_each(|line| {
+                    assert_eq!(line, "0123456789");
+                    total_lines += 1;
+                })
+            }
+            Err(err) => {
+                eprintln!("Failed to read from the compressed test document: {}", err);
+                break;
+            }
+        }
+    }
+
+    assert_eq!(
+        total_lines, 200,
+        "Total lines read does not match the expected value."
+    );
+}
+
+#[test]
+fn uncompressed_extension_is_read_as_is() {
+    const CHUNK_SIZE: usize = 128;
+    const MAX_LINE: usize = 24;
+
+    let path = fs::canonicalize(TEST_DIR)
+        .expect("Failed to canonicalize the test directory path.")
+        .join("test_document.txt");
+
+    let mut reader = reader::FixedMemoryReader::<_, MAX_LINE>::from_compressed_path(path, CHUNK_SIZE)
+        .expect("Failed to open the uncompressed test document.");
+
+    let mut buffer = reader::utils::new_buffer(CHUNK_SIZE);
+    let mut total_lines = 0;
+    while let Ok(len) = reader.take_until(b'\n', &mut buffer) {
+        if len == 0 {
+            break;
+        }
+        let chunk = unsafe { String::from_utf8_unchecked(buffer[..len].to_vec()) };
+        chunk
+            .split_whitespace()
+            .for_each(|_| total_lines += 1);
+    }
+
+    assert_eq!(
+        total_lines, 200,
+        "Total lines read does not match the expected value."
+    );
+}
@@ -0,0 +1,105 @@
+const TEST_DIR: &str = "./.tests";
+const TEST_FILE: &str = "test_document.txt";
+
+use std::{fs, io};
+
+/// Path to the test document, memory-mapped by every test in this file.
+fn get_test_document_path() -> io::Result<std::path::PathBuf> {
+    let path = fs::canonicalize(TEST_DIR).unwrap_or_else(|_| {
+        panic!(
+            "Failed to canonicalize the test directory path at '{path}'; does it exist?",
+            path = TEST_DIR
+        )
+    });
+
+    Ok(path.join(TEST_FILE))
+}
+
+macro_rules! create_test {
+    (
+        $name:ident,
+        $chunk_size:literal
+        $(,)?
+    ) => {
+        #[test]
+        fn $name() {
+            let path = get_test_document_path().expect("Failed to get the test document.");
+            let reader = reader::MmapReader::from_path(path)
+                .expect("Failed to memory-map the test document.")
+                .with_chunk_size($chunk_size);
+
+            let mut total_lines = 0;
+            for chunk in reader.iter::<b'\n'>() {
+                let chunk = std::str::from_utf8(chunk).expect("Chunk was not valid UTF-8.");
+                assert!(
+                    chunk.ends_with('\n'),
+                    "Chunk does not end with a newline character: {:?}",
+                    chunk
+                );
+                chunk.split_whitespace().for_each(|line| {
+                    assert_eq!(line, "0123456789");
+                    total_lines += 1;
+                })
+            }
+
+            assert_eq!(
+                total_lines, 200,
+                "Total lines read does not match the expected value."
+            );
+        }
+    };
+}
+
+create_test!(read_mmap_document_64, 64);
+create_test!(read_mmap_document_128, 128);
+create_test!(read_mmap_document_256, 256);
+create_test!(read_mmap_document_4096, 4096);
+
+#[test]
+fn with_chunks_may_split_a_line_across_chunk_boundaries() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+    // Each line is 11 bytes ("0123456789\n"); a chunk size that isn't a multiple of
+    // that is certain to split at least one line if chunking is not separator-aware.
+    let reader = reader::MmapReader::from_path(path)
+        .expect("Failed to memory-map the test document.")
+        .with_chunk_size(5usize);
+
+    let reassembled: Vec<u8> = reader.with_chunks().flatten().copied().collect();
+    let expected = fs::read(
+        fs::canonicalize(TEST_DIR).unwrap().join(TEST_FILE),
+    )
+    .expect("Failed to read the test document directly.");
+
+    assert_eq!(reassembled, expected, "with_chunks did not reconstruct the file exactly.");
+}
+
+#[test]
+fn iter_with_separator_matches_the_const_generic_variant() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+
+    let by_const = reader::MmapReader::from_path(&path)
+        .expect("Failed to memory-map the test document.")
+        .with_chunk_size(128usize)
+        .iter::<b'\n'>()
+        .map(<[u8]>::to_vec)
+        .collect::<Vec<_>>();
+
+    let by_runtime = reader::MmapReader::from_path(&path)
+        .expect("Failed to memory-map the test document.")
+        .with_chunk_size(128usize)
+        .iter_with_separator(b'\n')
+        .map(<[u8]>::to_vec)
+        .collect::<Vec<_>>();
+
+    assert_eq!(by_const, by_runtime);
+}
+
+#[test]
+fn size_matches_the_file_length_on_disk() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+    let file_length = fs::metadata(&path).expect("Failed to stat the test document.").len() as usize;
+
+    let reader = reader::MmapReader::from_path(path).expect("Failed to memory-map the test document.");
+
+    assert_eq!(reader.size(), file_length);
+}
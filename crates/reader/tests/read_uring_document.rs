@@ -0,0 +1,88 @@
+#![cfg(all(target_os = "linux", feature = "io-uring"))]
+
+const TEST_DIR: &str = "./.tests";
+const TEST_FILE: &str = "test_document.txt";
+
+use std::{fs, io};
+
+fn get_test_document_path() -> io::Result<std::path::PathBuf> {
+    let path = fs::canonicalize(TEST_DIR).unwrap_or_else(|_| {
+        panic!(
+            "Failed to canonicalize the test directory path at '{path}'; does it exist?",
+            path = TEST_DIR
+        )
+    });
+
+    Ok(path.join(TEST_FILE))
+}
+
+/// The kernel `io_uring` itself may be unavailable (older kernel, or disabled by a
+/// container's seccomp policy) - in which case [`reader::UringFile::from_path`]
+/// surfaces that as an `ENOSYS` [`io::Error`], which every test below tolerates as a
+/// pass rather than a failure, since it reflects the host, not this crate.
+fn is_io_uring_unsupported(err: &io::Error) -> bool {
+    const ENOSYS: i32 = 38; // Same numeric value on every Linux architecture.
+    err.raw_os_error() == Some(ENOSYS)
+}
+
+#[test]
+fn read_uring_document_matches_the_fixed_memory_reader() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+
+    let uring_file = match reader::UringFile::from_path(&path) {
+        Ok(uring_file) => uring_file,
+        Err(err) if is_io_uring_unsupported(&err) => return,
+        Err(err) => panic!("Failed to open the test document via io_uring: {}", err),
+    };
+
+    let mut reader = reader::FixedMemoryReader::<_, 24>::from_read(uring_file, 128usize);
+    let mut buffer = reader::utils::new_buffer(128usize);
+
+    let mut total_lines = 0;
+    loop {
+        match reader.take_until(b'\n', &mut buffer) {
+            Ok(0) => break,
+            Ok(len) => {
+                let chunk = std::str::from_utf8(&buffer[..len]).expect("Chunk was not valid UTF-8.");
+                chunk.split_whitespace().for_each(|line| {
+                    assert_eq!(line, "0123456789");
+                    total_lines += 1;
+                })
+            }
+            Err(err) => panic!("Failed to read from the test document: {}", err),
+        }
+    }
+
+    assert_eq!(total_lines, 200);
+}
+
+#[test]
+fn read_uring_document_with_a_chunk_size_smaller_than_the_file() {
+    let path = get_test_document_path().expect("Failed to get the test document.");
+
+    let uring_file = match reader::UringFile::from_path(&path).map(|f| f.with_chunk_size(37)) {
+        Ok(uring_file) => uring_file,
+        Err(err) if is_io_uring_unsupported(&err) => return,
+        Err(err) => panic!("Failed to open the test document via io_uring: {}", err),
+    };
+
+    let mut reader = reader::FixedMemoryReader::<_, 24>::from_read(uring_file, 128usize);
+    let mut buffer = reader::utils::new_buffer(128usize);
+
+    let mut total_lines = 0;
+    loop {
+        match reader.take_until(b'\n', &mut buffer) {
+            Ok(0) => break,
+            Ok(len) => {
+                let chunk = std::str::from_utf8(&buffer[..len]).expect("Chunk was not valid UTF-8.");
+                chunk.split_whitespace().for_each(|line| {
+                    assert_eq!(line, "0123456789");
+                    total_lines += 1;
+                })
+            }
+            Err(err) => panic!("Failed to read from the test document: {}", err),
+        }
+    }
+
+    assert_eq!(total_lines, 200);
+}
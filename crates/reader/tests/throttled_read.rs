@@ -0,0 +1,48 @@
+use std::{io::Read, time::Instant};
+
+#[test]
+fn throttled_reader_caps_throughput_to_roughly_the_configured_rate() {
+    let data = vec![0u8; 50_000];
+    let mut reader = reader::ThrottledReader::new(data.as_slice(), 100_000);
+
+    let started = Instant::now();
+    let mut out = Vec::new();
+    reader.read_to_end(&mut out).expect("Failed to read from the throttled reader.");
+    let elapsed = started.elapsed();
+
+    assert_eq!(out, data);
+    // 50_000 bytes at 100_000 bytes/sec should take at least ~0.4s; give plenty of
+    // slack below that to avoid flaking on a slow CI box.
+    assert!(elapsed.as_secs_f64() >= 0.4, "expected throttling to slow the read down, took {elapsed:?}");
+}
+
+#[test]
+fn throttled_reader_with_zero_rate_does_not_throttle() {
+    let data = vec![0u8; 50_000];
+    let mut reader = reader::ThrottledReader::new(data.as_slice(), 0);
+
+    let started = Instant::now();
+    let mut out = Vec::new();
+    reader.read_to_end(&mut out).expect("Failed to read from the throttled reader.");
+    let elapsed = started.elapsed();
+
+    assert_eq!(out, data);
+    assert!(elapsed.as_secs_f64() < 0.4, "expected no throttling with a zero rate, took {elapsed:?}");
+}
+
+#[test]
+fn from_throttled_path_yields_the_exact_bytes_of_the_file() {
+    let path = std::fs::canonicalize("./.tests")
+        .expect("Failed to canonicalize the test directory path.")
+        .join("test_document.txt");
+
+    let chunks: Vec<Vec<u8>> = reader::FixedMemoryReader::<_, 24>::from_throttled_path(&path, 128usize, 0)
+        .expect("Failed to open the test document.")
+        .into_chunks::<b'\n'>()
+        .collect();
+
+    let reassembled: Vec<u8> = chunks.into_iter().flatten().collect();
+    let expected = std::fs::read(&path).expect("Failed to read the test document directly.");
+
+    assert_eq!(reassembled, expected);
+}
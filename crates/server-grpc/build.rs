@@ -0,0 +1,11 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo::rerun-if-changed=proto/search.proto");
+
+    // Avoids depending on `protoc` being installed on the machine building this
+    // crate; vendors a prebuilt binary instead.
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+
+    tonic_build::compile_protos("proto/search.proto")?;
+
+    Ok(())
+}
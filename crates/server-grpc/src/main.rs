@@ -0,0 +1,283 @@
+//! A gRPC front end for searching an index collection, as an alternative
+//! integration point to the cgo FFI in `crates/parse-ffi`.
+use std::path::Path;
+
+use clap::Parser;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{transport::Server, Request, Response, Status};
+
+use rockyou2024::config;
+use rockyou2024::models::{IndexCollection, IndexOf};
+use rockyou2024::search::{CancellationToken, SearchStyle};
+use rockyou2024::string::hex_escape_unprintable;
+
+const LOG_TARGET: &str = "grpc";
+
+/// Bounds the number of buffered messages per streaming response, so a slow client
+/// applies backpressure to the search instead of the whole result set piling up in
+/// memory on the server.
+const STREAM_BUFFER: usize = 32;
+
+mod proto {
+    tonic::include_proto!("rockyou2024.search");
+}
+
+use proto::search_server::{Search, SearchServer};
+use proto::{CountResult, IndexResult, IndicesOfRequest, SearchRequest, SearchResult};
+
+/// Command line arguments.
+#[derive(Parser, Debug, Clone)]
+struct CliArgs {
+    /// The address to listen on.
+    #[arg(short, long, default_value = "[::1]:50051")]
+    addr: String,
+}
+
+/// Parse a search style string into a [`SearchStyle`], mirroring the same accepted
+/// values as `crates/parse-ffi`'s `as_search_string`/`find_lines_in_index_collection`.
+// `tonic::Status` is a large, framework-mandated error type; returning it by value
+// here matches the RPC handlers that call this.
+#[allow(clippy::result_large_err)]
+fn parse_search_style(search_style: &str) -> Result<SearchStyle, Status> {
+    match search_style {
+        "strict" => Ok(SearchStyle::Strict),
+        "case-insensitive" => Ok(SearchStyle::CaseInsensitive { unicode: false }),
+        "case-insensitive-unicode" => Ok(SearchStyle::CaseInsensitive { unicode: true }),
+        "fuzzy" => Ok(SearchStyle::Fuzzy {
+            keyboard_adjacent: false,
+        }),
+        "fuzzy-keyboard" => Ok(SearchStyle::Fuzzy {
+            keyboard_adjacent: true,
+        }),
+        "wildcard" => Ok(SearchStyle::Wildcard),
+        "exact" => Ok(SearchStyle::Exact),
+        "phonetic" => Ok(SearchStyle::Phonetic),
+        s if s.starts_with("edit-distance-") => {
+            s["edit-distance-".len()..]
+                .parse::<usize>()
+                .map(SearchStyle::edit_distance)
+                .map_err(|_| {
+                    Status::invalid_argument(format!(
+                        "Could not parse the edit distance out of search style '{search_style}'."
+                    ))
+                })
+        }
+        _ => Err(Status::invalid_argument(format!(
+            "Unknown search style '{search_style}'."
+        ))),
+    }
+}
+
+/// Open the index collection at `dir`, or an error [`Status`] if `dir` is not a
+/// directory, or if it was built with different `LENGTH`/`DEPTH` parameters than this
+/// binary was compiled with, which would otherwise silently return no results for
+/// every search against it.
+#[allow(clippy::result_large_err)]
+fn open_index_collection(
+    dir: &str,
+) -> Result<IndexCollection<{ config::INDEX_LENGTH }, { config::INDEX_DEPTH }>, Status> {
+    let path = Path::new(dir);
+    if !path.is_dir() {
+        return Err(Status::invalid_argument(format!(
+            "'{dir}' is not a directory."
+        )));
+    }
+
+    IndexCollection::open_validated(path.to_path_buf())
+        .map_err(|err| Status::invalid_argument(format!("Failed to open '{dir}': {err}.")))
+}
+
+/// Cancels the [`CancellationToken`] it holds when dropped.
+///
+/// Held across an `.await` point in an RPC handler, this lets a search running on a
+/// blocking task be given up on as soon as tonic drops the handler's future — which
+/// happens when the client disconnects before a response is ready.
+struct CancelOnDrop(CancellationToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+#[derive(Default)]
+struct SearchService;
+
+#[tonic::async_trait]
+impl Search for SearchService {
+    type SearchStream = ReceiverStream<Result<SearchResult, Status>>;
+
+    async fn search(
+        &self,
+        request: Request<SearchRequest>,
+    ) -> Result<Response<Self::SearchStream>, Status> {
+        let SearchRequest {
+            dir,
+            query,
+            search_style,
+            timeout_ms,
+            hex_escape,
+        } = request.into_inner();
+
+        let search_style = parse_search_style(&search_style)?;
+        let index_collection = open_index_collection(&dir)?;
+        let deadline = timeout_ms
+            .filter(|&timeout_ms| timeout_ms > 0)
+            .map(|timeout_ms| std::time::Instant::now() + std::time::Duration::from_millis(timeout_ms));
+
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        tokio::task::spawn_blocking(move || {
+            for line in index_collection.find_lines_containing_iter(&query, search_style) {
+                if deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline) {
+                    let _ = tx.blocking_send(Ok(SearchResult {
+                        line: String::new(),
+                        truncated: Some(true),
+                    }));
+                    return;
+                }
+
+                let line = if hex_escape { hex_escape_unprintable(&line) } else { line };
+
+                if tx.blocking_send(Ok(SearchResult { line, truncated: None })).is_err() {
+                    // The client disconnected; stop scanning the remaining index files.
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn count(&self, request: Request<SearchRequest>) -> Result<Response<CountResult>, Status> {
+        let SearchRequest {
+            dir,
+            query,
+            search_style,
+            timeout_ms,
+            hex_escape: _,
+        } = request.into_inner();
+
+        let search_style = parse_search_style(&search_style)?;
+        let index_collection = open_index_collection(&dir)?;
+        let timeout = timeout_ms
+            .filter(|&timeout_ms| timeout_ms > 0)
+            .map(std::time::Duration::from_millis);
+
+        // Give up on the search as soon as either the deadline elapses or the client
+        // disconnects, whichever comes first; either way, whatever had already been
+        // found is still returned, marked as truncated.
+        let cancellation = CancellationToken::new();
+        let _cancel_on_disconnect = CancelOnDrop(cancellation.clone());
+
+        let mut search_task = tokio::task::spawn_blocking({
+            let cancellation = cancellation.clone();
+            move || index_collection.find_lines_containing_with_cancellation(&query, search_style, cancellation)
+        });
+
+        let found = match timeout {
+            Some(timeout) => {
+                tokio::select! {
+                    result = &mut search_task => result,
+                    _ = tokio::time::sleep(timeout) => {
+                        cancellation.cancel();
+                        (&mut search_task).await
+                    }
+                }
+            }
+            None => (&mut search_task).await,
+        }
+        .map_err(|err| Status::internal(format!("The search task panicked: {err}")))?;
+
+        Ok(Response::new(CountResult {
+            count: found.lines.len() as u64,
+            truncated: found.truncated,
+        }))
+    }
+
+    type IndicesOfStream = ReceiverStream<Result<IndexResult, Status>>;
+
+    async fn indices_of(
+        &self,
+        request: Request<IndicesOfRequest>,
+    ) -> Result<Response<Self::IndicesOfStream>, Status> {
+        let IndicesOfRequest { input } = request.into_inner();
+
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        tokio::task::spawn_blocking(move || {
+            let indices = IndexOf::<{ config::INDEX_LENGTH }, { config::INDEX_DEPTH }>::from(
+                input.as_bytes(),
+            );
+            for index in indices {
+                if tx.blocking_send(Ok(IndexResult { index })).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = CliArgs::parse();
+    let addr = args.addr.parse()?;
+
+    rockyou2024::info!(target: LOG_TARGET, "Listening on {addr}");
+
+    Server::builder()
+        .add_service(SearchServer::new(SearchService))
+        .serve(addr)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_search_style_accepts_every_documented_style() {
+        for style in [
+            "strict",
+            "case-insensitive",
+            "case-insensitive-unicode",
+            "fuzzy",
+            "fuzzy-keyboard",
+            "wildcard",
+            "exact",
+            "phonetic",
+        ] {
+            assert!(parse_search_style(style).is_ok(), "Failed to parse '{style}'.");
+        }
+    }
+
+    #[test]
+    fn parse_search_style_rejects_an_unknown_style() {
+        let err = parse_search_style("not-a-real-style").expect_err("Expected an error.");
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("Unknown search style"));
+    }
+
+    #[test]
+    fn parse_search_style_clamps_edit_distance_to_a_small_bounded_maximum() {
+        let style = parse_search_style("edit-distance-9999").expect("Failed to parse.");
+        assert_eq!(
+            style,
+            SearchStyle::EditDistance {
+                max_distance: rockyou2024::search::MAX_EDIT_DISTANCE,
+            },
+            "parse_search_style must route a caller-supplied distance through \
+             SearchStyle::edit_distance so it is clamped, not build EditDistance directly."
+        );
+    }
+
+    #[test]
+    fn parse_search_style_rejects_a_non_numeric_edit_distance() {
+        let err = parse_search_style("edit-distance-abc").expect_err("Expected an error.");
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("Could not parse the edit distance"));
+    }
+}
@@ -0,0 +1,167 @@
+//! A blocking Rust client for the Unix-domain-socket query protocol served by
+//! `server-uds`.
+use std::io::{self, BufReader, BufWriter};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+
+use crate::protocol::{self, Request, Response};
+
+/// A connection to a `server-uds` instance over a Unix domain socket.
+///
+/// Requests are not pipelined: each call blocks until its response arrives.
+/// Open one [`UdsClient`] per concurrent caller.
+pub struct UdsClient {
+    reader: BufReader<UnixStream>,
+    writer: BufWriter<UnixStream>,
+}
+
+impl UdsClient {
+    /// Connect to the `server-uds` instance listening at `path`.
+    pub fn connect(path: impl AsRef<Path>) -> io::Result<Self> {
+        let stream = UnixStream::connect(path)?;
+        let writer = BufWriter::new(stream.try_clone()?);
+        let reader = BufReader::new(stream);
+
+        Ok(Self { reader, writer })
+    }
+
+    fn call(&mut self, request: Request) -> io::Result<Response> {
+        protocol::write_request(&mut self.writer, &request)?;
+        protocol::read_response(&mut self.reader)
+    }
+
+    /// Find the lines in the index collection at `dir` that match `query`.
+    pub fn search(
+        &mut self,
+        dir: &str,
+        query: &str,
+        search_style: &str,
+    ) -> io::Result<Vec<String>> {
+        self.search_with_timeout(dir, query, search_style, None).map(|(lines, _)| lines)
+    }
+
+    /// Find the lines in the index collection at `dir` that match `query`, aborting
+    /// the search once `timeout_ms` milliseconds have elapsed (if given and
+    /// nonzero). The returned `bool` is `true` if the timeout cut the search short.
+    pub fn search_with_timeout(
+        &mut self,
+        dir: &str,
+        query: &str,
+        search_style: &str,
+        timeout_ms: Option<u64>,
+    ) -> io::Result<(Vec<String>, bool)> {
+        match self.call(Request::Search {
+            dir: dir.to_owned(),
+            query: query.to_owned(),
+            search_style: search_style.to_owned(),
+            timeout_ms,
+            domain: None,
+            combo_delimiter: ':',
+            hex_escape: false,
+        })? {
+            Response::Lines { lines, truncated } => Ok((lines, truncated)),
+            Response::Error { error } => Err(io::Error::other(error)),
+            other => Err(unexpected_response("search", &other)),
+        }
+    }
+
+    /// Find the lines in the index collection at `dir` that match `query` and whose
+    /// email field (split off at `combo_delimiter`) ends in `@domain`, for a
+    /// collection built with `--format combo --combo-keep-email`.
+    pub fn search_with_domain_filter(
+        &mut self,
+        dir: &str,
+        query: &str,
+        search_style: &str,
+        domain: &str,
+        combo_delimiter: char,
+    ) -> io::Result<Vec<String>> {
+        match self.call(Request::Search {
+            dir: dir.to_owned(),
+            query: query.to_owned(),
+            search_style: search_style.to_owned(),
+            timeout_ms: None,
+            domain: Some(domain.to_owned()),
+            combo_delimiter,
+            hex_escape: false,
+        })? {
+            Response::Lines { lines, .. } => Ok(lines),
+            Response::Error { error } => Err(io::Error::other(error)),
+            other => Err(unexpected_response("search", &other)),
+        }
+    }
+
+    /// Find the lines in the index collection at `dir` that match `query`,
+    /// escaping control bytes and the Unicode replacement character in the
+    /// returned lines as `\xNN` if `hex_escape` is set, so binary garbage in the
+    /// dump cannot break a caller's terminal or JSON encoder.
+    pub fn search_with_hex_escape(
+        &mut self,
+        dir: &str,
+        query: &str,
+        search_style: &str,
+        hex_escape: bool,
+    ) -> io::Result<Vec<String>> {
+        match self.call(Request::Search {
+            dir: dir.to_owned(),
+            query: query.to_owned(),
+            search_style: search_style.to_owned(),
+            timeout_ms: None,
+            domain: None,
+            combo_delimiter: ':',
+            hex_escape,
+        })? {
+            Response::Lines { lines, .. } => Ok(lines),
+            Response::Error { error } => Err(io::Error::other(error)),
+            other => Err(unexpected_response("search", &other)),
+        }
+    }
+
+    /// Count the lines in the index collection at `dir` that match `query`.
+    pub fn count(&mut self, dir: &str, query: &str, search_style: &str) -> io::Result<usize> {
+        self.count_with_timeout(dir, query, search_style, None).map(|(count, _)| count)
+    }
+
+    /// Count the lines in the index collection at `dir` that match `query`,
+    /// aborting the search once `timeout_ms` milliseconds have elapsed (if given
+    /// and nonzero). The returned `bool` is `true` if the timeout cut the search
+    /// short, meaning the count may be lower than the true match count.
+    pub fn count_with_timeout(
+        &mut self,
+        dir: &str,
+        query: &str,
+        search_style: &str,
+        timeout_ms: Option<u64>,
+    ) -> io::Result<(usize, bool)> {
+        match self.call(Request::Count {
+            dir: dir.to_owned(),
+            query: query.to_owned(),
+            search_style: search_style.to_owned(),
+            timeout_ms,
+            domain: None,
+            combo_delimiter: ':',
+        })? {
+            Response::Count { count, truncated } => Ok((count, truncated)),
+            Response::Error { error } => Err(io::Error::other(error)),
+            other => Err(unexpected_response("count", &other)),
+        }
+    }
+
+    /// Get the index strings `input` would be indexed under.
+    pub fn indices_of(&mut self, input: &str) -> io::Result<Vec<String>> {
+        match self.call(Request::IndicesOf {
+            input: input.to_owned(),
+        })? {
+            Response::Indices { indices } => Ok(indices),
+            Response::Error { error } => Err(io::Error::other(error)),
+            other => Err(unexpected_response("indices_of", &other)),
+        }
+    }
+}
+
+fn unexpected_response(op: &str, response: &Response) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Received an unexpected response to '{op}': {response:?}"),
+    )
+}
@@ -0,0 +1,7 @@
+//! Shared types for the Unix-domain-socket query protocol: the wire format
+//! ([`protocol`]) and a blocking Rust client ([`client::UdsClient`]).
+pub mod client;
+pub mod protocol;
+
+pub use client::UdsClient;
+pub use protocol::{Request, Response};
@@ -0,0 +1,252 @@
+//! A Unix-domain-socket front end for searching an index collection, as a
+//! lighter-weight alternative to the cgo FFI in `crates/parse-ffi` for co-located
+//! Go/Rust processes. See `server_uds::protocol` for the wire format.
+use std::io::{BufReader, BufWriter};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+
+use rockyou2024::config;
+use rockyou2024::models::{IndexCollection, IndexOf};
+use rockyou2024::search::SearchStyle;
+use rockyou2024::string::hex_escape_unprintable;
+
+use server_uds::protocol::{self, Request, Response};
+
+const LOG_TARGET: &str = "uds";
+
+/// Command line arguments.
+#[derive(Parser, Debug, Clone)]
+struct CliArgs {
+    /// Path of the Unix domain socket to listen on; removed and re-created if it
+    /// already exists.
+    #[arg(short, long)]
+    socket: PathBuf,
+}
+
+/// Parse a search style string into a [`SearchStyle`], mirroring the same accepted
+/// values as `crates/parse-ffi`'s `as_search_string`/`find_lines_in_index_collection`.
+fn parse_search_style(search_style: &str) -> Result<SearchStyle, String> {
+    match search_style {
+        "strict" => Ok(SearchStyle::Strict),
+        "case-insensitive" => Ok(SearchStyle::CaseInsensitive { unicode: false }),
+        "case-insensitive-unicode" => Ok(SearchStyle::CaseInsensitive { unicode: true }),
+        "fuzzy" => Ok(SearchStyle::Fuzzy {
+            keyboard_adjacent: false,
+        }),
+        "fuzzy-keyboard" => Ok(SearchStyle::Fuzzy {
+            keyboard_adjacent: true,
+        }),
+        "wildcard" => Ok(SearchStyle::Wildcard),
+        "exact" => Ok(SearchStyle::Exact),
+        "phonetic" => Ok(SearchStyle::Phonetic),
+        s if s.starts_with("edit-distance-") => s["edit-distance-".len()..]
+            .parse::<usize>()
+            .map(SearchStyle::edit_distance)
+            .map_err(|_| {
+                format!("Could not parse the edit distance out of search style '{search_style}'.")
+            }),
+        _ => Err(format!("Unknown search style '{search_style}'.")),
+    }
+}
+
+/// Find the lines in the index collection at `dir` that match `query`, aborting once
+/// `timeout_ms` milliseconds have elapsed (if given and nonzero).
+///
+/// If `domain` is set, the result is further restricted to lines whose email field
+/// (split off at `combo_delimiter`) ends in `@domain`; see
+/// [`rockyou2024::search::line_matches_domain`]. Intended for an index built with
+/// `--format combo --combo-keep-email`.
+fn run_search(
+    dir: &str,
+    query: &str,
+    search_style: &str,
+    timeout_ms: Option<u64>,
+    domain: Option<&str>,
+    combo_delimiter: char,
+    hex_escape: bool,
+) -> Result<(Vec<String>, bool), String> {
+    let search_style = parse_search_style(search_style)?;
+
+    let path = Path::new(dir);
+    if !path.is_dir() {
+        return Err(format!("'{dir}' is not a directory."));
+    }
+
+    let index_collection =
+        IndexCollection::<{ config::INDEX_LENGTH }, { config::INDEX_DEPTH }>::open_validated(
+            path.to_path_buf(),
+        )
+        .map_err(|err| format!("Failed to open '{dir}': {err}."))?;
+
+    let timeout = timeout_ms
+        .filter(|&timeout_ms| timeout_ms > 0)
+        .map(std::time::Duration::from_millis);
+
+    let found = index_collection.find_lines_containing_with_timeout(query, search_style, timeout);
+
+    let mut lines: Vec<String> = match domain {
+        Some(domain) => {
+            rockyou2024::search::filter_lines_by_domain(found.lines.iter(), combo_delimiter, domain)
+        }
+        None => found.lines.iter().cloned().collect(),
+    };
+
+    if hex_escape {
+        for line in &mut lines {
+            *line = hex_escape_unprintable(line);
+        }
+    }
+
+    Ok((lines, found.truncated))
+}
+
+/// Handle one [`Request`], producing the [`Response`] to send back.
+fn handle_request(request: Request) -> Response {
+    match request {
+        Request::Search {
+            dir,
+            query,
+            search_style,
+            timeout_ms,
+            domain,
+            combo_delimiter,
+            hex_escape,
+        } => match run_search(
+            &dir,
+            &query,
+            &search_style,
+            timeout_ms,
+            domain.as_deref(),
+            combo_delimiter,
+            hex_escape,
+        ) {
+            Ok((lines, truncated)) => Response::Lines { lines, truncated },
+            Err(error) => Response::Error { error },
+        },
+        Request::Count {
+            dir,
+            query,
+            search_style,
+            timeout_ms,
+            domain,
+            combo_delimiter,
+        } => match run_search(
+            &dir,
+            &query,
+            &search_style,
+            timeout_ms,
+            domain.as_deref(),
+            combo_delimiter,
+            false,
+        ) {
+            Ok((lines, truncated)) => Response::Count { count: lines.len(), truncated },
+            Err(error) => Response::Error { error },
+        },
+        Request::IndicesOf { input } => {
+            let indices =
+                IndexOf::<{ config::INDEX_LENGTH }, { config::INDEX_DEPTH }>::from(input.as_bytes())
+                    .collect();
+            Response::Indices { indices }
+        }
+    }
+}
+
+/// Serve requests on `stream` until the client disconnects or a frame fails to
+/// read.
+fn handle_connection(stream: UnixStream) {
+    let mut reader = BufReader::new(
+        stream
+            .try_clone()
+            .expect("Failed to clone the Unix stream for reading."),
+    );
+    let mut writer = BufWriter::new(stream);
+
+    loop {
+        let request = match protocol::read_request(&mut reader) {
+            Ok(request) => request,
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(err) => {
+                rockyou2024::warn!(target: LOG_TARGET, "Failed to read a request: {err}");
+                break;
+            }
+        };
+
+        let response = handle_request(request);
+        if let Err(err) = protocol::write_response(&mut writer, &response) {
+            rockyou2024::warn!(target: LOG_TARGET, "Failed to write a response: {err}");
+            break;
+        }
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let args = CliArgs::parse();
+
+    // A stale socket file left behind by a previous run would otherwise make
+    // binding fail.
+    let _ = std::fs::remove_file(&args.socket);
+
+    let listener = UnixListener::bind(&args.socket)?;
+    rockyou2024::info!(target: LOG_TARGET, "Listening on {:?}", args.socket);
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                std::thread::spawn(move || handle_connection(stream));
+            }
+            Err(err) => {
+                rockyou2024::warn!(target: LOG_TARGET, "Failed to accept a connection: {err}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_search_style_accepts_every_documented_style() {
+        for style in [
+            "strict",
+            "case-insensitive",
+            "case-insensitive-unicode",
+            "fuzzy",
+            "fuzzy-keyboard",
+            "wildcard",
+            "exact",
+            "phonetic",
+        ] {
+            assert!(parse_search_style(style).is_ok(), "Failed to parse '{style}'.");
+        }
+    }
+
+    #[test]
+    fn parse_search_style_rejects_an_unknown_style() {
+        let err = parse_search_style("not-a-real-style").expect_err("Expected an error.");
+        assert!(err.contains("Unknown search style"));
+    }
+
+    #[test]
+    fn parse_search_style_clamps_edit_distance_to_a_small_bounded_maximum() {
+        let style = parse_search_style("edit-distance-9999").expect("Failed to parse.");
+        assert_eq!(
+            style,
+            SearchStyle::EditDistance {
+                max_distance: rockyou2024::search::MAX_EDIT_DISTANCE,
+            },
+            "parse_search_style must route a caller-supplied distance through \
+             SearchStyle::edit_distance so it is clamped, not build EditDistance directly."
+        );
+    }
+
+    #[test]
+    fn parse_search_style_rejects_a_non_numeric_edit_distance() {
+        let err = parse_search_style("edit-distance-abc").expect_err("Expected an error.");
+        assert!(err.contains("Could not parse the edit distance"));
+    }
+}
@@ -0,0 +1,259 @@
+//! The wire format spoken over the Unix domain socket served by the `server-uds`
+//! binary: a small length-prefixed JSON protocol, avoiding cgo entirely for
+//! co-located Go/Rust processes.
+//!
+//! Each message, request or response, is one frame: a 4-byte big-endian length
+//! prefix followed by that many bytes of UTF-8 JSON. Connections are not
+//! pipelined — a client sends one [`Request`] and waits for the matching
+//! [`Response`] before sending the next.
+use std::io::{self, Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+/// The largest frame payload this protocol will read, guarding against a
+/// malformed or hostile length prefix causing an unbounded allocation.
+pub const MAX_FRAME_SIZE: u32 = 64 * 1024 * 1024;
+
+/// A request sent from the client to the server, tagged by `op`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Request {
+    /// Find the lines in the index collection at `dir` that match `query`.
+    Search {
+        dir: String,
+        query: String,
+        search_style: String,
+        /// Abort the search once this many milliseconds have elapsed, returning
+        /// whatever had already been found. `None`, or `Some(0)`, searches without
+        /// a deadline.
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+        /// Keep only matches whose email field ends in `@domain`, for an index
+        /// built with `--format combo --combo-keep-email`. Requires `domain`.
+        #[serde(default)]
+        domain: Option<String>,
+        /// The delimiter `domain` splits each matched line on to find its email
+        /// field; must match the delimiter the collection was indexed with.
+        /// Ignored unless `domain` is set.
+        #[serde(default = "default_combo_delimiter")]
+        combo_delimiter: char,
+        /// Escape control bytes and the Unicode replacement character in returned
+        /// lines as `\xNN`, so binary garbage in the dump cannot break a client's
+        /// terminal or JSON encoder.
+        #[serde(default)]
+        hex_escape: bool,
+    },
+    /// Count the lines in the index collection at `dir` that match `query`.
+    Count {
+        dir: String,
+        query: String,
+        search_style: String,
+        /// Abort the search once this many milliseconds have elapsed, returning
+        /// whatever had already been found. `None`, or `Some(0)`, searches without
+        /// a deadline.
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+        /// Keep only matches whose email field ends in `@domain`, for an index
+        /// built with `--format combo --combo-keep-email`. Requires `domain`.
+        #[serde(default)]
+        domain: Option<String>,
+        /// The delimiter `domain` splits each matched line on to find its email
+        /// field; must match the delimiter the collection was indexed with.
+        /// Ignored unless `domain` is set.
+        #[serde(default = "default_combo_delimiter")]
+        combo_delimiter: char,
+    },
+    /// Get the index strings `input` would be indexed under.
+    IndicesOf { input: String },
+}
+
+/// The default `combo_delimiter` for a [`Request::Search`]/[`Request::Count`] that
+/// sets `domain` without also setting `combo_delimiter`, matching the `index`
+/// binary's own `--combo-delimiter` default.
+fn default_combo_delimiter() -> char {
+    ':'
+}
+
+/// A response sent from the server to the client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Response {
+    Lines {
+        lines: Vec<String>,
+        /// True if `timeout_ms` elapsed before every index file had been scanned,
+        /// meaning `lines` may be missing matches a full search would have found.
+        #[serde(default)]
+        truncated: bool,
+    },
+    Count {
+        count: usize,
+        /// True if `timeout_ms` elapsed before every index file had been scanned,
+        /// meaning `count` may be lower than the true match count.
+        #[serde(default)]
+        truncated: bool,
+    },
+    Indices {
+        indices: Vec<String>,
+    },
+    Error {
+        error: String,
+    },
+}
+
+/// Read one length-prefixed frame from `reader`.
+pub fn read_frame(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut len_bytes = [0u8; 4];
+    reader.read_exact(&mut len_bytes)?;
+    let len = u32::from_be_bytes(len_bytes);
+
+    if len > MAX_FRAME_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Frame of {len} bytes exceeds the {MAX_FRAME_SIZE} byte limit."),
+        ));
+    }
+
+    let mut payload = vec![0u8; len as usize];
+    reader.read_exact(&mut payload)?;
+    Ok(payload)
+}
+
+/// Write `payload` to `writer` as one length-prefixed frame.
+pub fn write_frame(writer: &mut impl Write, payload: &[u8]) -> io::Result<()> {
+    let len: u32 = payload.len().try_into().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Payload of {} bytes exceeds u32::MAX.", payload.len()),
+        )
+    })?;
+
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+/// Read one [`Request`] from `reader`.
+pub fn read_request(reader: &mut impl Read) -> io::Result<Request> {
+    let payload = read_frame(reader)?;
+    serde_json::from_slice(&payload).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Write one [`Request`] to `writer`.
+pub fn write_request(writer: &mut impl Write, request: &Request) -> io::Result<()> {
+    let payload = serde_json::to_vec(request)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    write_frame(writer, &payload)
+}
+
+/// Read one [`Response`] from `reader`.
+pub fn read_response(reader: &mut impl Read) -> io::Result<Response> {
+    let payload = read_frame(reader)?;
+    serde_json::from_slice(&payload).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Write one [`Response`] to `writer`.
+pub fn write_response(writer: &mut impl Write, response: &Response) -> io::Result<()> {
+    let payload = serde_json::to_vec(response)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    write_frame(writer, &payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn write_frame_then_read_frame_round_trips_the_payload() {
+        let mut buffer = Vec::new();
+        write_frame(&mut buffer, b"hello world").expect("Failed to write frame.");
+
+        let payload = read_frame(&mut Cursor::new(buffer)).expect("Failed to read frame.");
+        assert_eq!(payload, b"hello world");
+    }
+
+    #[test]
+    fn write_frame_prefixes_the_payload_with_its_big_endian_length() {
+        let mut buffer = Vec::new();
+        write_frame(&mut buffer, b"abc").expect("Failed to write frame.");
+
+        assert_eq!(&buffer[..4], &3u32.to_be_bytes());
+        assert_eq!(&buffer[4..], b"abc");
+    }
+
+    #[test]
+    fn read_frame_rejects_a_length_prefix_over_max_frame_size() {
+        let mut buffer = Vec::new();
+        buffer.extend_from_slice(&(MAX_FRAME_SIZE + 1).to_be_bytes());
+
+        let err = read_frame(&mut Cursor::new(buffer)).expect_err("Expected an error.");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_frame_errors_on_a_truncated_length_prefix() {
+        let err = read_frame(&mut Cursor::new(vec![0u8, 0u8]))
+            .expect_err("Expected an error reading a truncated length prefix.");
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn read_frame_errors_on_a_payload_shorter_than_its_length_prefix() {
+        let mut buffer = Vec::new();
+        buffer.extend_from_slice(&10u32.to_be_bytes());
+        buffer.extend_from_slice(b"short");
+
+        let err = read_frame(&mut Cursor::new(buffer))
+            .expect_err("Expected an error reading a partial payload.");
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn read_frame_of_zero_length_yields_an_empty_payload() {
+        let payload = read_frame(&mut Cursor::new(0u32.to_be_bytes().to_vec()))
+            .expect("Failed to read a zero-length frame.");
+        assert!(payload.is_empty());
+    }
+
+    #[test]
+    fn read_request_rejects_garbage_json_inside_a_well_formed_frame() {
+        let mut buffer = Vec::new();
+        write_frame(&mut buffer, b"not json").expect("Failed to write frame.");
+
+        let err = read_request(&mut Cursor::new(buffer))
+            .expect_err("Expected an error decoding garbage JSON as a Request.");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn write_request_then_read_request_round_trips_a_search_request() {
+        let request = Request::Search {
+            dir: "/tmp/index".to_owned(),
+            query: "password".to_owned(),
+            search_style: "strict".to_owned(),
+            timeout_ms: Some(500),
+            domain: None,
+            combo_delimiter: ':',
+            hex_escape: false,
+        };
+
+        let mut buffer = Vec::new();
+        write_request(&mut buffer, &request).expect("Failed to write request.");
+
+        let decoded = read_request(&mut Cursor::new(buffer)).expect("Failed to read request.");
+        assert!(matches!(decoded, Request::Search { dir, query, .. } if dir == "/tmp/index" && query == "password"));
+    }
+
+    #[test]
+    fn write_response_then_read_response_round_trips_an_error_response() {
+        let response = Response::Error {
+            error: "something went wrong".to_owned(),
+        };
+
+        let mut buffer = Vec::new();
+        write_response(&mut buffer, &response).expect("Failed to write response.");
+
+        let decoded = read_response(&mut Cursor::new(buffer)).expect("Failed to read response.");
+        assert!(matches!(decoded, Response::Error { error } if error == "something went wrong"));
+    }
+}
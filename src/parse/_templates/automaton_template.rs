@@ -1,26 +1,65 @@
 /// This generates a cached automaton for `{{LIST_NAME}}`.
 use aho_corasick::AhoCorasick;
-
-#[cfg(not(test))]
 use std::sync::OnceLock;
 
-/// The automaton.
 #[cfg(not(test))]
 static AUTOMATON: OnceLock<(usize, AhoCorasick)> = OnceLock::new();
 
-/// Initialize the automaton.
+/// A user-supplied word list, installed via [`load_custom_words`], to build the
+/// automaton from instead of the list baked in at compile time.
+static CUSTOM_WORDS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Install a custom word list for this automaton, in place of the one baked in at
+/// compile time.
+///
+/// Must be called before the first call to [`get_automaton`]; the automaton is only
+/// ever built once per execution, so a call after that point has no effect.
 #[allow(dead_code)]
-fn init_automaton<const LENGTH: usize>() -> AhoCorasick {
-    let patterns = &["{{WORD_LIST}}"];
+pub fn load_custom_words(words: Vec<String>) {
+    let _ = CUSTOM_WORDS.set(words);
+}
+
+/// Load a custom word list from `path` (whitespace-separated) for this automaton, in
+/// place of the one baked in at compile time.
+///
+/// Must be called before the first call to [`get_automaton`]; see [`load_custom_words`].
+#[allow(dead_code)]
+pub fn load_custom_words_from_file(path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+    let contents = std::fs::read_to_string(path)?;
 
-    AhoCorasick::new(patterns.iter().filter_map(|word| match word.len() {
-        l if l < LENGTH => None,
-        l if l == LENGTH => Some(*word),
-        _ => word.get(..LENGTH),
+    load_custom_words(
+        contents
+            .split_whitespace()
+            .map(ToOwned::to_owned)
+            .collect(),
+    );
+
+    Ok(())
+}
+
+/// Build the automaton for `LENGTH`, from the custom word list if one was installed
+/// via [`load_custom_words`], or the compiled-in list otherwise.
+fn build_automaton<const LENGTH: usize>(words: impl Iterator<Item = impl AsRef<str>>) -> AhoCorasick {
+    AhoCorasick::new(words.filter_map(|word| {
+        let word = word.as_ref();
+        match word.len() {
+            l if l < LENGTH => None,
+            l if l == LENGTH => Some(word.to_owned()),
+            _ => word.get(..LENGTH).map(ToOwned::to_owned),
+        }
     }))
     .expect("Failed to create automaton for '{{LIST_NAME}}'. Please check the word list.")
 }
 
+/// Initialize the automaton.
+#[allow(dead_code)]
+fn init_automaton<const LENGTH: usize>() -> AhoCorasick {
+    match CUSTOM_WORDS.get() {
+        Some(words) => build_automaton::<LENGTH>(words.iter()),
+        None => build_automaton::<LENGTH>(["{{WORD_LIST}}"].into_iter()),
+    }
+}
+
 /// Get the automaton.
 ///
 /// Only one automaton length is allowed per execution. As `LENGTH` is a `const`, this is
@@ -0,0 +1,128 @@
+//! Aggregate statistics about a set of passwords: length distribution, character-class
+//! composition, and hashcat-style mask frequencies.
+//!
+//! This is for research and tuning rather than lookup, so results are accumulated in
+//! memory via [`AnalysisReport::record_line`] and reported once the stream of lines is
+//! exhausted, rather than persisted alongside an index.
+
+use std::collections::{BTreeMap, HashMap};
+
+/// Per-character-class counts accumulated by [`AnalysisReport::record_line`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CharacterClassCounts {
+    pub lowercase: usize,
+    pub uppercase: usize,
+    pub digits: usize,
+    pub symbols: usize,
+}
+
+/// Aggregate statistics accumulated over a set of passwords.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AnalysisReport {
+    /// The number of lines folded into this report.
+    pub lines: usize,
+    /// The number of lines seen for each length, in characters.
+    pub length_histogram: BTreeMap<usize, usize>,
+    /// The number of characters seen of each class, across every line.
+    pub character_classes: CharacterClassCounts,
+    /// The number of lines seen for each hashcat-style mask, e.g. `?l?l?l?d?d`.
+    pub mask_frequencies: HashMap<String, usize>,
+}
+
+impl AnalysisReport {
+    /// Create an empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fold one password line into the running totals.
+    pub fn record_line(&mut self, line: &[u8]) {
+        self.lines += 1;
+        *self.length_histogram.entry(line.len()).or_insert(0) += 1;
+
+        let mut mask = String::with_capacity(line.len() * 2);
+        for &byte in line {
+            let class = mask_class(byte);
+            match class {
+                'l' => self.character_classes.lowercase += 1,
+                'u' => self.character_classes.uppercase += 1,
+                'd' => self.character_classes.digits += 1,
+                _ => self.character_classes.symbols += 1,
+            }
+            mask.push('?');
+            mask.push(class);
+        }
+
+        *self.mask_frequencies.entry(mask).or_insert(0) += 1;
+    }
+
+    /// The most frequent masks, sorted by count descending (ties broken by the mask
+    /// itself, for stable output), capped at `limit`.
+    pub fn top_masks(&self, limit: usize) -> Vec<(&str, usize)> {
+        let mut masks: Vec<(&str, usize)> = self
+            .mask_frequencies
+            .iter()
+            .map(|(mask, &count)| (mask.as_str(), count))
+            .collect();
+
+        masks.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        masks.truncate(limit);
+        masks
+    }
+}
+
+/// Classify a single byte into its hashcat-style mask character: `l` (lowercase),
+/// `u` (uppercase), `d` (digit), or `s` (everything else, including punctuation and
+/// non-ASCII bytes).
+fn mask_class(byte: u8) -> char {
+    match byte {
+        b'a'..=b'z' => 'l',
+        b'A'..=b'Z' => 'u',
+        b'0'..=b'9' => 'd',
+        _ => 's',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_length_and_character_classes() {
+        let mut report = AnalysisReport::new();
+        report.record_line(b"Password1!");
+
+        assert_eq!(report.lines, 1);
+        assert_eq!(report.length_histogram.get(&10), Some(&1));
+        assert_eq!(
+            report.character_classes,
+            CharacterClassCounts {
+                lowercase: 7,
+                uppercase: 1,
+                digits: 1,
+                symbols: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn tallies_mask_frequencies() {
+        let mut report = AnalysisReport::new();
+        report.record_line(b"abc123");
+        report.record_line(b"xyz789");
+        report.record_line(b"ABCDEF");
+
+        assert_eq!(report.mask_frequencies.get("?l?l?l?d?d?d"), Some(&2));
+        assert_eq!(report.mask_frequencies.get("?u?u?u?u?u?u"), Some(&1));
+    }
+
+    #[test]
+    fn top_masks_orders_by_count_then_mask() {
+        let mut report = AnalysisReport::new();
+        report.record_line(b"aa");
+        report.record_line(b"aa");
+        report.record_line(b"11");
+
+        assert_eq!(report.top_masks(1), vec![("?l?l", 2)]);
+    }
+}
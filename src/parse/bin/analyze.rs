@@ -0,0 +1,64 @@
+//! Report length, character-class, and hashcat-style mask statistics for a set of
+//! raw password lines, for research and index-tuning purposes rather than lookup.
+
+use clap::Parser;
+use reader::FixedMemoryReader;
+use rockyou2024::{analysis::AnalysisReport, config, io::resolve_inputs};
+
+/// Fold every line of `path` into `report`.
+fn analyze_file(path: &std::path::Path, report: &mut AnalysisReport) -> anyhow::Result<()> {
+    let input_file = std::fs::File::open(path).map_err(|err| {
+        anyhow::Error::new(err).context(format!("Failed to open input file: {}", path.display()))
+    })?;
+
+    let mut reader =
+        FixedMemoryReader::<_, { config::MAX_LINE_LENGTH }>::from_read(input_file, config::CHUNK_SIZE);
+
+    for chunk in reader.iter::<b'\n'>() {
+        for line in chunk.split(|&byte| byte == b'\n') {
+            if !line.is_empty() {
+                report.record_line(line);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn analyze() -> anyhow::Result<()> {
+    let args = rockyou2024::cli::AnalyzeArgs::parse();
+
+    let inputs = resolve_inputs(&args.input)?;
+    if inputs.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No input files found for the given --input arguments."
+        ));
+    }
+
+    let mut report = AnalysisReport::new();
+    for path in &inputs {
+        analyze_file(path, &mut report)?;
+    }
+
+    println!("Lines analyzed:    {}", report.lines);
+    println!("Character classes: {:?}", report.character_classes);
+
+    println!("\nLength histogram:");
+    for (length, count) in &report.length_histogram {
+        println!("  {length:>4}: {count}");
+    }
+
+    println!("\nTop {} masks:", args.top_masks);
+    for (mask, count) in report.top_masks(args.top_masks) {
+        println!("  {mask:<40} {count}");
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(err) = analyze() {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
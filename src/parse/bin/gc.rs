@@ -0,0 +1,45 @@
+//! Remove zero-length index files, orphaned files, and stale temp files left behind
+//! in an index directory by an interrupted or otherwise unclean previous run.
+
+use clap::Parser;
+use rockyou2024::models::garbage_collect;
+
+fn gc() -> anyhow::Result<()> {
+    let args = rockyou2024::cli::GcArgs::parse();
+
+    let report = garbage_collect(&args.index).map_err(|err| {
+        anyhow::Error::new(err).context(format!("Failed to garbage-collect {}", args.index))
+    })?;
+
+    println!("Removed {} file(s) from {}.", report.total(), args.index);
+
+    if !report.empty.is_empty() {
+        println!("\nEmpty index files ({}):", report.empty.len());
+        for path in &report.empty {
+            println!("  {}", path.display());
+        }
+    }
+
+    if !report.orphaned.is_empty() {
+        println!("\nOrphaned files ({}):", report.orphaned.len());
+        for path in &report.orphaned {
+            println!("  {}", path.display());
+        }
+    }
+
+    if !report.temp.is_empty() {
+        println!("\nStale temp files ({}):", report.temp.len());
+        for path in &report.temp {
+            println!("  {}", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(err) = gc() {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
@@ -1,90 +1,1456 @@
-use std::sync::Arc;
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fs,
+    io::{self, Seek, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
 use clap::Parser;
-use rayon::prelude::*;
 use reader::FixedMemoryReader;
-use rockyou2024::{config, models::IndexCollection};
+use rockyou2024::{
+    config,
+    models::{indices_of, Checkpoint, IndexCollection},
+};
+use serde::Serialize;
 
 #[cfg(feature = "progress")]
 use kdam::{tqdm, BarExt};
-#[cfg(feature = "progress")]
-use std::sync::Mutex;
 
-/// Index the input file.
+/// Marker error signalling that indexing stopped because of a SIGINT, rather than a
+/// genuine failure; [`main`] uses this to decide the process exit code.
+#[derive(Debug)]
+struct Interrupted;
+
+impl std::fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "indexing was interrupted before it could complete")
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// Tracks which prefix of the source file has been fully processed, so that a
+/// checkpoint always records a safe resume point.
+///
+/// Chunks are dispatched to worker threads in file order but may finish out of
+/// order; completions are buffered here and only merged into `confirmed` once every
+/// chunk before them has also completed.
+#[derive(Default)]
+struct ProgressTracker {
+    confirmed: Mutex<(usize, BTreeMap<usize, usize>)>,
+}
+
+impl ProgressTracker {
+    /// Start tracking from `offset`, e.g. when resuming from a checkpoint.
+    fn starting_at(offset: usize) -> Self {
+        Self {
+            confirmed: Mutex::new((offset, BTreeMap::new())),
+        }
+    }
+
+    /// Record that the chunk spanning `[start, start + len)` has finished processing,
+    /// and return the confirmed offset after merging it in.
+    fn complete(&self, start: usize, len: usize) -> usize {
+        let mut state = self
+            .confirmed
+            .lock()
+            .unwrap_or_else(|_| panic!("The progress tracker is poisoned; could not continue."));
+
+        state.1.insert(start, len);
+
+        let (confirmed, pending) = &mut *state;
+        while let Some(len) = pending.remove(confirmed) {
+            *confirmed += len;
+        }
+
+        *confirmed
+    }
+}
+
+/// How `--format` splits an input line into the field that drives indexing and the
+/// field(s) stored for retrieval.
+#[derive(Debug, Clone)]
+enum LineFormat {
+    /// Index and store each line verbatim; the default.
+    Plain,
+    /// Split each line on `delimiter` at its first occurrence (e.g.
+    /// `email:password`) and index only the field after it, storing either just
+    /// that field or the full original line if `keep_email` is set.
+    Combo { delimiter: u8, keep_email: bool },
+    /// Parse each line as a delimited record on `delimiter`, handling quoted fields,
+    /// and index and store only `column`.
+    #[cfg(feature = "csv_input")]
+    Csv { delimiter: u8, column: usize },
+    /// Parse each line as a JSON object and index and store only `field`.
+    Jsonl { field: String },
+}
+
+/// The bytes to store in the index and the bytes to derive its bucket keys from,
+/// borrowed from the source line where possible; see [`LineFormat::split`].
+type SplitLine<'l> = (Cow<'l, [u8]>, Cow<'l, [u8]>);
+
+impl LineFormat {
+    /// Parse `--format`, together with `--combo-delimiter`/`--combo-keep-email` for
+    /// the `"combo"` case, `--csv-delimiter`/`--csv-column` for the `"csv"` case, and
+    /// `--field` for the `"jsonl"` case.
+    fn from_args(args: &rockyou2024::cli::CliArgs) -> anyhow::Result<Self> {
+        match args.format.as_str() {
+            "plain" => Ok(Self::Plain),
+            "combo" => {
+                if !args.combo_delimiter.is_ascii() {
+                    return Err(anyhow::anyhow!(
+                        "--combo-delimiter must be an ASCII character, got {:?}.",
+                        args.combo_delimiter
+                    ));
+                }
+
+                Ok(Self::Combo {
+                    delimiter: args.combo_delimiter as u8,
+                    keep_email: args.combo_keep_email,
+                })
+            }
+            #[cfg(feature = "csv_input")]
+            "csv" => {
+                if !args.csv_delimiter.is_ascii() {
+                    return Err(anyhow::anyhow!(
+                        "--csv-delimiter must be an ASCII character, got {:?}.",
+                        args.csv_delimiter
+                    ));
+                }
+
+                Ok(Self::Csv {
+                    delimiter: args.csv_delimiter as u8,
+                    column: args.csv_column,
+                })
+            }
+            "jsonl" => {
+                let field = args
+                    .field
+                    .clone()
+                    .ok_or_else(|| anyhow::anyhow!("--format jsonl requires --field."))?;
+
+                Ok(Self::Jsonl { field })
+            }
+            other => Err(anyhow::anyhow!(
+                "Unknown --format {other:?}; expected 'plain', 'combo', 'jsonl'{}.",
+                if cfg!(feature = "csv_input") { ", or 'csv'" } else { "" }
+            )),
+        }
+    }
+
+    /// Split `line` per this format, returning `(stored, key_source)`: the bytes to
+    /// store in the index, and the bytes to derive its bucket keys from. Returns
+    /// `None` if a combo line has no delimiter to split on, a csv line fails to parse
+    /// or has no such column, or a jsonl line fails to parse or has no such field.
+    fn split<'l>(&self, line: &'l [u8]) -> Option<SplitLine<'l>> {
+        match self {
+            Self::Plain => Some((Cow::Borrowed(line), Cow::Borrowed(line))),
+            Self::Combo { delimiter, keep_email } => {
+                let position = line.iter().position(|byte| byte == delimiter)?;
+                let password = &line[position + 1..];
+                let stored = if *keep_email { line } else { password };
+                Some((Cow::Borrowed(stored), Cow::Borrowed(password)))
+            }
+            #[cfg(feature = "csv_input")]
+            Self::Csv { delimiter, column } => {
+                let mut reader = csv::ReaderBuilder::new()
+                    .delimiter(*delimiter)
+                    .has_headers(false)
+                    .flexible(true)
+                    .from_reader(line);
+
+                let mut record = csv::ByteRecord::new();
+                if !reader.read_byte_record(&mut record).ok()? {
+                    return None;
+                }
+
+                let field = record.get(*column)?.to_vec();
+                Some((Cow::Owned(field.clone()), Cow::Owned(field)))
+            }
+            Self::Jsonl { field } => {
+                let value: serde_json::Value = serde_json::from_slice(line).ok()?;
+                let field = value.get(field)?;
+                let field = field.as_str().map(str::to_owned).unwrap_or_else(|| field.to_string());
+                let field = field.into_bytes();
+                Some((Cow::Owned(field.clone()), Cow::Owned(field)))
+            }
+        }
+    }
+}
+
+/// Strip a trailing `\r` and any other trailing ASCII whitespace from `line`, for
+/// `--normalize-line-endings`.
+fn normalize_line_ending(line: &[u8]) -> &[u8] {
+    let end = line.len() - line.iter().rev().take_while(|byte| byte.is_ascii_whitespace()).count();
+    &line[..end]
+}
+
+/// Outcome of indexing a single input file.
+enum FileOutcome {
+    /// The file was fully processed.
+    Completed {
+        /// CRC-32 digest of the whole file, present when this file was read from the
+        /// start rather than resumed from a checkpoint offset.
+        #[cfg(feature = "checksum_source")]
+        checksum: Option<String>,
+    },
+    /// Indexing stopped partway through, at the given byte offset into the file.
+    Interrupted { bytes_processed: usize },
+}
+
+/// Per-run indexing settings passed into [`index_one_file`], grouped so the function
+/// does not need a separate parameter for every option that does not vary per file.
+struct IndexingConfig {
+    max_chunk_size: usize,
+    threads: usize,
+    format: LineFormat,
+    separator: u8,
+    normalize_line_endings: bool,
+    max_line_length: usize,
+    /// Every line rejected for exceeding `max_line_length` is appended here, if set,
+    /// shared across worker threads since each one may reject lines concurrently.
+    quarantine: Option<Arc<Mutex<fs::File>>>,
+    /// Set for `--dry-run`: every line that would have been indexed is tallied here
+    /// instead of actually being added to the collection.
+    dry_run: Option<Arc<DryRunStats>>,
+    /// Every line seen across all workers, whether or not it ended up indexed;
+    /// tallied for the end-of-run summary report.
+    lines_read: AtomicUsize,
+    /// Every line rejected for being too long or not matching `--format`, a subset
+    /// of `lines_read`; tallied for the end-of-run summary report.
+    lines_skipped: AtomicUsize,
+}
+
+/// The end-of-run summary reported by a completed (non-dry-run, non-interrupted)
+/// indexing run, replacing the old unconditional "Indexing completed successfully."
+/// message with something a user can actually act on.
+#[derive(Serialize)]
+struct IndexSummary {
+    lines_read: usize,
+    lines_skipped: usize,
+    unique_keys_created: usize,
+    bytes_written: usize,
+    duration_seconds: f64,
+    /// `bytes_written` divided by `duration_seconds`; `0.0` if the run was
+    /// effectively instantaneous.
+    bytes_per_second: f64,
+}
+
+impl IndexSummary {
+    fn new(indexing: &IndexingConfig, index_stats: rockyou2024::models::IndexStats, duration: Duration) -> Self {
+        let duration_seconds = duration.as_secs_f64();
+
+        Self {
+            lines_read: indexing.lines_read.load(Ordering::Relaxed),
+            lines_skipped: indexing.lines_skipped.load(Ordering::Relaxed),
+            unique_keys_created: index_stats.files,
+            bytes_written: index_stats.bytes_flushed,
+            duration_seconds,
+            bytes_per_second: if duration_seconds > 0.0 {
+                index_stats.bytes_flushed as f64 / duration_seconds
+            } else {
+                0.0
+            },
+        }
+    }
+
+    /// Log this summary as human-readable text, and additionally as one line of JSON
+    /// to stderr if `summary_json` is set.
+    fn report(&self, summary_json: bool) {
+        rockyou2024::info!(
+            "Indexing completed: {} line(s) read, {} skipped, {} unique key(s) created, {} \
+             byte(s) written in {:.2}s ({:.0} bytes/s).",
+            self.lines_read,
+            self.lines_skipped,
+            self.unique_keys_created,
+            self.bytes_written,
+            self.duration_seconds,
+            self.bytes_per_second,
+        );
+
+        if summary_json {
+            match serde_json::to_string(self) {
+                Ok(line) => eprintln!("{line}"),
+                Err(err) => {
+                    rockyou2024::error!("Failed to serialize the indexing summary: {err}");
+                }
+            }
+        }
+    }
+}
+
+/// Accumulates what a `--dry-run` pass over the input would have produced, without
+/// ever adding anything to the index collection, so it can be reported to the user
+/// in place of an actual run.
+#[derive(Default)]
+struct DryRunStats {
+    lines: AtomicUsize,
+    bytes: AtomicUsize,
+    /// Every distinct index bucket key a line would have been stored under, shared
+    /// across worker threads since each one may discover new keys concurrently.
+    keys: Mutex<std::collections::HashSet<String>>,
+}
+
+impl DryRunStats {
+    /// Record that `stored` would have been added under `keys`.
+    fn record(&self, stored: &[u8], keys: impl Iterator<Item = String>) {
+        self.lines.fetch_add(1, Ordering::Relaxed);
+
+        let mut seen = self
+            .keys
+            .lock()
+            .expect("Failed to acquire lock on dry-run key set; should be uncontended.");
+
+        let mut under = 0usize;
+        for key in keys {
+            seen.insert(key);
+            under += 1;
+        }
+
+        self.bytes.fetch_add(stored.len() * under, Ordering::Relaxed);
+    }
+
+    /// Log a summary of what a real run over `file_count` input file(s) would have
+    /// produced.
+    fn report(&self, file_count: usize) {
+        let keys = self
+            .keys
+            .lock()
+            .expect("Failed to acquire lock on dry-run key set; should be uncontended.");
+
+        rockyou2024::info!(
+            "Dry run complete: {file_count} input file(s) would produce {} line(s) across {} \
+             index bucket(s), totalling an estimated {} byte(s) on disk.",
+            self.lines.load(Ordering::Relaxed),
+            keys.len(),
+            self.bytes.load(Ordering::Relaxed),
+        );
+    }
+}
+
+/// Progress-reporting hooks passed into [`index_one_file`], grouped so the function
+/// does not need a separate parameter for every reporter that observes indexing.
+struct ProgressReporters<'a> {
+    #[cfg(feature = "progress")]
+    pbar: &'a Arc<Mutex<kdam::Bar>>,
+    progress_json_bytes: Option<&'a Arc<AtomicUsize>>,
+}
+
+/// How often `--progress-json` prints a [`ProgressEvent`] to stderr.
+const PROGRESS_JSON_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A single `--progress-json` progress record, printed as one line of JSON to
+/// stderr so wrapper scripts and orchestration tooling can track a long indexing
+/// run without parsing the interactive `kdam` bar's terminal output.
+#[derive(Serialize)]
+struct ProgressEvent {
+    bytes_processed: usize,
+    total_bytes: usize,
+    bytes_per_second: f64,
+    /// `None` once the run is effectively stalled (no bytes processed since the
+    /// last tick), since a rate of zero would otherwise give a meaningless ETA.
+    eta_seconds: Option<f64>,
+    flushes: usize,
+}
+
+/// Handle to a background thread spawned by [`spawn_progress_json_reporter`].
+///
+/// Dropping this handle stops the thread and waits for it to exit, mirroring
+/// [`rockyou2024::models::IndexCollection`]'s `AutoFlushHandle`.
+struct ProgressJsonHandle {
+    stop: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl Drop for ProgressJsonHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Spawn a background thread that prints a [`ProgressEvent`] to stderr every
+/// [`PROGRESS_JSON_INTERVAL`], for as long as the returned handle is kept alive.
+///
+/// `bytes_processed` is read from `bytes_counter`, which callers must keep updating
+/// as chunks complete; `collection`'s flush count is sampled fresh on every tick.
+fn spawn_progress_json_reporter(
+    collection: Arc<IndexCollection<{ config::INDEX_LENGTH }, { config::INDEX_DEPTH }>>,
+    bytes_counter: Arc<AtomicUsize>,
+    total_bytes: usize,
+) -> ProgressJsonHandle {
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = Arc::clone(&stop);
+
+    let handle = thread::spawn(move || {
+        let mut last_tick = Instant::now();
+        let mut last_processed = bytes_counter.load(Ordering::Relaxed);
+
+        while !thread_stop.load(Ordering::Relaxed) {
+            thread::sleep(PROGRESS_JSON_INTERVAL);
+
+            if thread_stop.load(Ordering::Relaxed) {
+                break;
+            }
+
+            let processed = bytes_counter.load(Ordering::Relaxed);
+            let elapsed = last_tick.elapsed().as_secs_f64();
+            let bytes_per_second = if elapsed > 0.0 {
+                (processed.saturating_sub(last_processed)) as f64 / elapsed
+            } else {
+                0.0
+            };
+
+            let event = ProgressEvent {
+                bytes_processed: processed,
+                total_bytes,
+                bytes_per_second,
+                eta_seconds: (bytes_per_second > 0.0)
+                    .then(|| total_bytes.saturating_sub(processed) as f64 / bytes_per_second),
+                flushes: collection.total_flush_count(),
+            };
+
+            match serde_json::to_string(&event) {
+                Ok(line) => eprintln!("{line}"),
+                Err(err) => {
+                    rockyou2024::error!("Failed to serialize progress event: {err}");
+                }
+            }
+
+            last_tick = Instant::now();
+            last_processed = processed;
+        }
+    });
+
+    ProgressJsonHandle {
+        stop,
+        handle: Some(handle),
+    }
+}
+
+/// Index a single input file into `collection`, starting from `start_offset`.
+///
+/// Chunks are read by a single producer and dispatched to `threads` worker threads
+/// over a bounded channel: the channel's capacity caps how many chunks may be queued
+/// in memory at once, so a burst of small, fast-to-read chunks cannot outrun slower
+/// downstream indexing and blow up memory use the way an unbounded
+/// `par_bridge` would. Separate log targets for the producer and worker stages make
+/// each stage's throughput independently observable.
+fn index_one_file(
+    path: &Path,
+    start_offset: usize,
+    collection: &Arc<IndexCollection<{ config::INDEX_LENGTH }, { config::INDEX_DEPTH }>>,
+    indexing: &IndexingConfig,
+    interrupted: &Arc<AtomicBool>,
+    reporters: &ProgressReporters,
+) -> anyhow::Result<FileOutcome> {
+    let input_file: Box<dyn io::Read + Send> = if path == Path::new("-") {
+        if start_offset != 0 {
+            return Err(anyhow::anyhow!(
+                "Cannot resume indexing standard input from a byte offset; --resume is not \
+                 supported when reading from '-'."
+            ));
+        }
+
+        Box::new(io::stdin())
+    } else {
+        let mut file = fs::File::open(path).map_err(|err| {
+            anyhow::Error::new(err)
+                .context(format!("Failed to open input file: {}", path.display()))
+        })?;
+        file.seek(io::SeekFrom::Start(start_offset as u64)).map_err(|err| {
+            anyhow::Error::new(err)
+                .context(format!("Failed to seek to byte offset {start_offset}"))
+        })?;
+
+        Box::new(file)
+    };
+
+    #[cfg(feature = "checksum_source")]
+    let input_file = reader::ChecksummingReader::<_, reader::Crc32>::new(input_file);
+
+    let mut reader = FixedMemoryReader::<_, { config::MAX_LINE_LENGTH }>::from_read(
+        input_file,
+        indexing.max_chunk_size,
+    );
+
+    let progress = ProgressTracker::starting_at(start_offset);
+
+    // Each chunk is wrapped in an `Arc<[u8]>` as soon as it comes off the reader, so
+    // handing it to a worker is a refcount bump rather than a copy of the whole chunk.
+    let chunks_with_offsets = {
+        let mut offset = start_offset;
+        reader.iter_with_separator(indexing.separator).map(move |chunk| {
+            let start = offset;
+            offset += chunk.len();
+            (start, Arc::<[u8]>::from(chunk))
+        })
+    };
+
+    // Bounded to `threads` in-flight chunks: the producer blocks on `send` once every
+    // worker already has a chunk queued, so the pipeline never buffers more chunks
+    // than it can immediately act on.
+    let (sender, receiver) = crossbeam::channel::bounded::<(usize, Arc<[u8]>)>(indexing.threads);
+
+    let scope_result = crossbeam::thread::scope(|scope| {
+        scope.spawn(move |_| {
+            const LOG_TARGET: &str = "IndexPipelineProducer";
+            let mut sent = 0usize;
+
+            for (start, chunk) in chunks_with_offsets {
+                if interrupted.load(Ordering::SeqCst) || sender.send((start, chunk)).is_err() {
+                    break;
+                }
+                sent += 1;
+            }
+
+            rockyou2024::debug!(target: LOG_TARGET, "Producer finished after queuing {sent} chunks.");
+        });
+
+        let worker_handles: Vec<_> = (0..indexing.threads)
+            .map(|worker_id| {
+                let receiver = receiver.clone();
+                let progress = &progress;
+                let format = indexing.format.clone();
+                let quarantine = indexing.quarantine.clone();
+                let dry_run = indexing.dry_run.clone();
+                scope.spawn(move |_| -> anyhow::Result<()> {
+                    const LOG_TARGET: &str = "IndexPipelineWorker";
+                    let mut processed = 0usize;
+
+                    while let Ok((start, chunk)) = receiver.recv() {
+                        if interrupted.load(Ordering::SeqCst) {
+                            return Err(anyhow::Error::new(Interrupted));
+                        }
+
+                        let _span =
+                            tracing::info_span!("chunk", worker_id, start, size = chunk.len())
+                                .entered();
+
+                        rockyou2024::info!(target: LOG_TARGET, "Worker {worker_id} processing chunk of size: {}", chunk.len());
+
+                        let result = process_chunk(
+                            Arc::clone(collection),
+                            &chunk,
+                            &format,
+                            indexing,
+                            quarantine.as_deref(),
+                            dry_run.as_deref(),
+                        );
+
+                        if result.is_ok() {
+                            progress.complete(start, chunk.len());
+
+                            if let Some(counter) = reporters.progress_json_bytes {
+                                counter.fetch_add(chunk.len(), Ordering::Relaxed);
+                            }
+                        }
+
+                        #[cfg(feature = "progress")]
+                        reporters.pbar.lock().map_err(
+                            |_err| {
+                                anyhow::Error::msg("Failed to lock progress bar")
+                            }
+                        ).and_then(
+                            |mut pbar| {
+                                pbar.update(chunk.len()).and_then(
+                                    |_| pbar.refresh()
+                                ).map_err(
+                                    |err| {
+                                        anyhow::Error::new(err).context("Failed to update progress bar")
+                                    }
+                                )
+                            }
+                        ).unwrap_or_else(
+                            |err| {
+                                rockyou2024::error!("Failed to update progress bar: {}", err);
+                            }
+                        );
+
+                        result?;
+                        processed += 1;
+                    }
+
+                    rockyou2024::debug!(target: LOG_TARGET, "Worker {worker_id} processed {processed} chunks.");
+                    Ok(())
+                })
+            })
+            .collect();
+
+        worker_handles.into_iter().try_for_each(|handle| {
+            handle
+                .join()
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("An indexing worker thread panicked.")))
+        })
+    });
+
+    let indexing_result =
+        scope_result.unwrap_or_else(|_| Err(anyhow::anyhow!("The indexing pipeline panicked.")));
+
+    if let Err(err) = indexing_result {
+        if err.downcast_ref::<Interrupted>().is_some() {
+            let bytes_processed = progress
+                .confirmed
+                .lock()
+                .map(|state| state.0)
+                .unwrap_or(start_offset);
+
+            return Ok(FileOutcome::Interrupted { bytes_processed });
+        }
+
+        return Err(err);
+    }
+
+    // A whole-file digest is only meaningful when the whole file was actually read
+    // in this run; a checkpoint-resumed file only had its unread tail streamed
+    // through the checksumming reader, so recording that as "the file's checksum"
+    // would be misleading.
+    #[cfg(feature = "checksum_source")]
+    let checksum = (start_offset == 0).then(|| reader.get_ref().hex_digest());
+
+    Ok(FileOutcome::Completed {
+        #[cfg(feature = "checksum_source")]
+        checksum,
+    })
+}
+
+/// Size of `path` in bytes, for progress reporting; `0` for standard input (`-`),
+/// whose total size cannot be known up front.
+fn input_size(path: &Path) -> anyhow::Result<usize> {
+    if path == Path::new("-") {
+        return Ok(0);
+    }
+
+    fs::metadata(path)
+        .map(|metadata| metadata.len() as usize)
+        .map_err(|err| {
+            anyhow::Error::new(err)
+                .context(format!("Failed to get metadata for input file: {}", path.display()))
+        })
+}
+
+/// Index the input file(s).
 fn index() -> anyhow::Result<()> {
+    let start_time = Instant::now();
+
     let args = rockyou2024::cli::CliArgs::parse();
 
+    if args.log_json {
+        rockyou2024::logger::set_json_format();
+    }
+
+    let inputs = rockyou2024::io::resolve_inputs(&args.input)?;
+    if inputs.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No input files found for the given --input arguments."
+        ));
+    }
+
+    let format = LineFormat::from_args(&args)?;
+
+    if let Some(path) = &args.common_words {
+        rockyou2024::automatons::en_common_words::load_custom_words_from_file(path).map_err(
+            |err| anyhow::Error::new(err).context("Failed to load the custom common-words list"),
+        )?;
+    }
+
+    #[cfg(feature = "custom_substitutions")]
+    let custom_mapping = args
+        .substitution_map
+        .as_ref()
+        .map(rockyou2024::character::load_custom_mapping)
+        .transpose()
+        .map_err(|err| anyhow::Error::new(err).context("Failed to load the custom substitution map"))?;
+
+    #[cfg(not(feature = "custom_substitutions"))]
+    if args.substitution_map.is_some() {
+        rockyou2024::warn!(
+            "A substitution map was provided, but this binary was built without the \
+             'custom_substitutions' feature; ignoring it."
+        );
+    }
+
+    #[cfg(feature = "custom_substitutions")]
+    if let Some(mapping) = &custom_mapping {
+        rockyou2024::character::set_custom_mapping(mapping.clone());
+    }
+
+    // The reader's line buffer is a fixed `MAX_LINE_LENGTH` bytes at compile time, so
+    // a runtime threshold above that can never actually be observed.
+    let max_line_length = if args.max_line_length > config::MAX_LINE_LENGTH {
+        rockyou2024::warn!(
+            "--max-line-length {} exceeds the compiled-in limit of {} bytes; clamping to it.",
+            args.max_line_length,
+            config::MAX_LINE_LENGTH
+        );
+        config::MAX_LINE_LENGTH
+    } else {
+        args.max_line_length
+    };
+
+    if args.dry_run && args.quarantine_file.is_some() {
+        rockyou2024::warn!(
+            "--quarantine-file was passed alongside --dry-run; a dry run writes nothing, so it \
+             will be ignored."
+        );
+    }
+
+    let quarantine = args
+        .quarantine_file
+        .as_ref()
+        .filter(|_| !args.dry_run)
+        .map(|path| -> anyhow::Result<_> {
+            let file = fs::OpenOptions::new().create(true).append(true).open(path).map_err(|err| {
+                anyhow::Error::new(err)
+                    .context(format!("Failed to open the quarantine file: {path}"))
+            })?;
+            Ok(Arc::new(Mutex::new(file)))
+        })
+        .transpose()?;
+
+    let dry_run = args.dry_run.then(|| Arc::new(DryRunStats::default()));
+
+    let (resume_file_index, resume_offset) = if args.resume {
+        match Checkpoint::read(&args.output) {
+            Ok(checkpoint) => {
+                rockyou2024::info!(
+                    "Resuming from checkpoint at file #{file_index} ({path}), byte offset {offset}.",
+                    file_index = checkpoint.file_index,
+                    path = inputs
+                        .get(checkpoint.file_index)
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_else(|| "<unknown>".to_owned()),
+                    offset = checkpoint.bytes_processed
+                );
+                (checkpoint.file_index, checkpoint.bytes_processed)
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                rockyou2024::warn!("--resume was passed, but no checkpoint was found; starting from the beginning.");
+                (0, 0)
+            }
+            Err(err) => {
+                return Err(anyhow::Error::new(err).context("Failed to read the checkpoint"))
+            }
+        }
+    } else {
+        (0, 0)
+    };
+
     #[cfg(feature = "progress")]
-    let file_size = std::fs::metadata(&args.input)
-        .map_err(|err| {
-            anyhow::Error::new(err).context(format!(
-                "Failed to get metadata for input file: {}",
-                args.input
-            ))
-        })?
-        .len();
+    let file_sizes = inputs
+        .iter()
+        .map(|path| input_size(path))
+        .collect::<anyhow::Result<Vec<usize>>>()?;
+
+    let progress_json_bytes = args.progress_json.then(|| Arc::new(AtomicUsize::new(0)));
+
+    let progress_json_total_and_offset = if args.progress_json {
+        let file_sizes: Vec<usize> = inputs
+            .iter()
+            .map(|path| input_size(path))
+            .collect::<anyhow::Result<Vec<usize>>>()?;
+
+        let total_size: usize = file_sizes.iter().sum();
+        let already_processed: usize =
+            file_sizes[..resume_file_index].iter().sum::<usize>() + resume_offset;
+
+        Some((total_size, already_processed))
+    } else {
+        None
+    };
 
     #[cfg(feature = "progress")]
     let pbar = Arc::new(Mutex::new({
+        let total_size: usize = file_sizes.iter().sum();
+        let already_processed: usize =
+            file_sizes[..resume_file_index].iter().sum::<usize>() + resume_offset;
+
         let mut bar = tqdm!(
-            total = file_size as usize,
+            total = total_size,
             position = 0,
             desc = "Indexing",
             unit = "bytes",
             miniters = 1
         );
 
+        bar.update(already_processed)
+            .expect("Failed to set the progress bar's starting position.");
         bar.refresh().expect("Failed to refresh progress bar.");
 
         bar
     }));
 
-    let mut reader = FixedMemoryReader::<_, { config::MAX_LINE_LENGTH }>::from_path(
-        &args.input,
-        args.max_chunk_size,
-    )
-    .map_err(|err| {
-        anyhow::Error::new(err).context(format!("Failed to memory-map input file: {}", args.input))
+    let output_dir = args.output.clone();
+
+    let durability = args
+        .durability
+        .parse::<rockyou2024::models::DurabilityPolicy>()
+        .map_err(anyhow::Error::msg)?;
+
+    // Opened read-only for `--dry-run` so the collection cannot write to disk even by
+    // accident; `process_chunk` never calls `add_under_field` on it in that case.
+    let collection = Arc::new(if args.dry_run {
+        IndexCollection::<
+            { config::INDEX_LENGTH },
+            { config::INDEX_DEPTH },
+            // { config::MAX_INDEX_BUFFER_SIZE },
+        >::open_read_only(args.output.into())
+    } else {
+        IndexCollection::<
+            { config::INDEX_LENGTH },
+            { config::INDEX_DEPTH },
+            // { config::MAX_INDEX_BUFFER_SIZE },
+        >::with_durability(args.output.into(), durability)
+    });
+
+    let progress_json_handle = match (&progress_json_bytes, progress_json_total_and_offset) {
+        (Some(counter), Some((total_size, already_processed))) => {
+            counter.fetch_add(already_processed, Ordering::Relaxed);
+            Some(spawn_progress_json_reporter(
+                Arc::clone(&collection),
+                Arc::clone(counter),
+                total_size,
+            ))
+        }
+        _ => None,
+    };
+
+    let interrupted = Arc::new(AtomicBool::new(false));
+    {
+        let interrupted = Arc::clone(&interrupted);
+        ctrlc::set_handler(move || {
+            rockyou2024::warn!(
+                "Received interrupt signal; finishing in-flight chunks and checkpointing..."
+            );
+            interrupted.store(true, Ordering::SeqCst);
+        })
+        .map_err(|err| anyhow::Error::new(err).context("Failed to install a SIGINT handler"))?;
+    }
+
+    let reporters = ProgressReporters {
+        #[cfg(feature = "progress")]
+        pbar: &pbar,
+        progress_json_bytes: progress_json_bytes.as_ref(),
+    };
+
+    let indexing = IndexingConfig {
+        max_chunk_size: args.max_chunk_size,
+        threads: args.threads,
+        format,
+        separator: args.separator,
+        normalize_line_endings: args.normalize_line_endings,
+        max_line_length,
+        quarantine,
+        dry_run: dry_run.clone(),
+        lines_read: AtomicUsize::new(0),
+        lines_skipped: AtomicUsize::new(0),
+    };
+
+    #[cfg(feature = "checksum_source")]
+    let mut source_checksums: Vec<Option<String>> = vec![None; inputs.len()];
+
+    for (file_index, path) in inputs.iter().enumerate() {
+        if file_index < resume_file_index {
+            continue;
+        }
+
+        let start_offset = if file_index == resume_file_index {
+            resume_offset
+        } else {
+            0
+        };
+
+        let outcome = index_one_file(
+            path,
+            start_offset,
+            &collection,
+            &indexing,
+            &interrupted,
+            &reporters,
+        )?;
+
+        #[cfg(feature = "checksum_source")]
+        if let FileOutcome::Completed { checksum } = &outcome {
+            source_checksums[file_index] = checksum.clone();
+        }
+
+        if let FileOutcome::Interrupted { bytes_processed } = outcome {
+            if let Some(dry_run) = &dry_run {
+                dry_run.report(file_index);
+                return Err(anyhow::Error::new(Interrupted));
+            }
+
+            let stats = collection.flush_all().map_err(|err| {
+                anyhow::Error::new(err).context("Failed to flush index buffers on interrupt")
+            })?;
+
+            Checkpoint::new(stats, file_index, bytes_processed)
+                .write(&output_dir)
+                .map_err(|err| anyhow::Error::new(err).context("Failed to write checkpoint"))?;
+
+            return Err(anyhow::Error::new(Interrupted));
+        }
+    }
+
+    // Stop the reporter before reclaiming exclusive ownership of `collection` below;
+    // it holds its own `Arc` clone for reading `total_flush_count`, and `try_unwrap`
+    // requires the last reference.
+    drop(progress_json_handle);
+
+    if let Some(dry_run) = &dry_run {
+        dry_run.report(inputs.len());
+        return Ok(());
+    }
+
+    #[cfg(feature = "frequency")]
+    let frequency_report = args
+        .top_frequencies
+        .map(|limit| rockyou2024::models::FrequencyReport::new(collection.top_frequencies(limit)));
+
+    #[cfg(not(feature = "frequency"))]
+    if args.top_frequencies.is_some() {
+        rockyou2024::warn!(
+            "--top-frequencies was passed, but this binary was built without the \
+             'frequency' feature; ignoring it."
+        );
+    }
+
+    let stats = Arc::try_unwrap(collection)
+        .unwrap_or_else(|_| {
+            panic!("Failed to reclaim exclusive ownership of the index collection after indexing.")
+        })
+        .finalize()
+        .map_err(|err| anyhow::Error::new(err).context("Failed to finalize the index collection"))?;
+
+    #[cfg(feature = "frequency")]
+    if let Some(report) = frequency_report {
+        report.write(&output_dir).map_err(|err| {
+            anyhow::Error::new(err).context("Failed to write the frequency report")
+        })?;
+    }
+
+    let manifest = rockyou2024::models::IndexManifest::new::<
+        { config::INDEX_LENGTH },
+        { config::INDEX_DEPTH },
+    >(&inputs, stats.files, stats.bytes_flushed)
+    .map_err(|err| anyhow::Error::new(err).context("Failed to build the index manifest"))?;
+
+    #[cfg(feature = "custom_substitutions")]
+    let manifest = manifest.with_custom_substitutions(custom_mapping.as_ref());
+
+    #[cfg(feature = "checksum_source")]
+    let manifest = manifest.with_source_checksums(source_checksums);
+
+    let manifest = manifest
+        .with_index_file_hashes(&output_dir)
+        .map_err(|err| anyhow::Error::new(err).context("Failed to checksum the index files"))?;
+
+    manifest
+        .write(&output_dir)
+        .map_err(|err| anyhow::Error::new(err).context("Failed to write the index manifest"))?;
+
+    #[cfg(feature = "hash_lookup")]
+    if !args.hash_algorithms.is_empty() {
+        let algorithms = args
+            .hash_algorithms
+            .iter()
+            .map(|name| name.parse::<rockyou2024::hash::HashAlgorithm>().map_err(anyhow::Error::msg))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let backend = args
+            .backend
+            .parse::<rockyou2024::models::StorageBackend>()
+            .map_err(anyhow::Error::msg)?;
+
+        build_hash_indices(&inputs, &algorithms, &output_dir, backend, args.separator, args.normalize_line_endings)?;
+    }
+
+    #[cfg(not(feature = "hash_lookup"))]
+    if !args.hash_algorithms.is_empty() {
+        rockyou2024::warn!(
+            "--hash-algorithms was passed, but this binary was built without the \
+             'hash_lookup' feature; ignoring it."
+        );
+    }
+
+    #[cfg(feature = "reversed_index")]
+    if args.reversed_index {
+        build_reversed_index(&inputs, &output_dir, args.separator, args.normalize_line_endings)?;
+    }
+
+    #[cfg(not(feature = "reversed_index"))]
+    if args.reversed_index {
+        rockyou2024::warn!(
+            "--reversed-index was passed, but this binary was built without the \
+             'reversed_index' feature; ignoring it."
+        );
+    }
+
+    #[cfg(feature = "case_folded_index")]
+    if args.case_folded_index {
+        build_case_folded_index(&inputs, &output_dir, args.separator, args.normalize_line_endings)?;
+    }
+
+    #[cfg(not(feature = "case_folded_index"))]
+    if args.case_folded_index {
+        rockyou2024::warn!(
+            "--case-folded-index was passed, but this binary was built without the \
+             'case_folded_index' feature; ignoring it."
+        );
+    }
+
+    #[cfg(feature = "fuzzed_index")]
+    if args.fuzzed_index {
+        build_fuzzed_index(&inputs, &output_dir, args.separator, args.normalize_line_endings)?;
+    }
+
+    #[cfg(not(feature = "fuzzed_index"))]
+    if args.fuzzed_index {
+        rockyou2024::warn!(
+            "--fuzzed-index was passed, but this binary was built without the \
+             'fuzzed_index' feature; ignoring it."
+        );
+    }
+
+    #[cfg(feature = "ngram_index")]
+    if let Some(stride) = args.ngram_stride {
+        build_ngram_index(&inputs, &output_dir, stride, args.separator, args.normalize_line_endings)?;
+    }
+
+    #[cfg(not(feature = "ngram_index"))]
+    if args.ngram_stride.is_some() {
+        rockyou2024::warn!(
+            "--ngram-stride was passed, but this binary was built without the \
+             'ngram_index' feature; ignoring it."
+        );
+    }
+
+    #[cfg(feature = "phonetic_index")]
+    if args.phonetic_index {
+        build_phonetic_index(&inputs, &output_dir, args.separator, args.normalize_line_endings)?;
+    }
+
+    #[cfg(not(feature = "phonetic_index"))]
+    if args.phonetic_index {
+        rockyou2024::warn!(
+            "--phonetic-index was passed, but this binary was built without the \
+             'phonetic_index' feature; ignoring it."
+        );
+    }
+
+    #[cfg(feature = "symspell_index")]
+    if let Some(max_distance) = args.symspell_max_distance {
+        build_symspell_index(&inputs, &output_dir, max_distance, args.separator, args.normalize_line_endings)?;
+    }
+
+    #[cfg(not(feature = "symspell_index"))]
+    if args.symspell_max_distance.is_some() {
+        rockyou2024::warn!(
+            "--symspell-max-distance was passed, but this binary was built without \
+             the 'symspell_index' feature; ignoring it."
+        );
+    }
+
+    if let Some(wordlist_path) = args.export_wordlist {
+        let lines = rockyou2024::models::IndexCollection::<
+            { config::INDEX_LENGTH },
+            { config::INDEX_DEPTH },
+        >::new(output_dir.clone().into())
+        .export(&wordlist_path)
+        .map_err(|err| anyhow::Error::new(err).context("Failed to export the wordlist"))?;
+
+        rockyou2024::info!("Exported {lines} unique lines to {wordlist_path}.");
+    }
+
+    #[cfg(feature = "sqlite_export")]
+    if let Some(sqlite_path) = args.export_sqlite {
+        let rows = rockyou2024::models::IndexCollection::<
+            { config::INDEX_LENGTH },
+            { config::INDEX_DEPTH },
+        >::new(output_dir.clone().into())
+        .export_sqlite(&sqlite_path)
+        .map_err(|err| anyhow::Error::new(err).context("Failed to export the SQLite database"))?;
+
+        rockyou2024::info!("Exported {rows} rows to {sqlite_path}.");
+    }
+
+    #[cfg(not(feature = "sqlite_export"))]
+    if args.export_sqlite.is_some() {
+        rockyou2024::warn!(
+            "--export-sqlite was passed, but this binary was built without the \
+             'sqlite_export' feature; ignoring it."
+        );
+    }
+
+    IndexSummary::new(&indexing, stats, start_time.elapsed()).report(args.summary_json);
+
+    // The run completed; any checkpoint left over from an earlier interrupted run is
+    // now stale.
+    Checkpoint::remove(&output_dir)
+        .map_err(|err| anyhow::Error::new(err).context("Failed to remove a stale checkpoint"))
+}
+
+/// Build a secondary hash-to-plaintext lookup index for each of `algorithms`, by
+/// streaming `inputs` a second time.
+///
+/// This is a separate, single-threaded pass over the same files the primary index
+/// was built from, rather than being folded into [`index_one_file`]'s parallel chunk
+/// processing, so that the hot indexing path stays free of the extra locking that
+/// writing to a shared [`rockyou2024::models::HashIndex`] from multiple threads at
+/// once would require.
+#[cfg(feature = "hash_lookup")]
+fn build_hash_indices(
+    inputs: &[std::path::PathBuf],
+    algorithms: &[rockyou2024::hash::HashAlgorithm],
+    output_dir: &str,
+    backend: rockyou2024::models::StorageBackend,
+    separator: u8,
+    normalize_line_endings: bool,
+) -> anyhow::Result<()> {
+    let indices: Vec<rockyou2024::models::HashIndex> = algorithms
+        .iter()
+        .map(|&algorithm| rockyou2024::models::HashIndex::with_backend(algorithm, output_dir, backend))
+        .collect();
+
+    for path in inputs {
+        let input_file = fs::File::open(path).map_err(|err| {
+            anyhow::Error::new(err).context(format!("Failed to open input file: {}", path.display()))
+        })?;
+
+        let mut reader = FixedMemoryReader::<_, { config::MAX_LINE_LENGTH }>::from_read(
+            input_file,
+            config::CHUNK_SIZE,
+        );
+
+        for chunk in reader.iter_with_separator(separator) {
+            for line in chunk.split(|&byte| byte == separator) {
+                let line = if normalize_line_endings { normalize_line_ending(line) } else { line };
+
+                if line.is_empty() || line.len() > config::MAX_LINE_LENGTH {
+                    continue;
+                }
+
+                for index in &indices {
+                    index.add(line).map_err(|err| {
+                        anyhow::Error::new(err).context("Failed to add line to hash index")
+                    })?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Build a secondary index of every line reversed, by streaming `inputs` a second
+/// time.
+///
+/// Like [`build_hash_indices`], this is a separate, single-threaded pass over the
+/// same files the primary index was built from, rather than being folded into
+/// [`index_one_file`]'s parallel chunk processing.
+#[cfg(feature = "reversed_index")]
+fn build_reversed_index(
+    inputs: &[std::path::PathBuf],
+    output_dir: &str,
+    separator: u8,
+    normalize_line_endings: bool,
+) -> anyhow::Result<()> {
+    let index = rockyou2024::models::ReversedIndex::<
+        { config::INDEX_LENGTH },
+        { config::INDEX_DEPTH },
+    >::new(output_dir);
+
+    for path in inputs {
+        let input_file = fs::File::open(path).map_err(|err| {
+            anyhow::Error::new(err).context(format!("Failed to open input file: {}", path.display()))
+        })?;
+
+        let mut reader = FixedMemoryReader::<_, { config::MAX_LINE_LENGTH }>::from_read(
+            input_file,
+            config::CHUNK_SIZE,
+        );
+
+        for chunk in reader.iter_with_separator(separator) {
+            for line in chunk.split(|&byte| byte == separator) {
+                let line = if normalize_line_endings { normalize_line_ending(line) } else { line };
+
+                if line.is_empty() || line.len() > config::MAX_LINE_LENGTH {
+                    continue;
+                }
+
+                index.add(line).map_err(|err| {
+                    anyhow::Error::new(err).context("Failed to add line to reversed index")
+                })?;
+            }
+        }
+    }
+
+    index.finalize().map_err(|err| {
+        anyhow::Error::new(err).context("Failed to finalize the reversed index")
+    })?;
+
+    Ok(())
+}
+
+/// Build a secondary index of every line pre-lowercased, by streaming `inputs` a
+/// second time.
+///
+/// Like [`build_hash_indices`], this is a separate, single-threaded pass over the
+/// same files the primary index was built from, rather than being folded into
+/// [`index_one_file`]'s parallel chunk processing.
+#[cfg(feature = "case_folded_index")]
+fn build_case_folded_index(
+    inputs: &[std::path::PathBuf],
+    output_dir: &str,
+    separator: u8,
+    normalize_line_endings: bool,
+) -> anyhow::Result<()> {
+    let index = rockyou2024::models::CaseFoldedIndex::<
+        { config::INDEX_LENGTH },
+        { config::INDEX_DEPTH },
+    >::new(output_dir);
+
+    for path in inputs {
+        let input_file = fs::File::open(path).map_err(|err| {
+            anyhow::Error::new(err).context(format!("Failed to open input file: {}", path.display()))
+        })?;
+
+        let mut reader = FixedMemoryReader::<_, { config::MAX_LINE_LENGTH }>::from_read(
+            input_file,
+            config::CHUNK_SIZE,
+        );
+
+        for chunk in reader.iter_with_separator(separator) {
+            for line in chunk.split(|&byte| byte == separator) {
+                let line = if normalize_line_endings { normalize_line_ending(line) } else { line };
+
+                if line.is_empty() || line.len() > config::MAX_LINE_LENGTH {
+                    continue;
+                }
+
+                index.add(line).map_err(|err| {
+                    anyhow::Error::new(err).context("Failed to add line to case-folded index")
+                })?;
+            }
+        }
+    }
+
+    index.finalize().map_err(|err| {
+        anyhow::Error::new(err).context("Failed to finalize the case-folded index")
     })?;
 
-    let collection = Arc::new(IndexCollection::<
+    Ok(())
+}
+
+/// Build a secondary index of every line pre-fuzzed (leet-speak folded), by streaming
+/// `inputs` a second time.
+///
+/// Like [`build_hash_indices`], this is a separate, single-threaded pass over the
+/// same files the primary index was built from, rather than being folded into
+/// [`index_one_file`]'s parallel chunk processing.
+#[cfg(feature = "fuzzed_index")]
+fn build_fuzzed_index(
+    inputs: &[std::path::PathBuf],
+    output_dir: &str,
+    separator: u8,
+    normalize_line_endings: bool,
+) -> anyhow::Result<()> {
+    let index = rockyou2024::models::FuzzedIndex::<
         { config::INDEX_LENGTH },
         { config::INDEX_DEPTH },
-        // { config::MAX_INDEX_BUFFER_SIZE },
-    >::new(args.output.into()));
+    >::new(output_dir);
 
-    reader.iter::<b'\n'>().par_bridge().try_for_each(|chunk| {
-        rockyou2024::info!(target: "ParBridgeProcessChunk", "Processing chunk of size: {}", chunk.len());
-        let collection = Arc::clone(&collection);
+    for path in inputs {
+        let input_file = fs::File::open(path).map_err(|err| {
+            anyhow::Error::new(err).context(format!("Failed to open input file: {}", path.display()))
+        })?;
 
-        #[cfg(feature = "progress")]
-        let pbar_local = Arc::clone(&pbar);
+        let mut reader = FixedMemoryReader::<_, { config::MAX_LINE_LENGTH }>::from_read(
+            input_file,
+            config::CHUNK_SIZE,
+        );
 
-        let result = process_chunk(collection, &chunk);
+        for chunk in reader.iter_with_separator(separator) {
+            for line in chunk.split(|&byte| byte == separator) {
+                let line = if normalize_line_endings { normalize_line_ending(line) } else { line };
 
-        #[cfg(feature = "progress")]
-        pbar_local.lock().map_err(
-            |_err| {
-                anyhow::Error::msg("Failed to lock progress bar")
-            }
-        ).and_then(
-            |mut pbar| {
-                pbar.update(chunk.len()).and_then(
-                    |_| pbar.refresh()
-                ).map_err(
-                    |err| {
-                        anyhow::Error::new(err).context("Failed to update progress bar")
-                    }
-                )
+                if line.is_empty() || line.len() > config::MAX_LINE_LENGTH {
+                    continue;
+                }
+
+                index.add(line).map_err(|err| {
+                    anyhow::Error::new(err).context("Failed to add line to fuzzed index")
+                })?;
             }
-        ).unwrap_or_else(
-            |err| {
-                rockyou2024::error!("Failed to update progress bar: {}", err);
+        }
+    }
+
+    index.finalize().map_err(|err| {
+        anyhow::Error::new(err).context("Failed to finalize the fuzzed index")
+    })?;
+
+    Ok(())
+}
+
+/// Build a secondary index bucketing every line under all of its n-grams `stride`
+/// characters apart, by streaming `inputs` a second time.
+///
+/// Like [`build_hash_indices`], this is a separate, single-threaded pass over the
+/// same files the primary index was built from, rather than being folded into
+/// [`index_one_file`]'s parallel chunk processing.
+#[cfg(feature = "ngram_index")]
+fn build_ngram_index(
+    inputs: &[std::path::PathBuf],
+    output_dir: &str,
+    stride: usize,
+    separator: u8,
+    normalize_line_endings: bool,
+) -> anyhow::Result<()> {
+    let index = rockyou2024::models::NgramIndex::<
+        { config::INDEX_LENGTH },
+        { config::INDEX_DEPTH },
+    >::new(output_dir, stride);
+
+    for path in inputs {
+        let input_file = fs::File::open(path).map_err(|err| {
+            anyhow::Error::new(err).context(format!("Failed to open input file: {}", path.display()))
+        })?;
+
+        let mut reader = FixedMemoryReader::<_, { config::MAX_LINE_LENGTH }>::from_read(
+            input_file,
+            config::CHUNK_SIZE,
+        );
+
+        for chunk in reader.iter_with_separator(separator) {
+            for line in chunk.split(|&byte| byte == separator) {
+                let line = if normalize_line_endings { normalize_line_ending(line) } else { line };
+
+                if line.is_empty() || line.len() > config::MAX_LINE_LENGTH {
+                    continue;
+                }
+
+                index.add(line).map_err(|err| {
+                    anyhow::Error::new(err).context("Failed to add line to n-gram index")
+                })?;
             }
+        }
+    }
+
+    index.finalize().map_err(|err| {
+        anyhow::Error::new(err).context("Failed to finalize the n-gram index")
+    })?;
+
+    Ok(())
+}
+
+/// Build a secondary index of every line's Soundex code, by streaming `inputs` a
+/// second time.
+///
+/// Like [`build_hash_indices`], this is a separate, single-threaded pass over the
+/// same files the primary index was built from, rather than being folded into
+/// [`index_one_file`]'s parallel chunk processing.
+#[cfg(feature = "phonetic_index")]
+fn build_phonetic_index(
+    inputs: &[std::path::PathBuf],
+    output_dir: &str,
+    separator: u8,
+    normalize_line_endings: bool,
+) -> anyhow::Result<()> {
+    let index = rockyou2024::models::PhoneticIndex::<
+        { config::INDEX_LENGTH },
+        { config::INDEX_DEPTH },
+    >::new(output_dir);
+
+    for path in inputs {
+        let input_file = fs::File::open(path).map_err(|err| {
+            anyhow::Error::new(err).context(format!("Failed to open input file: {}", path.display()))
+        })?;
+
+        let mut reader = FixedMemoryReader::<_, { config::MAX_LINE_LENGTH }>::from_read(
+            input_file,
+            config::CHUNK_SIZE,
         );
 
-        result
-    })
+        for chunk in reader.iter_with_separator(separator) {
+            for line in chunk.split(|&byte| byte == separator) {
+                let line = if normalize_line_endings { normalize_line_ending(line) } else { line };
+
+                if line.is_empty() || line.len() > config::MAX_LINE_LENGTH {
+                    continue;
+                }
+
+                index.add(line).map_err(|err| {
+                    anyhow::Error::new(err).context("Failed to add line to phonetic index")
+                })?;
+            }
+        }
+    }
+
+    index.finalize().map_err(|err| {
+        anyhow::Error::new(err).context("Failed to finalize the phonetic index")
+    })?;
+
+    Ok(())
+}
+
+/// Build a secondary index bucketing every line under all of its own deletion
+/// variants up to `max_distance` deletions, by streaming `inputs` a second time.
+///
+/// Like [`build_hash_indices`], this is a separate, single-threaded pass over the
+/// same files the primary index was built from, rather than being folded into
+/// [`index_one_file`]'s parallel chunk processing.
+#[cfg(feature = "symspell_index")]
+fn build_symspell_index(
+    inputs: &[std::path::PathBuf],
+    output_dir: &str,
+    max_distance: usize,
+    separator: u8,
+    normalize_line_endings: bool,
+) -> anyhow::Result<()> {
+    let index = rockyou2024::models::SymSpellIndex::<
+        { config::INDEX_LENGTH },
+        { config::INDEX_DEPTH },
+    >::new(output_dir, max_distance);
+
+    for path in inputs {
+        let input_file = fs::File::open(path).map_err(|err| {
+            anyhow::Error::new(err).context(format!("Failed to open input file: {}", path.display()))
+        })?;
+
+        let mut reader = FixedMemoryReader::<_, { config::MAX_LINE_LENGTH }>::from_read(
+            input_file,
+            config::CHUNK_SIZE,
+        );
+
+        for chunk in reader.iter_with_separator(separator) {
+            for line in chunk.split(|&byte| byte == separator) {
+                let line = if normalize_line_endings { normalize_line_ending(line) } else { line };
+
+                if line.is_empty() || line.len() > config::MAX_LINE_LENGTH {
+                    continue;
+                }
+
+                index.add(line).map_err(|err| {
+                    anyhow::Error::new(err).context("Failed to add line to SymSpell index")
+                })?;
+            }
+        }
+    }
+
+    index.finalize().map_err(|err| {
+        anyhow::Error::new(err).context("Failed to finalize the SymSpell index")
+    })?;
+
+    Ok(())
 }
 
 /// Process a chunk of data.
@@ -97,27 +1463,77 @@ fn process_chunk(
         >,
     >,
     chunk: &[u8],
+    format: &LineFormat,
+    indexing: &IndexingConfig,
+    quarantine: Option<&Mutex<fs::File>>,
+    dry_run: Option<&DryRunStats>,
 ) -> anyhow::Result<()> {
     const LOG_TARGET: &str = "ProcessChunk";
 
     chunk
-        .split(|&byte| byte == b'\n')
+        .split(|&byte| byte == indexing.separator)
         .filter_map(|line| {
+            indexing.lines_read.fetch_add(1, Ordering::Relaxed);
+
             // Remove lines that are too long; they would not be read correctly anyway.
-            if line.len() > config::MAX_LINE_LENGTH {
+            if line.len() > indexing.max_line_length {
+                indexing.lines_skipped.fetch_add(1, Ordering::Relaxed);
+
                 rockyou2024::warn!(
                     target: LOG_TARGET,
                     "Line too long ({} bytes); skipping.",
                     line.len()
                 );
+
+                if let Some(quarantine) = quarantine {
+                    let mut quarantine = quarantine
+                        .lock()
+                        .expect("Failed to acquire lock on the quarantine file; should be uncontended.");
+
+                    if let Err(error) = quarantine
+                        .write_all(line)
+                        .and_then(|()| quarantine.write_all(&[indexing.separator]))
+                    {
+                        rockyou2024::error!(
+                            target: LOG_TARGET,
+                            "Failed to write a rejected line to the quarantine file: {error}"
+                        );
+                    }
+                }
+
                 return None;
             }
 
-            Some(
-                collection.add(line.to_vec()).map_err(|err| {
+            let line = if indexing.normalize_line_endings {
+                normalize_line_ending(line)
+            } else {
+                line
+            };
+
+            let Some((stored, key_source)) = format.split(line) else {
+                indexing.lines_skipped.fetch_add(1, Ordering::Relaxed);
+
+                rockyou2024::warn!(
+                    target: LOG_TARGET,
+                    "Line does not match the expected --format; skipping."
+                );
+                return None;
+            };
+
+            Some(match dry_run {
+                Some(stats) => {
+                    stats.record(
+                        &stored,
+                        indices_of::<{ config::INDEX_LENGTH }, { config::INDEX_DEPTH }>(
+                            &key_source,
+                        ),
+                    );
+                    Ok(())
+                }
+                None => collection.add_under_field(&stored, &key_source).map_err(|err| {
                     anyhow::Error::new(err).context("Failed to insert line into index")
                 }),
-            )
+            })
         })
         .for_each(
             // Do not panic on error; just log it.
@@ -133,9 +1549,13 @@ fn process_chunk(
 
 fn main() {
     if let Err(err) = index() {
+        if err.downcast_ref::<Interrupted>().is_some() {
+            eprintln!("{}; a checkpoint was written to the output directory.", err);
+            // 128 + SIGINT, following the usual shell convention.
+            std::process::exit(130);
+        }
+
         eprintln!("Error: {}", err);
         std::process::exit(1);
-    } else {
-        rockyou2024::info!("Indexing completed successfully.");
     }
 }
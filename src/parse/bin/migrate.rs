@@ -0,0 +1,30 @@
+//! Upgrade an index directory built by an older version of this crate to the
+//! current on-disk layout, in place or into a new directory.
+
+use clap::Parser;
+use rockyou2024::models::migrate;
+
+fn migrate_cmd() -> anyhow::Result<()> {
+    let args = rockyou2024::cli::MigrateArgs::parse();
+    let output = args.output.as_deref().unwrap_or(&args.index);
+
+    let report = migrate(&args.index, output).map_err(|err| {
+        anyhow::Error::new(err).context(format!("Failed to migrate {}", args.index))
+    })?;
+
+    println!(
+        "Migrated {index} into {output}: sharded {sharded} legacy file(s), rebuilt {offsets} missing offsets sidecar(s).",
+        index = args.index,
+        sharded = report.sharded.len(),
+        offsets = report.offsets_rebuilt.len(),
+    );
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(err) = migrate_cmd() {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,34 @@
+//! Split oversized "hot key" index files into longer, more specific keys, so a
+//! search narrows down to a smaller candidate file instead of always scanning one
+//! huge one.
+
+use clap::Parser;
+use rockyou2024::models::rebalance;
+
+fn rebalance_cmd() -> anyhow::Result<()> {
+    let args = rockyou2024::cli::RebalanceArgs::parse();
+
+    let report = rebalance(&args.index, args.threshold_bytes).map_err(|err| {
+        anyhow::Error::new(err).context(format!("Failed to rebalance {}", args.index))
+    })?;
+
+    println!(
+        "Split {keys} oversized key(s) into {created} longer key(s) in {index}.",
+        keys = report.split.len(),
+        created = report.total_created(),
+        index = args.index,
+    );
+
+    for split in &report.split {
+        println!("  {} -> {}", split.key, split.into.join(", "));
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(err) = rebalance_cmd() {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,36 @@
+//! Produce a consistent, checksummed backup of an index directory: every index file
+//! is copied into the destination and a fresh manifest recording each file's
+//! checksum is written alongside them, so `verify` can later confirm the snapshot on
+//! its own, independently of the index it was taken from.
+
+use clap::Parser;
+use rockyou2024::{config, models::IndexCollection};
+
+fn snapshot() -> anyhow::Result<()> {
+    let args = rockyou2024::cli::SnapshotArgs::parse();
+
+    let collection = IndexCollection::<{ config::INDEX_LENGTH }, { config::INDEX_DEPTH }>::open_read_only(
+        args.index.clone().into(),
+    );
+
+    let manifest = collection.snapshot(&args.output).map_err(|err| {
+        anyhow::Error::new(err)
+            .context(format!("Failed to snapshot {} into {}", args.index, args.output))
+    })?;
+
+    println!(
+        "Snapshotted {files} index file(s) from {source} into {destination}.",
+        files = manifest.index_file_hashes.len(),
+        source = args.index,
+        destination = args.output,
+    );
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(err) = snapshot() {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,59 @@
+//! Check an index directory's integrity: recompute each index file's checksum
+//! against the one recorded in the manifest, and confirm its file name's key
+//! round-trips through `key_for_path`, reporting any corrupt, truncated, or missing
+//! index files.
+
+use clap::Parser;
+use rockyou2024::models::IndexManifest;
+
+fn verify() -> anyhow::Result<bool> {
+    let args = rockyou2024::cli::VerifyArgs::parse();
+
+    let manifest = IndexManifest::read(&args.index).map_err(|err| {
+        anyhow::Error::new(err).context(format!("Failed to read the manifest in {}", args.index))
+    })?;
+
+    let report = manifest.verify(&args.index).map_err(|err| {
+        anyhow::Error::new(err).context("Failed to verify the index directory")
+    })?;
+
+    println!("Checked {} index files.", report.checked);
+
+    if !report.missing.is_empty() {
+        println!("\nMissing ({}):", report.missing.len());
+        for key in &report.missing {
+            println!("  {key}");
+        }
+    }
+
+    if !report.corrupt.is_empty() {
+        println!("\nCorrupt or truncated ({}):", report.corrupt.len());
+        for key in &report.corrupt {
+            println!("  {key}");
+        }
+    }
+
+    if !report.key_mismatches.is_empty() {
+        println!("\nKey mismatches ({}):", report.key_mismatches.len());
+        for key in &report.key_mismatches {
+            println!("  {key}");
+        }
+    }
+
+    if report.is_ok() {
+        println!("\nIndex is healthy.");
+    }
+
+    Ok(report.is_ok())
+}
+
+fn main() {
+    match verify() {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(2);
+        }
+    }
+}
@@ -4,6 +4,11 @@
 use hashbrown::HashMap;
 use std::sync::OnceLock;
 
+#[cfg(feature = "custom_substitutions")]
+use std::{fs, io, path::Path};
+
+const LOG_TARGET: &str = "character";
+
 pub enum CharacterClass {
     Alphanumeric(char),
     Punctuation,
@@ -11,6 +16,11 @@ pub enum CharacterClass {
     Chinese,
     Japanese,
     Korean,
+    Cyrillic,
+    Greek,
+    Hebrew,
+    Thai,
+    Devanagari,
     Unclassified,
 }
 
@@ -59,6 +69,21 @@ impl From<char> for CharacterClass {
                         0xFE70..=0xFEFF | // Arabic Presentation Forms-B
                         0x1EE00..=0x1EEFF // Arabic Mathematical Alphabetic Symbols
                         => Self::Arabic,
+                    0x0400..=0x04FF | // Cyrillic
+                        0x0500..=0x052F | // Cyrillic Supplement
+                        0x2DE0..=0x2DFF | // Cyrillic Extended-A
+                        0xA640..=0xA69F   // Cyrillic Extended-B
+                        => Self::Cyrillic,
+                    0x0370..=0x03FF | // Greek and Coptic
+                        0x1F00..=0x1FFF   // Greek Extended
+                        => Self::Greek,
+                    0x0590..=0x05FF | // Hebrew
+                        0xFB1D..=0xFB4F   // Hebrew Presentation Forms
+                        => Self::Hebrew,
+                    0x0E00..=0x0E7F // Thai
+                        => Self::Thai,
+                    0x0900..=0x097F // Devanagari
+                        => Self::Devanagari,
                     _ => Self::Unclassified,
                 }
             }
@@ -77,6 +102,11 @@ impl CharacterClass {
             Self::Japanese => Some('2'),
             Self::Korean => Some('3'),
             Self::Arabic => Some('4'),
+            Self::Cyrillic => Some('5'),
+            Self::Greek => Some('6'),
+            Self::Hebrew => Some('7'),
+            Self::Thai => Some('8'),
+            Self::Devanagari => Some('9'),
             _ => None,
         }
     }
@@ -96,8 +126,14 @@ macro_rules! create_index {
 
 /// Get the character mapping.
 ///
-/// If the mapping has not been created, it will be created.
+/// If a custom mapping has been installed via [`set_custom_mapping`], it takes
+/// precedence; otherwise the built-in leet-speak mapping is returned, being created
+/// the first time this is called.
 pub fn get_fuzzy_mapping() -> &'static HashMap<char, char> {
+    if let Some(custom) = CUSTOM_CHAR_MAP.get() {
+        return custom;
+    }
+
     FUZZY_CHAR_MAP.get_or_init(|| {
         create_index! {
             '4' => 'a',
@@ -121,3 +157,239 @@ pub fn get_fuzzy_mapping() -> &'static HashMap<char, char> {
         }
     })
 }
+
+static KEYBOARD_ADJACENCY_MAP: OnceLock<HashMap<char, char>> = OnceLock::new();
+
+/// Get the keyboard-adjacency mapping.
+///
+/// Every key on a QWERTY row is folded down to the first key of that row, and every
+/// shifted symbol is folded down to the unshifted digit it sits above, so that keys
+/// typed one physical position off from each other (e.g. `q`/`w`, or `1`/`!`) compare
+/// equal. If the mapping has not been created, it will be created.
+pub fn get_keyboard_adjacency_mapping() -> &'static HashMap<char, char> {
+    KEYBOARD_ADJACENCY_MAP.get_or_init(|| {
+        create_index! {
+            'w' => 'q',
+            'e' => 'q',
+            'r' => 'q',
+            't' => 'q',
+            'y' => 'q',
+            'u' => 'q',
+            'i' => 'q',
+            'o' => 'q',
+            'p' => 'q',
+            's' => 'a',
+            'd' => 'a',
+            'f' => 'a',
+            'g' => 'a',
+            'h' => 'a',
+            'j' => 'a',
+            'k' => 'a',
+            'l' => 'a',
+            'x' => 'z',
+            'c' => 'z',
+            'v' => 'z',
+            'b' => 'z',
+            'n' => 'z',
+            'm' => 'z',
+            '!' => '1',
+            '@' => '2',
+            '#' => '3',
+            '$' => '4',
+            '%' => '5',
+            '^' => '6',
+            '&' => '7',
+            '*' => '8',
+            '(' => '9',
+            ')' => '0',
+        }
+    })
+}
+
+static CUSTOM_CHAR_MAP: OnceLock<HashMap<char, char>> = OnceLock::new();
+
+/// Install a custom character-substitution table, overriding the built-in leet-speak
+/// mapping returned by [`get_fuzzy_mapping`] for the remainder of the process.
+///
+/// A custom mapping can only be installed once per process, since it backs a static
+/// [`OnceLock`]. Installing the same mapping more than once is a harmless no-op;
+/// installing a different one after the first is ignored with a warning, since both
+/// an index built with the first mapping and one built with the second cannot be
+/// searched correctly at the same time.
+pub fn set_custom_mapping(mapping: HashMap<char, char>) {
+    if let Err(mapping) = CUSTOM_CHAR_MAP.set(mapping) {
+        if CUSTOM_CHAR_MAP.get() != Some(&mapping) {
+            crate::warn!(
+                target: LOG_TARGET,
+                "A different custom character-substitution table is already active; \
+                 ignoring the newly requested one.",
+            );
+        }
+    }
+}
+
+/// Load a custom character-substitution table from a TOML or CSV file, chosen by the
+/// file's extension.
+///
+/// A TOML file is expected to contain a `[substitutions]` table mapping one-character
+/// strings to one-character strings, e.g. `"4" = "a"`. A CSV file is expected to have
+/// one `from,to` pair per line, with no header row, e.g. `4,a`.
+#[cfg(feature = "custom_substitutions")]
+pub fn load_custom_mapping(path: impl AsRef<Path>) -> io::Result<HashMap<char, char>> {
+    let path = path.as_ref();
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("toml") => load_custom_mapping_from_toml(path),
+        Some("csv") => load_custom_mapping_from_csv(path),
+        other => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Unsupported substitution table extension {other:?}; expected 'toml' or 'csv'."),
+        )),
+    }
+}
+
+#[cfg(feature = "custom_substitutions")]
+fn load_custom_mapping_from_toml(path: &Path) -> io::Result<HashMap<char, char>> {
+    #[derive(serde::Deserialize)]
+    struct SubstitutionTable {
+        substitutions: std::collections::HashMap<String, String>,
+    }
+
+    let contents = fs::read_to_string(path)?;
+    let table: SubstitutionTable =
+        toml::from_str(&contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    pairs_to_mapping(table.substitutions.into_iter())
+}
+
+#[cfg(feature = "custom_substitutions")]
+fn load_custom_mapping_from_csv(path: &Path) -> io::Result<HashMap<char, char>> {
+    let contents = fs::read_to_string(path)?;
+
+    let pairs = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.split_once(',')
+                .map(|(from, to)| (from.to_owned(), to.to_owned()))
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Malformed substitution CSV line (expected 'from,to'): {line:?}"),
+                    )
+                })
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    pairs_to_mapping(pairs.into_iter())
+}
+
+/// Convert `(from, to)` string pairs into a character mapping, erroring if either side
+/// of a pair is not exactly one character.
+#[cfg(feature = "custom_substitutions")]
+fn pairs_to_mapping(
+    pairs: impl Iterator<Item = (String, String)>,
+) -> io::Result<HashMap<char, char>> {
+    pairs
+        .map(|(from, to)| Ok((single_char(&from)?, single_char(&to)?)))
+        .collect()
+}
+
+/// Parse `s` as a single character, erroring if it is empty or contains more than one.
+#[cfg(feature = "custom_substitutions")]
+fn single_char(s: &str) -> io::Result<char> {
+    let mut chars = s.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Expected a single character in the substitution table, got {s:?}."),
+        )),
+    }
+}
+
+#[cfg(all(test, feature = "custom_substitutions"))]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "custom_substitutions")]
+    #[test]
+    fn loads_a_toml_substitution_table() {
+        let dir = std::env::temp_dir().join(format!("{}-toml", std::process::id()));
+        fs::create_dir_all(&dir).expect("Failed to create temp dir.");
+        let path = dir.join("substitutions.toml");
+        fs::write(&path, "[substitutions]\n\"4\" = \"a\"\n\"8\" = \"b\"\n")
+            .expect("Failed to write temp file.");
+
+        let mapping = load_custom_mapping(&path).expect("Failed to load the substitution table.");
+        assert_eq!(mapping.get(&'4'), Some(&'a'));
+        assert_eq!(mapping.get(&'8'), Some(&'b'));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(feature = "custom_substitutions")]
+    #[test]
+    fn loads_a_csv_substitution_table() {
+        let dir = std::env::temp_dir().join(format!("{}-csv", std::process::id()));
+        fs::create_dir_all(&dir).expect("Failed to create temp dir.");
+        let path = dir.join("substitutions.csv");
+        fs::write(&path, "4,a\n8,b\n").expect("Failed to write temp file.");
+
+        let mapping = load_custom_mapping(&path).expect("Failed to load the substitution table.");
+        assert_eq!(mapping.get(&'4'), Some(&'a'));
+        assert_eq!(mapping.get(&'8'), Some(&'b'));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(feature = "custom_substitutions")]
+    #[test]
+    fn rejects_a_multi_character_substitution() {
+        let dir = std::env::temp_dir().join(format!("{}-invalid", std::process::id()));
+        fs::create_dir_all(&dir).expect("Failed to create temp dir.");
+        let path = dir.join("substitutions.csv");
+        fs::write(&path, "45,a\n").expect("Failed to write temp file.");
+
+        assert!(load_custom_mapping(&path).is_err());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
+
+#[cfg(test)]
+mod classification_tests {
+    use super::*;
+
+    #[test]
+    fn classifies_cyrillic() {
+        assert!(matches!(CharacterClass::from('п'), CharacterClass::Cyrillic));
+        assert_eq!(CharacterClass::from('п').to_substitution_symbol(), Some('5'));
+    }
+
+    #[test]
+    fn classifies_greek() {
+        assert!(matches!(CharacterClass::from('α'), CharacterClass::Greek));
+        assert_eq!(CharacterClass::from('α').to_substitution_symbol(), Some('6'));
+    }
+
+    #[test]
+    fn classifies_hebrew() {
+        assert!(matches!(CharacterClass::from('א'), CharacterClass::Hebrew));
+        assert_eq!(CharacterClass::from('א').to_substitution_symbol(), Some('7'));
+    }
+
+    #[test]
+    fn classifies_thai() {
+        assert!(matches!(CharacterClass::from('ก'), CharacterClass::Thai));
+        assert_eq!(CharacterClass::from('ก').to_substitution_symbol(), Some('8'));
+    }
+
+    #[test]
+    fn classifies_devanagari() {
+        assert!(matches!(CharacterClass::from('क'), CharacterClass::Devanagari));
+        assert_eq!(CharacterClass::from('क').to_substitution_symbol(), Some('9'));
+    }
+}
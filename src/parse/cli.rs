@@ -4,11 +4,31 @@ use clap::Parser;
 
 use crate::config;
 
+/// Parse `--separator`: either a single ASCII byte, or one of the backslash escapes
+/// `\n`, `\r`, `\t`, `\0`, since a literal null byte cannot be passed as a shell
+/// argument.
+fn parse_separator(input: &str) -> Result<u8, String> {
+    match input {
+        "\\n" => Ok(b'\n'),
+        "\\r" => Ok(b'\r'),
+        "\\t" => Ok(b'\t'),
+        "\\0" => Ok(0),
+        _ if input.len() == 1 && input.is_ascii() => Ok(input.as_bytes()[0]),
+        _ => Err(format!(
+            "--separator must be a single ASCII character or one of \\n, \\r, \\t, \\0; got {input:?}."
+        )),
+    }
+}
+
 /// Command line arguments.
 #[derive(Parser, Debug, Clone)]
 pub struct CliArgs {
-    #[arg(short, long, default_value_t = config::SOURCE_PATH.to_owned())]
-    pub input: String,
+    /// One or more input files to index. Directories are expanded to their immediate
+    /// files (non-recursively); a single `-` reads from standard input instead, so a
+    /// dump can be piped through a decompressor or filter without touching disk
+    /// twice. May be passed more than once.
+    #[arg(short, long, num_args = 1.., default_value = config::SOURCE_PATH)]
+    pub input: Vec<String>,
 
     #[arg(short, long, default_value_t = config::INDEX_PATH.to_owned())]
     pub output: String,
@@ -21,4 +41,263 @@ pub struct CliArgs {
 
     #[arg(long, default_value_t = config::MAX_CHUNK_SIZE)]
     pub max_chunk_size: usize,
+
+    /// Resume a previous indexing run from the checkpoint left in `output`, if any.
+    #[arg(long, default_value_t = false)]
+    pub resume: bool,
+
+    /// Path to a custom character-substitution table (TOML or CSV) to use instead of
+    /// the built-in leet-speak mapping for both indexing and later fuzzy search.
+    /// Requires the `custom_substitutions` feature.
+    #[arg(long)]
+    pub substitution_map: Option<String>,
+
+    /// Path to a whitespace-separated word list to use for the common-words index
+    /// bucket instead of the compiled-in English word list.
+    #[arg(long)]
+    pub common_words: Option<String>,
+
+    /// Emit a report of the N most frequently occurring lines to
+    /// `<output>/frequency_report.json` once indexing completes. Requires the
+    /// `frequency` feature.
+    #[arg(long)]
+    pub top_frequencies: Option<usize>,
+
+    /// Comma-separated list of hash algorithms ("md5", "sha1", "ntlm") to build a
+    /// secondary hash-to-plaintext lookup index for, alongside the primary index.
+    /// Requires the `hash_lookup` feature.
+    #[arg(long, value_delimiter = ',')]
+    pub hash_algorithms: Vec<String>,
+
+    /// Merge every index file into a single deduplicated, sorted wordlist at this
+    /// path, suitable for hashcat/john.
+    #[arg(long)]
+    pub export_wordlist: Option<String>,
+
+    /// Export every index file into a single SQLite database at this path, with
+    /// `(key, line, count)` rows and indexes, for querying with SQL tools. Requires
+    /// the `sqlite_export` feature.
+    #[arg(long)]
+    pub export_sqlite: Option<String>,
+
+    /// Also build a secondary index of every line reversed, alongside the primary
+    /// index, so an anchored-suffix query ("ends with ...") can be routed to it
+    /// instead of falling back to a full scan. Requires the `reversed_index` feature.
+    #[arg(long, default_value_t = false)]
+    pub reversed_index: bool,
+
+    /// Also build a secondary index of every line pre-lowercased, alongside the
+    /// primary index, so a case-insensitive search can be routed to it instead of
+    /// re-lowercasing every index file on the fly. Requires the `case_folded_index`
+    /// feature.
+    #[arg(long, default_value_t = false)]
+    pub case_folded_index: bool,
+
+    /// Also build a secondary index of every line pre-fuzzed (leet-speak folded),
+    /// alongside the primary index, so a fuzzy search can be routed to it instead of
+    /// re-folding every index file on the fly. Requires the `fuzzed_index` feature.
+    #[arg(long, default_value_t = false)]
+    pub fuzzed_index: bool,
+
+    /// Also build a secondary index bucketing every line under all of its n-grams
+    /// this many characters apart, alongside the primary index, so a substring query
+    /// anywhere in a line can narrow its candidate files instead of falling back to
+    /// a full scan. A value of 1 covers every position (larger index, no missed
+    /// matches); larger values shrink the index at the cost of only catching matches
+    /// that land on a bucketed position. Requires the `ngram_index` feature.
+    #[arg(long)]
+    pub ngram_stride: Option<usize>,
+
+    /// Also build a secondary index of every line's Soundex code, alongside the
+    /// primary index, so a phonetic search (`SearchStyle::Phonetic`) can be routed to
+    /// it instead of falling back to a full scan re-encoding every line on the fly.
+    /// Requires the `phonetic_index` feature.
+    #[arg(long, default_value_t = false)]
+    pub phonetic_index: bool,
+
+    /// Also build a secondary index bucketing every line under all of its own
+    /// deletion variants up to this many deletions, alongside the primary index, so a
+    /// bounded edit-distance search within this distance can be resolved by exact
+    /// dictionary lookups instead of scanning every candidate index file. Requires
+    /// the `symspell_index` feature.
+    #[arg(long)]
+    pub symspell_max_distance: Option<usize>,
+
+    /// How to interpret each input line ("plain", "combo", "csv", or "jsonl").
+    /// "combo" splits each line on `--combo-delimiter` and indexes only the field
+    /// after it (e.g. the password in an `email:password` dump), optionally keeping
+    /// the field before it in the stored line via `--combo-keep-email` so it can
+    /// still be filtered by email later. "csv" parses each line as a delimited
+    /// record on `--csv-delimiter`, handling quoted fields, and indexes only column
+    /// `--csv-column`. Requires the `csv_input` feature. "jsonl" parses each line as
+    /// a JSON object and indexes only the field named by `--field`.
+    #[arg(long, default_value = "plain")]
+    pub format: String,
+
+    /// The delimiter `--format combo` splits each line on, at its first occurrence.
+    #[arg(long, default_value_t = ':')]
+    pub combo_delimiter: char,
+
+    /// With `--format combo`, store the full line (rather than just the indexed
+    /// field) so the field before the delimiter remains available for later
+    /// filtering.
+    #[arg(long, default_value_t = false)]
+    pub combo_keep_email: bool,
+
+    /// The delimiter `--format csv` splits each record on. Requires the `csv_input`
+    /// feature.
+    #[arg(long, default_value_t = ',')]
+    pub csv_delimiter: char,
+
+    /// The zero-based column `--format csv` indexes and stores out of each record.
+    /// Requires the `csv_input` feature.
+    #[arg(long, default_value_t = 0)]
+    pub csv_column: usize,
+
+    /// The JSON object field `--format jsonl` indexes and stores out of each line.
+    #[arg(long)]
+    pub field: Option<String>,
+
+    /// The byte each input file is split into lines on, before `--format` runs on
+    /// each one. A single ASCII character, or one of the backslash escapes `\n`,
+    /// `\r`, `\t`, `\0` (e.g. `--separator '\0'` for null-delimited dumps).
+    #[arg(long, default_value = "\\n", value_parser = parse_separator)]
+    pub separator: u8,
+
+    /// Strip a trailing `\r` and any other trailing ASCII whitespace from each line
+    /// before `--format` runs on it, so a Windows-origin (CRLF) dump does not index
+    /// `\r` as part of the line and break exact matches against it.
+    #[arg(long, default_value_t = false)]
+    pub normalize_line_endings: bool,
+
+    /// The storage backend for the secondary hash-lookup index built from
+    /// `--hash-algorithms` ("file" or "kv"). "kv" keeps every hash prefix bucket in a
+    /// single embedded key-value store instead of one file each, and requires the
+    /// `kv_storage` feature.
+    #[arg(long, default_value = "file")]
+    pub backend: String,
+
+    /// Emit structured JSON log lines instead of plain text, for ingestion into log
+    /// pipelines such as ELK/Loki. Equivalent to setting
+    /// `ROCKYOU2024_LOG_FORMAT=json`.
+    #[arg(long, default_value_t = false)]
+    pub log_json: bool,
+
+    /// Periodically print a JSON progress record (bytes processed, rate, ETA, flush
+    /// count) to stderr, alongside the interactive progress bar, so wrapper scripts
+    /// and orchestration tooling can track long indexing runs without parsing the
+    /// bar's terminal output.
+    #[arg(long, default_value_t = false)]
+    pub progress_json: bool,
+
+    /// Reject a line longer than this many bytes instead of indexing it. Cannot
+    /// exceed `MAX_LINE_LENGTH` (the reader's fixed-size line buffer); a larger value
+    /// is clamped down to it with a warning.
+    #[arg(long, default_value_t = config::MAX_LINE_LENGTH)]
+    pub max_line_length: usize,
+
+    /// Append every line rejected by `--max-line-length` to this file, one raw line
+    /// per line, instead of only logging a warning, so what was excluded can be
+    /// audited afterwards.
+    #[arg(long)]
+    pub quarantine_file: Option<String>,
+
+    /// Also print the end-of-run summary (lines read, lines skipped, unique keys
+    /// created, bytes written, duration, throughput) as one line of JSON to stderr,
+    /// alongside the human-readable summary, for ingestion into log pipelines or
+    /// wrapper scripts.
+    #[arg(long, default_value_t = false)]
+    pub summary_json: bool,
+
+    /// How aggressively to force index writes to durable storage instead of relying
+    /// on the OS's own write-back caching ("none", "fsync-on-flush",
+    /// "fsync-on-finalize", or "wal"), trading indexing speed for crash safety on
+    /// flaky disks. "wal" additionally survives a crash between flushes without
+    /// having to re-read the original input, by replaying an unflushed
+    /// write-ahead log the next time indexing starts.
+    #[arg(long, default_value = "none")]
+    pub durability: String,
+
+    /// Run the full read/normalise/index-key pipeline without writing anything to
+    /// disk, reporting how many lines and index buckets a real run over the same
+    /// input would produce - useful for sizing disk space before a multi-hour run.
+    /// Secondary indices, hash lookups, wordlist/SQLite export, checkpointing, and
+    /// `--quarantine-file` are all skipped, since they only make sense for a run
+    /// that actually writes an index.
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+/// Command line arguments for the `analyze` binary.
+#[derive(Parser, Debug, Clone)]
+pub struct AnalyzeArgs {
+    /// One or more raw input files to analyze. Directories are expanded to their
+    /// immediate files (non-recursively); may be passed more than once.
+    #[arg(short, long, num_args = 1.., default_value = config::SOURCE_PATH)]
+    pub input: Vec<String>,
+
+    /// The number of most frequent masks to include in the report.
+    #[arg(long, default_value_t = 20)]
+    pub top_masks: usize,
+}
+
+/// Command line arguments for the `verify` binary.
+#[derive(Parser, Debug, Clone)]
+pub struct VerifyArgs {
+    /// The index directory to verify.
+    #[arg(short, long, default_value_t = config::INDEX_PATH.to_owned())]
+    pub index: String,
+}
+
+/// Command line arguments for the `gc` binary.
+#[derive(Parser, Debug, Clone)]
+pub struct GcArgs {
+    /// The index directory to garbage-collect.
+    #[arg(short, long, default_value_t = config::INDEX_PATH.to_owned())]
+    pub index: String,
+}
+
+/// Command line arguments for the `rebalance` binary.
+#[derive(Parser, Debug, Clone)]
+pub struct RebalanceArgs {
+    /// The index directory to rebalance.
+    #[arg(short, long, default_value_t = config::INDEX_PATH.to_owned())]
+    pub index: String,
+
+    /// Split an index file once it exceeds this many bytes, into longer keys taking
+    /// one more character from each line that can be split further.
+    #[arg(short, long, default_value_t = config::REBALANCE_THRESHOLD_BYTES)]
+    pub threshold_bytes: u64,
+}
+
+/// Command line arguments for the `migrate` binary.
+#[derive(Parser, Debug, Clone)]
+pub struct MigrateArgs {
+    /// The index directory to migrate to the current on-disk layout.
+    #[arg(short, long, default_value_t = config::INDEX_PATH.to_owned())]
+    pub index: String,
+
+    /// Where to write the migrated index. Defaults to `--index`, migrating it in
+    /// place; if given a different path, `--index` is left untouched and every
+    /// file is hardlinked into `--output` before being upgraded.
+    #[arg(short, long)]
+    pub output: Option<String>,
+}
+
+/// Command line arguments for the `snapshot` binary.
+#[derive(Parser, Debug, Clone)]
+pub struct SnapshotArgs {
+    /// The index directory to snapshot. Opened read-only, so this is safe to run
+    /// alongside a concurrently running indexer, though the snapshot only reflects
+    /// whatever that indexer has flushed to disk so far.
+    #[arg(short, long, default_value_t = config::INDEX_PATH.to_owned())]
+    pub index: String,
+
+    /// Where to write the snapshot. Created if it does not already exist; every
+    /// index file is copied byte-for-byte rather than hardlinked, so the snapshot
+    /// stays a true point-in-time copy even if `--index` keeps being written to
+    /// afterwards, at the cost of taking time and disk space proportional to the
+    /// size of `--index`.
+    #[arg(short, long)]
+    pub output: String,
 }
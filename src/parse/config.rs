@@ -33,6 +33,29 @@ pub const MAX_LINE_LENGTH: usize = 256;
 /// The default cache size.
 pub const CACHE_SIZE: usize = 1024;
 
+/// The maximum number of bytes of lines held in memory per run by the external
+/// sort/dedup pass over an index file (used when the `deduplicate` feature is off).
+pub const EXTERNAL_SORT_RUN_SIZE: usize = 2_usize.pow(20);
+
+/// The maximum number of bytes of unique lines an index file holds in memory before
+/// spilling them to disk, when the `deduplicate` feature is on; see
+/// [`crate::models::IndexFile`]'s use of a spilling dedup set.
+pub const DEDUP_SPILL_MEMORY_BUDGET: usize = 2_usize.pow(20);
+
+/// How often the background thread spawned by
+/// [`IndexCollection::spawn_auto_flush`](crate::models::IndexCollection::spawn_auto_flush)
+/// wakes to check whether a flush is due, at most.
+pub const AUTO_FLUSH_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// The number of the largest index file buffers the background auto-flush thread
+/// flushes each time a flush is due, so a single burst does not stall on every
+/// buffered file at once.
+pub const AUTO_FLUSH_BATCH_SIZE: usize = 8;
+
+/// The default size an index file has to exceed before
+/// [`crate::models::rebalance`] splits it into longer, more specific keys.
+pub const REBALANCE_THRESHOLD_BYTES: u64 = 64 * 2_usize.pow(20) as u64;
+
 #[cfg(test)]
 #[cfg(not(feature = "skip_index_write"))]
 pub(crate) const TEST_DIR: &str = "./.tests";
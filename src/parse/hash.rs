@@ -0,0 +1,100 @@
+//! Password hash digests, used by [`crate::models::HashIndex`] to look up whether a
+//! given hash's plaintext exists in the dump, without hashing every candidate at
+//! query time.
+
+use std::str::FromStr;
+
+/// A hash algorithm supported by [`crate::models::HashIndex`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    /// NTLM, i.e. MD4 of the UTF-16LE encoding of the plaintext.
+    Ntlm,
+}
+
+impl HashAlgorithm {
+    /// This algorithm's name, used both as a CLI value and as the on-disk directory
+    /// name for its index.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Md5 => "md5",
+            Self::Sha1 => "sha1",
+            Self::Ntlm => "ntlm",
+        }
+    }
+
+    /// Hex-encode the digest of `plaintext` under this algorithm.
+    pub fn digest_hex(self, plaintext: &[u8]) -> String {
+        match self {
+            Self::Md5 => {
+                use md5::{Digest, Md5};
+                format!("{:x}", Md5::digest(plaintext))
+            }
+            Self::Sha1 => {
+                use sha1::{Digest, Sha1};
+                format!("{:x}", Sha1::digest(plaintext))
+            }
+            Self::Ntlm => {
+                use md4::{Digest, Md4};
+                let utf16le: Vec<u8> = String::from_utf8_lossy(plaintext)
+                    .encode_utf16()
+                    .flat_map(u16::to_le_bytes)
+                    .collect();
+                format!("{:x}", Md4::digest(&utf16le))
+            }
+        }
+    }
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "md5" => Ok(Self::Md5),
+            "sha1" => Ok(Self::Sha1),
+            "ntlm" => Ok(Self::Ntlm),
+            other => Err(format!(
+                "Unknown hash algorithm {other:?}; expected 'md5', 'sha1', or 'ntlm'."
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn md5_matches_a_known_digest() {
+        assert_eq!(
+            HashAlgorithm::Md5.digest_hex(b"password"),
+            "5f4dcc3b5aa765d61d8327deb882cf99"
+        );
+    }
+
+    #[test]
+    fn sha1_matches_a_known_digest() {
+        assert_eq!(
+            HashAlgorithm::Sha1.digest_hex(b"password"),
+            "5baa61e4c9b93f3f0682250b6cf8331b7ee68fd8"
+        );
+    }
+
+    #[test]
+    fn ntlm_matches_a_known_digest() {
+        assert_eq!(
+            HashAlgorithm::Ntlm.digest_hex(b"password"),
+            "8846f7eaee8fb117ad06bdd830b7586c"
+        );
+    }
+
+    #[test]
+    fn parses_algorithm_names_case_insensitively() {
+        assert_eq!("MD5".parse(), Ok(HashAlgorithm::Md5));
+        assert_eq!("Sha1".parse(), Ok(HashAlgorithm::Sha1));
+        assert_eq!("ntlm".parse(), Ok(HashAlgorithm::Ntlm));
+        assert!("bcrypt".parse::<HashAlgorithm>().is_err());
+    }
+}
@@ -7,28 +7,102 @@ const INDEX_PREFIX: &str = "subset_";
 /// The suffix for index files.
 const INDEX_EXTENSION: &str = "csv";
 
+/// Returns the first-character shard sub-directory for a given key, nested under
+/// `dir`.
+///
+/// Index files are sharded by the first character of their key so that a single
+/// directory does not end up holding tens of thousands of entries, which is slow to
+/// list on some filesystems.
+pub(crate) fn shard_dir_for_key(key: &str, dir: impl AsRef<path::Path>) -> path::PathBuf {
+    let mut path_buf = dir.as_ref().to_path_buf();
+    if let Some(first_char) = key.chars().next() {
+        path_buf.push(first_char.to_string());
+    }
+    path_buf
+}
+
 /// Returns the path for the given key and path.
+///
+/// Index files are nested under a first-character shard sub-directory (see
+/// [`shard_dir_for_key`]); for backward compatibility, if a file for this key
+/// already exists directly under `dir` from before sharding was introduced, that
+/// flat path is returned instead so existing indices keep working without migration.
 pub fn path_for_key(
     key: impl AsRef<str>,
     dir: impl AsRef<path::Path>,
 ) -> io::Result<path::PathBuf> {
-    let mut path_buf = path::Path::new(dir.as_ref()).to_path_buf();
+    let key = key.as_ref();
+    let dir = path::Path::new(dir.as_ref()).to_path_buf();
 
     // There is no need to check if the directory exists in tests, since many of them don't.
     #[cfg(not(test))]
-    if !path_buf.is_dir() {
+    if !dir.is_dir() {
         return Err(io::Error::new(
             io::ErrorKind::NotFound,
             format!(
                 "The directory does not exist at {path:?}",
-                path = path_buf.as_os_str()
+                path = dir.as_os_str()
             ),
         ));
     }
-    let file_name = format!("{}{}.{}", INDEX_PREFIX, key.as_ref(), INDEX_EXTENSION);
+    let file_name = format!("{}{}.{}", INDEX_PREFIX, key, INDEX_EXTENSION);
+
+    let flat_path = dir.join(&file_name);
+    if flat_path.is_file() {
+        return Ok(flat_path);
+    }
+
+    Ok(shard_dir_for_key(key, dir).join(file_name))
+}
+
+/// Returns the path to the line-offset sidecar table for the given key and path.
+pub fn offsets_path_for_key(
+    key: impl AsRef<str>,
+    dir: impl AsRef<path::Path>,
+) -> io::Result<path::PathBuf> {
+    path_for_key(key, dir).map(|path| {
+        let mut file_name = path.file_name().expect("index paths always have a file name").to_owned();
+        file_name.push(".offsets");
+        path.with_file_name(file_name)
+    })
+}
+
+/// Returns the path to the finite-state-transducer sidecar for the given key and
+/// path; see [`crate::models::FstIndexSet`].
+#[cfg(feature = "fst_index")]
+pub fn fst_path_for_key(
+    key: impl AsRef<str>,
+    dir: impl AsRef<path::Path>,
+) -> io::Result<path::PathBuf> {
+    path_for_key(key, dir).map(|path| {
+        let mut file_name = path.file_name().expect("index paths always have a file name").to_owned();
+        file_name.push(".fst");
+        path.with_file_name(file_name)
+    })
+}
+
+/// Returns the path to the write-ahead log sidecar for the given key and path; see
+/// [`crate::models::DurabilityPolicy::Wal`].
+pub fn wal_path_for_key(
+    key: impl AsRef<str>,
+    dir: impl AsRef<path::Path>,
+) -> io::Result<path::PathBuf> {
+    path_for_key(key, dir).map(|path| {
+        let mut file_name = path.file_name().expect("index paths always have a file name").to_owned();
+        file_name.push(".wal");
+        path.with_file_name(file_name)
+    })
+}
 
-    path_buf.push(file_name);
-    Ok(path_buf)
+/// Returns the key for the given path if it is an index file; otherwise, returns
+/// `None`.
+///
+/// Unlike [`key_for_path`], this does not require the `search` feature, since it is
+/// used by [`crate::models::FileStorage::list_keys`] as well.
+pub(crate) fn key_for_file_name(file_name: &str) -> Option<&str> {
+    file_name
+        .strip_prefix(INDEX_PREFIX)?
+        .strip_suffix(&format!(".{INDEX_EXTENSION}"))
 }
 
 #[cfg(feature = "search")]
@@ -123,4 +197,36 @@ mod tests {
         "{}.{}",
         INDEX_PREFIX, INDEX_EXTENSION
     )));
+
+    #[test]
+    fn new_keys_are_nested_under_a_first_character_shard() {
+        let dir = "/path/to/dir";
+        let path = path_for_key("password", dir).expect("Failed to get path for key.");
+
+        assert_eq!(
+            path,
+            path::PathBuf::from(dir)
+                .join("p")
+                .join(format!("{INDEX_PREFIX}password.{INDEX_EXTENSION}"))
+        );
+    }
+
+    #[test]
+    fn a_pre_existing_flat_file_is_returned_instead_of_its_shard() {
+        let dir = std::env::temp_dir().join(format!(
+            "rockyou2024-index-key-path-test-{pid}",
+            pid = std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).expect("Failed to create test directory.");
+
+        let flat_path = dir.join(format!("{INDEX_PREFIX}password.{INDEX_EXTENSION}"));
+        std::fs::write(&flat_path, b"").expect("Failed to create legacy flat file.");
+
+        assert_eq!(
+            path_for_key("password", &dir).expect("Failed to get path for key."),
+            flat_path
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
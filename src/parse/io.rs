@@ -0,0 +1,31 @@
+//! Helpers for turning CLI `--input` arguments into concrete files to read.
+
+use std::{fs, io, path::Path, path::PathBuf};
+
+/// Expand `--input` arguments into a flat, ordered list of files to read.
+///
+/// A directory is expanded to its immediate files (non-recursively), sorted by name;
+/// a plain file is kept as-is. Files are concatenated in the order the `--input`
+/// arguments were given, so a checkpoint's `file_index` stays meaningful across
+/// repeated `--resume` runs with the same arguments.
+pub fn resolve_inputs(inputs: &[String]) -> io::Result<Vec<PathBuf>> {
+    let mut resolved = Vec::new();
+
+    for input in inputs {
+        let path = Path::new(input);
+
+        if path.is_dir() {
+            let mut entries: Vec<PathBuf> = fs::read_dir(path)?
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file())
+                .collect();
+            entries.sort();
+            resolved.extend(entries);
+        } else {
+            resolved.push(path.to_path_buf());
+        }
+    }
+
+    Ok(resolved)
+}
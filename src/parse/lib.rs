@@ -2,11 +2,17 @@
 //!
 //! This module is responsible for parsing the input data into the indices.
 
+pub mod analysis;
 pub mod automatons;
 pub mod character;
 pub mod cli;
 pub mod config;
 
+#[cfg(feature = "hash_lookup")]
+pub mod hash;
+
+pub mod io;
+
 mod index_key_path;
 pub use index_key_path::*;
 
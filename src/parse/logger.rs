@@ -3,24 +3,62 @@
 
 use std::sync::OnceLock;
 
-/// Lock for initialising the logger.
+/// Lock for initialising the tracing subscriber.
 static INIT: OnceLock<()> = OnceLock::new();
 
-/// Initialises the logger.
+/// The environment variable that selects structured JSON log output when set to
+/// `"json"` (case-insensitive), for callers that would rather not thread a CLI flag
+/// through. [`set_json_format`] achieves the same thing programmatically.
+pub const LOG_FORMAT_ENV_VAR: &str = "ROCKYOU2024_LOG_FORMAT";
+
+/// Set by [`set_json_format`] to force JSON output regardless of
+/// [`LOG_FORMAT_ENV_VAR`]; left unset to fall back to the environment variable.
+static JSON_FORMAT: OnceLock<bool> = OnceLock::new();
+
+/// Forces structured JSON log output on the next call to [`init`], for callers such
+/// as a `--log-format json` CLI flag that want to select it without relying on
+/// [`LOG_FORMAT_ENV_VAR`]. Has no effect if the subscriber has already been
+/// initialised, so this must be called before the first log line is emitted.
+pub fn set_json_format() {
+    let _ = JSON_FORMAT.set(true);
+}
+
+/// Whether the log output should be structured JSON rather than plain text.
+fn use_json_format() -> bool {
+    *JSON_FORMAT.get().unwrap_or(&false)
+        || std::env::var(LOG_FORMAT_ENV_VAR).is_ok_and(|value| value.eq_ignore_ascii_case("json"))
+}
+
+/// Initialises the global tracing subscriber, honouring `RUST_LOG` the same way the
+/// previous `env_logger`-based setup did. Emits structured JSON lines (including
+/// `target`, `level` and any span fields such as `key`/`query`) instead of plain
+/// text when [`use_json_format`] says so, so the output can be ingested by log
+/// pipelines such as ELK/Loki.
 pub fn init() {
-    INIT.get_or_init(env_logger::init);
+    INIT.get_or_init(|| {
+        let env_filter = tracing_subscriber::EnvFilter::from_default_env();
+
+        if use_json_format() {
+            tracing_subscriber::fmt()
+                .json()
+                .with_env_filter(env_filter)
+                .init();
+        } else {
+            tracing_subscriber::fmt().with_env_filter(env_filter).init();
+        }
+    });
 }
 
 #[macro_export]
 macro_rules! trace {
     (target: $target:expr, $($arg:tt)+) => (
         $crate::logger::init();
-        log::trace!(target: $target, $($arg)+);
+        tracing::trace!(target: $target, $($arg)+);
     );
 
     ($($arg:tt)+) => (
         $crate::logger::init();
-        log::trace!($($arg)+);
+        tracing::trace!($($arg)+);
     )
 }
 
@@ -28,12 +66,12 @@ macro_rules! trace {
 macro_rules! debug {
     (target: $target:expr, $($arg:tt)+) => (
         $crate::logger::init();
-        log::debug!(target: $target, $($arg)+);
+        tracing::debug!(target: $target, $($arg)+);
     );
 
     ($($arg:tt)+) => (
         $crate::logger::init();
-        log::debug!($($arg)+);
+        tracing::debug!($($arg)+);
     )
 }
 
@@ -41,12 +79,12 @@ macro_rules! debug {
 macro_rules! info {
     (target: $target:expr, $($arg:tt)+) => (
         $crate::logger::init();
-        log::info!(target: $target, $($arg)+);
+        tracing::info!(target: $target, $($arg)+);
     );
 
     ($($arg:tt)+) => (
         $crate::logger::init();
-        log::info!($($arg)+);
+        tracing::info!($($arg)+);
     )
 }
 
@@ -54,12 +92,12 @@ macro_rules! info {
 macro_rules! warn {
     (target: $target:expr, $($arg:tt)+) => (
         $crate::logger::init();
-        log::warn!(target: $target, $($arg)+);
+        tracing::warn!(target: $target, $($arg)+);
     );
 
     ($($arg:tt)+) => (
         $crate::logger::init();
-        log::warn!($($arg)+);
+        tracing::warn!($($arg)+);
     )
 }
 
@@ -67,11 +105,11 @@ macro_rules! warn {
 macro_rules! error {
     (target: $target:expr, $($arg:tt)+) => (
         $crate::logger::init();
-        log::error!(target: $target, $($arg)+);
+        tracing::error!(target: $target, $($arg)+);
     );
 
     ($($arg:tt)+) => (
         $crate::logger::init();
-        log::error!($($arg)+);
+        tracing::error!($($arg)+);
     )
 }
@@ -0,0 +1,134 @@
+//! A secondary index of every line pre-lowercased, so a case-insensitive search can
+//! be routed to it instead of re-lowercasing every index file on the fly at query
+//! time.
+//!
+
+use std::{io, path};
+
+use crate::config::DEFAULT_MAX_BUFFER;
+
+use super::{IndexCollection, IndexStats};
+
+/// The sub-directory a [`CaseFoldedIndex`] is nested under, relative to the primary
+/// collection's directory it accompanies - a multi-character name so it is never
+/// mistaken for one of [`super::IndexCollection`]'s own single-character shard
+/// sub-directories when that collection lists its own keys.
+const SUBDIRECTORY: &str = "case_folded";
+
+/// The byte separating a stored line's lowercased form from its original, so a match
+/// against the lowercased form can still be resolved back to the original casing; see
+/// [`crate::models::HashIndex`] for the same `key\tvalue` line format used for hash
+/// lookups.
+const SEPARATOR: u8 = b'\t';
+
+/// A secondary index storing every line pre-lowercased, built alongside a primary
+/// [`IndexCollection`] over the same lines.
+///
+/// A case-insensitive search normally re-lowercases every candidate index file on
+/// the fly through `ManipulatedReader`, on every query. Indexing every line
+/// pre-lowercased once, at build time, lets a case-insensitive search scan a strict,
+/// already-folded byte stream instead; see
+/// [`crate::search::CaseFoldedIndex::find_lines_containing_case_insensitively`] for
+/// the query side, behind the `search` feature.
+///
+/// Each stored line is `lowered\toriginal`, so a match against the lowered half can
+/// still be resolved back to the line's original casing.
+pub struct CaseFoldedIndex<
+    const LENGTH: usize,
+    const DEPTH: usize = 1,
+    const MAX_BUFFER: usize = DEFAULT_MAX_BUFFER,
+> {
+    pub(crate) collection: IndexCollection<LENGTH, DEPTH, MAX_BUFFER>,
+}
+
+impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
+    CaseFoldedIndex<LENGTH, DEPTH, MAX_BUFFER>
+{
+    /// Open (or create) the case-folded index nested under `dir`, alongside the
+    /// primary collection rooted there.
+    pub fn new(dir: impl Into<path::PathBuf>) -> Self {
+        Self {
+            collection: IndexCollection::new(dir.into().join(SUBDIRECTORY)),
+        }
+    }
+
+    /// Open the case-folded index nested under `dir` for reading only, without
+    /// buffering writes; see [`IndexCollection::open_read_only`].
+    pub fn open_read_only(dir: impl Into<path::PathBuf>) -> Self {
+        Self {
+            collection: IndexCollection::open_read_only(dir.into().join(SUBDIRECTORY)),
+        }
+    }
+
+    /// Whether a case-folded index has actually been built under `dir`. `false` for
+    /// a primary collection indexed before the `case_folded_index` feature (or
+    /// `--case-folded-index`) was used, so a caller can fall back to the on-the-fly
+    /// `ManipulatedReader` approach instead of a query silently coming back empty.
+    pub fn exists(dir: impl AsRef<path::Path>) -> bool {
+        dir.as_ref().join(SUBDIRECTORY).is_dir()
+    }
+
+    /// Add `item`, alongside its lowercased form, to the index.
+    pub fn add(&self, item: &[u8]) -> io::Result<()> {
+        let mut line = item.to_ascii_lowercase();
+        line.push(SEPARATOR);
+        line.extend_from_slice(item);
+
+        self.collection.add(&line)
+    }
+
+    /// Flush and post-process every index file; see [`IndexCollection::finalize`].
+    pub fn finalize(self) -> io::Result<IndexStats> {
+        self.collection.finalize()
+    }
+}
+
+/// Split a stored `lowered\toriginal` line back into its two halves, or `None` if it
+/// is missing the separator - which should not happen for a line this index wrote
+/// itself.
+pub(crate) fn split_folded_line(line: &str) -> Option<(&str, &str)> {
+    line.split_once(SEPARATOR as char)
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+    use std::{fs, path};
+
+    #[test]
+    fn add_stores_the_line_alongside_its_lowercased_form() {
+        let dir = path::PathBuf::from(TEST_DIR).join("case_folded_index_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let index = CaseFoldedIndex::<3, 1>::new(dir.clone());
+        index.add(b"Password").expect("Failed to add line.");
+        index.finalize().expect("Failed to finalize case-folded index.");
+
+        assert!(CaseFoldedIndex::<3, 1>::exists(&dir));
+
+        let key = super::super::indices_of::<3, 1>(b"password")
+            .next()
+            .expect("Expected at least one index key for the lowercased line.");
+
+        let collection = IndexCollection::<3, 1>::new(dir.join(SUBDIRECTORY));
+        let lines: Vec<Vec<u8>> = collection
+            .iter_lines_for_key(&key)
+            .expect("Failed to iterate key.")
+            .collect::<io::Result<_>>()
+            .expect("Failed to read a line.");
+        assert_eq!(lines, vec![b"password\tPassword".to_vec()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn exists_is_false_until_a_case_folded_index_has_been_built() {
+        let dir = path::PathBuf::from(TEST_DIR).join("case_folded_index_missing_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(!CaseFoldedIndex::<3, 1>::exists(&dir));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,73 @@
+//! On-disk record of an indexing run that was interrupted before completion.
+//!
+//! [`IndexCollection`]: super::IndexCollection
+
+use std::{fs, io, path};
+
+use serde::{Deserialize, Serialize};
+
+use super::IndexStats;
+
+/// The name of the checkpoint file within an index directory.
+pub const CHECKPOINT_FILE_NAME: &str = "checkpoint.json";
+
+/// Records that an indexing run was interrupted, along with the statistics for the
+/// index files flushed via [`IndexCollection::flush_all`] just before exiting.
+///
+/// Unlike [`IndexManifest`](super::IndexManifest), which is only written once an
+/// indexing run completes, a checkpoint marks a run that did not finish; its presence
+/// in an index directory means the index is incomplete.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Statistics for the index files flushed at the time of interruption.
+    pub stats: IndexStats,
+    /// Index, within the resolved list of `--input` files, of the file that was being
+    /// processed when indexing was interrupted. Every file before it in the list is
+    /// assumed fully processed.
+    pub file_index: usize,
+    /// The number of bytes of that file confirmed fully processed at the time of
+    /// interruption; `--resume` seeks that file to this offset before picking
+    /// indexing back up.
+    pub bytes_processed: usize,
+}
+
+impl Checkpoint {
+    /// Build a checkpoint from the given stats, input file index and source offset.
+    pub fn new(stats: IndexStats, file_index: usize, bytes_processed: usize) -> Self {
+        Self {
+            stats,
+            file_index,
+            bytes_processed,
+        }
+    }
+
+    /// Path to the checkpoint file within `dir`.
+    pub fn path(dir: impl AsRef<path::Path>) -> path::PathBuf {
+        dir.as_ref().join(CHECKPOINT_FILE_NAME)
+    }
+
+    /// Write this checkpoint into `dir`.
+    pub fn write(&self, dir: impl AsRef<path::Path>) -> io::Result<()> {
+        let contents = serde_json::to_vec_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        fs::write(Self::path(dir), contents)
+    }
+
+    /// Read the checkpoint from `dir`.
+    pub fn read(dir: impl AsRef<path::Path>) -> io::Result<Self> {
+        let contents = fs::read(Self::path(dir))?;
+
+        serde_json::from_slice(&contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Remove the checkpoint from `dir`, if it exists.
+    pub fn remove(dir: impl AsRef<path::Path>) -> io::Result<()> {
+        let path = Self::path(dir);
+        if path.is_file() {
+            fs::remove_file(path)
+        } else {
+            Ok(())
+        }
+    }
+}
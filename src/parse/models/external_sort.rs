@@ -0,0 +1,214 @@
+//! Bounded-memory external sort with deduplication, for index files built without
+//! the `deduplicate` feature (which dedupes at insert time, in memory, and so has no
+//! need of this).
+//!
+//! Lines are read in bounded-size runs, each sorted and deduplicated in memory and
+//! spilled to a temporary file, then merged back together with a k-way merge that
+//! only ever holds one line per run in memory at a time - bounding peak memory use
+//! to roughly `run_size_budget` regardless of the input file's total size.
+
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fs,
+    io::{self, BufRead, BufWriter, Read, Write},
+    path,
+};
+
+/// Sort and deduplicate the lines read from `reader`, writing the result to `writer`.
+///
+/// `tmp_dir` is used to hold the intermediate sorted runs; it is created if it does
+/// not exist, and removed once the merge is complete. `run_size_budget` bounds the
+/// number of bytes of lines buffered in memory per run before it is sorted and
+/// spilled to disk.
+pub fn sort_and_dedup_lines(
+    reader: impl Read,
+    writer: impl Write,
+    tmp_dir: impl AsRef<path::Path>,
+    run_size_budget: usize,
+) -> io::Result<()> {
+    let tmp_dir = tmp_dir.as_ref();
+    let runs = write_sorted_runs(reader, tmp_dir, run_size_budget)?;
+    let result = merge_runs(&runs, writer);
+
+    let _ = fs::remove_dir_all(tmp_dir);
+    result
+}
+
+/// Split `reader`'s lines into sorted, deduplicated runs of at most
+/// `run_size_budget` bytes each, and return the paths of the run files, in no
+/// particular order.
+fn write_sorted_runs(
+    reader: impl Read,
+    tmp_dir: &path::Path,
+    run_size_budget: usize,
+) -> io::Result<Vec<path::PathBuf>> {
+    fs::create_dir_all(tmp_dir)?;
+
+    let mut runs = Vec::new();
+    let mut buffer: Vec<Vec<u8>> = Vec::new();
+    let mut buffered_bytes = 0;
+
+    for line in io::BufReader::new(reader).split(b'\n') {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+
+        buffered_bytes += line.len();
+        buffer.push(line);
+
+        if buffered_bytes >= run_size_budget {
+            runs.push(flush_run(&mut buffer, tmp_dir, runs.len())?);
+            buffered_bytes = 0;
+        }
+    }
+
+    if !buffer.is_empty() {
+        runs.push(flush_run(&mut buffer, tmp_dir, runs.len())?);
+    }
+
+    Ok(runs)
+}
+
+/// Sort and deduplicate `buffer` in place, write it to a new run file under
+/// `tmp_dir`, and return that file's path.
+fn flush_run(buffer: &mut Vec<Vec<u8>>, tmp_dir: &path::Path, index: usize) -> io::Result<path::PathBuf> {
+    buffer.sort_unstable();
+    buffer.dedup();
+
+    let path = tmp_dir.join(format!("run_{index}.tmp"));
+    let mut writer = BufWriter::new(fs::File::create(&path)?);
+    for line in buffer.drain(..) {
+        writer.write_all(&line)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+
+    Ok(path)
+}
+
+/// One run's cursor into the k-way merge: the next unread line, if any, plus the
+/// reader to pull the line after it from.
+struct RunCursor {
+    lines: io::Split<io::BufReader<fs::File>>,
+    next: Vec<u8>,
+}
+
+impl RunCursor {
+    fn open(path: &path::Path) -> io::Result<Option<Self>> {
+        let mut lines = io::BufReader::new(fs::File::open(path)?).split(b'\n');
+        match lines.next().transpose()? {
+            Some(next) => Ok(Some(Self { lines, next })),
+            None => Ok(None),
+        }
+    }
+
+    /// Replace `self.next` with the run's following line, returning whether there
+    /// was one.
+    fn advance(&mut self) -> io::Result<bool> {
+        match self.lines.next().transpose()? {
+            Some(next) => {
+                self.next = next;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// A heap entry ordering [`RunCursor`]s by their next line, smallest first.
+struct HeapEntry {
+    run: usize,
+    line: Vec<u8>,
+}
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.line == other.line
+    }
+}
+impl Eq for HeapEntry {}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.line.cmp(&other.line)
+    }
+}
+
+/// Merge the already-sorted, already-internally-deduplicated `runs` into `writer`,
+/// deduplicating across run boundaries as lines are merged.
+fn merge_runs(runs: &[path::PathBuf], writer: impl Write) -> io::Result<()> {
+    let mut cursors: Vec<RunCursor> = runs
+        .iter()
+        .map(|path| RunCursor::open(path))
+        .collect::<io::Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let mut heap: BinaryHeap<Reverse<HeapEntry>> = cursors
+        .iter()
+        .enumerate()
+        .map(|(run, cursor)| Reverse(HeapEntry { run, line: cursor.next.clone() }))
+        .collect();
+
+    let mut writer = io::BufWriter::new(writer);
+    let mut last_written: Option<Vec<u8>> = None;
+
+    while let Some(Reverse(HeapEntry { run, line })) = heap.pop() {
+        if last_written.as_deref() != Some(line.as_slice()) {
+            writer.write_all(&line)?;
+            writer.write_all(b"\n")?;
+            last_written = Some(line);
+        }
+
+        if cursors[run].advance()? {
+            heap.push(Reverse(HeapEntry { run, line: cursors[run].next.clone() }));
+        }
+    }
+
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sort_bytes(input: &[u8], budget: usize) -> Vec<u8> {
+        let dir = std::env::temp_dir().join(format!(
+            "rockyou2024-external-sort-test-{pid}-{budget}",
+            pid = std::process::id()
+        ));
+
+        let mut output = Vec::new();
+        sort_and_dedup_lines(input, &mut output, &dir, budget).expect("Failed to sort and dedup lines.");
+        output
+    }
+
+    #[test]
+    fn dedupes_and_sorts_within_a_single_run() {
+        let output = sort_bytes(b"banana\napple\nbanana\ncherry\n", 4096);
+        assert_eq!(output, b"apple\nbanana\ncherry\n");
+    }
+
+    #[test]
+    fn dedupes_and_sorts_across_multiple_runs() {
+        // A tiny budget forces every line into its own run, exercising the k-way
+        // merge and its cross-run deduplication.
+        let output = sort_bytes(b"banana\napple\nbanana\ncherry\napple\n", 1);
+        assert_eq!(output, b"apple\nbanana\ncherry\n");
+    }
+
+    #[test]
+    fn handles_empty_input() {
+        let output = sort_bytes(b"", 4096);
+        assert!(output.is_empty());
+    }
+}
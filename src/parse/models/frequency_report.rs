@@ -0,0 +1,66 @@
+//! On-disk report of the most frequently occurring lines in an [`IndexCollection`],
+//! written by the `index` binary when `--top-frequencies` is passed.
+//!
+//! [`IndexCollection`]: super::IndexCollection
+
+use std::{fs, io, path};
+
+use serde::{Deserialize, Serialize};
+
+/// The name of the frequency report file within an index directory.
+pub const FREQUENCY_REPORT_FILE_NAME: &str = "frequency_report.json";
+
+/// One line and the number of times it was added to the collection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrequencyEntry {
+    /// The line, decoded as UTF-8 on a best-effort basis; invalid bytes are replaced
+    /// with the Unicode replacement character.
+    pub line: String,
+    /// The number of times this line was added to the collection.
+    pub count: usize,
+}
+
+/// A top-N report of the most frequently occurring lines in an [`IndexCollection`].
+///
+/// [`IndexCollection`]: super::IndexCollection
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrequencyReport {
+    pub entries: Vec<FrequencyEntry>,
+}
+
+impl FrequencyReport {
+    /// Build a report from `counts`, in the order given; callers such as
+    /// [`super::IndexCollection::top_frequencies`] are expected to have already
+    /// sorted by descending count.
+    pub fn new(counts: Vec<(Vec<u8>, usize)>) -> Self {
+        Self {
+            entries: counts
+                .into_iter()
+                .map(|(line, count)| FrequencyEntry {
+                    line: String::from_utf8_lossy(&line).into_owned(),
+                    count,
+                })
+                .collect(),
+        }
+    }
+
+    /// Path to the frequency report file within `dir`.
+    pub fn path(dir: impl AsRef<path::Path>) -> path::PathBuf {
+        dir.as_ref().join(FREQUENCY_REPORT_FILE_NAME)
+    }
+
+    /// Write this report into `dir`.
+    pub fn write(&self, dir: impl AsRef<path::Path>) -> io::Result<()> {
+        let contents = serde_json::to_vec_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        fs::write(Self::path(dir), contents)
+    }
+
+    /// Read the frequency report from `dir`.
+    pub fn read(dir: impl AsRef<path::Path>) -> io::Result<Self> {
+        let contents = fs::read(Self::path(dir))?;
+
+        serde_json::from_slice(&contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
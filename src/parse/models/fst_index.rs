@@ -0,0 +1,126 @@
+//! An alternative, compact index-file backend built on a finite-state transducer
+//! (fst), trading index build time for smaller on-disk size and faster prefix
+//! queries than the flat CSV index files [`super::IndexFile`] produces.
+//!
+//! An [`FstIndexSet`] is built once, from a complete, sorted, deduplicated set of
+//! lines, and is read-only from then on; see [`super::IndexFile::post_process`].
+
+use std::io;
+
+use fst::automaton::{Automaton, Str};
+use fst::{IntoStreamer, Set, SetBuilder, Streamer};
+
+/// A read-only set of byte-string lines backed by an [`fst::Set`].
+pub struct FstIndexSet {
+    set: Set<Vec<u8>>,
+}
+
+impl FstIndexSet {
+    /// Build a set from `lines`, writing its compact on-disk representation to
+    /// `writer`.
+    ///
+    /// `lines` must already be sorted in ascending order and free of duplicates;
+    /// this is the same precondition `IndexFile::post_process` already establishes
+    /// for its own dedup pass, so the sorted lines it produces can be fed straight
+    /// in.
+    pub fn build(
+        lines: impl IntoIterator<Item = impl AsRef<[u8]>>,
+        writer: impl io::Write,
+    ) -> io::Result<()> {
+        let mut builder = SetBuilder::new(writer).map_err(into_io_error)?;
+        for line in lines {
+            builder.insert(line).map_err(into_io_error)?;
+        }
+        builder.finish().map_err(into_io_error)
+    }
+
+    /// Load a set previously written by [`Self::build`].
+    pub fn open(mut reader: impl io::Read) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        Ok(Self {
+            set: Set::new(bytes).map_err(into_io_error)?,
+        })
+    }
+
+    /// Whether `line` is present in the set.
+    pub fn contains(&self, line: impl AsRef<[u8]>) -> bool {
+        self.set.contains(line)
+    }
+
+    /// All lines in the set starting with `prefix`, in ascending order.
+    pub fn starting_with(&self, prefix: &str) -> io::Result<Vec<Vec<u8>>> {
+        let matcher = Str::new(prefix).starts_with();
+        let mut stream = self.set.search(matcher).into_stream();
+
+        let mut matches = Vec::new();
+        while let Some(line) = stream.next() {
+            matches.push(line.to_vec());
+        }
+
+        Ok(matches)
+    }
+}
+
+/// Maps an [`fst::Error`] to an [`io::Error`], since the rest of the crate surfaces
+/// index errors as [`io::Result`].
+fn into_io_error(err: fst::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_build_and_open() {
+        let lines = ["apple", "banana", "cherry"];
+
+        let mut bytes = Vec::new();
+        FstIndexSet::build(lines, &mut bytes).expect("Failed to build the set.");
+
+        let set = FstIndexSet::open(io::Cursor::new(bytes)).expect("Failed to open the set.");
+
+        assert!(set.contains("apple"));
+        assert!(set.contains("banana"));
+        assert!(!set.contains("durian"));
+    }
+
+    #[test]
+    fn starting_with_returns_every_matching_line_in_order() {
+        let lines = ["letmein", "password", "password1", "password2"];
+
+        let mut bytes = Vec::new();
+        FstIndexSet::build(lines, &mut bytes).expect("Failed to build the set.");
+
+        let set = FstIndexSet::open(io::Cursor::new(bytes)).expect("Failed to open the set.");
+
+        assert_eq!(
+            set.starting_with("password").expect("Failed to search the set."),
+            vec![
+                b"password".to_vec(),
+                b"password1".to_vec(),
+                b"password2".to_vec(),
+            ]
+        );
+        assert_eq!(
+            set.starting_with("letme").expect("Failed to search the set."),
+            vec![b"letmein".to_vec()]
+        );
+        assert!(set
+            .starting_with("xyz")
+            .expect("Failed to search the set.")
+            .is_empty());
+    }
+
+    #[test]
+    fn build_rejects_unsorted_input() {
+        let lines = ["banana", "apple"];
+
+        let mut bytes = Vec::new();
+        let result = FstIndexSet::build(lines, &mut bytes);
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,140 @@
+//! A secondary index of every line pre-fuzzed (leet-speak folded), so a fuzzy search
+//! can be routed to it instead of re-transforming every byte of every index file on
+//! the fly at query time.
+//!
+
+use std::{io, path};
+
+use crate::config::DEFAULT_MAX_BUFFER;
+use crate::string;
+
+use super::{IndexCollection, IndexStats};
+
+/// The sub-directory a [`FuzzedIndex`] is nested under, relative to the primary
+/// collection's directory it accompanies - a multi-character name so it is never
+/// mistaken for one of [`super::IndexCollection`]'s own single-character shard
+/// sub-directories when that collection lists its own keys.
+const SUBDIRECTORY: &str = "fuzzed";
+
+/// The byte separating a stored line's fuzzed form from its original, so a match
+/// against the fuzzed form can still be resolved back to the original; see
+/// [`crate::models::CaseFoldedIndex`] for the same `key\tvalue` line format.
+const SEPARATOR: u8 = b'\t';
+
+/// A secondary index storing every line leet-speak-folded, built alongside a primary
+/// [`IndexCollection`] over the same lines.
+///
+/// [`SearchStyle::Fuzzy { keyboard_adjacent: false }`](crate::search::SearchStyle::Fuzzy)
+/// normally re-folds every candidate index file on the fly through
+/// `ManipulatedReader`, on every query. Indexing every line pre-folded once, at build
+/// time, lets a fuzzy search scan a strict, already-folded byte stream instead; see
+/// [`crate::search::FuzzedIndex::find_lines_containing_fuzzily`] for the query side,
+/// behind the `search` feature.
+///
+/// This only covers the plain leet-speak folding, not the keyboard-adjacent variant
+/// of [`crate::search::SearchStyle::Fuzzy`], which still falls back to
+/// `ManipulatedReader`.
+///
+/// Each stored line is `fuzzed\toriginal`, so a match against the fuzzed half can
+/// still be resolved back to the line's original text.
+pub struct FuzzedIndex<
+    const LENGTH: usize,
+    const DEPTH: usize = 1,
+    const MAX_BUFFER: usize = DEFAULT_MAX_BUFFER,
+> {
+    pub(crate) collection: IndexCollection<LENGTH, DEPTH, MAX_BUFFER>,
+}
+
+impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
+    FuzzedIndex<LENGTH, DEPTH, MAX_BUFFER>
+{
+    /// Open (or create) the fuzzed index nested under `dir`, alongside the primary
+    /// collection rooted there.
+    pub fn new(dir: impl Into<path::PathBuf>) -> Self {
+        Self {
+            collection: IndexCollection::new(dir.into().join(SUBDIRECTORY)),
+        }
+    }
+
+    /// Open the fuzzed index nested under `dir` for reading only, without buffering
+    /// writes; see [`IndexCollection::open_read_only`].
+    pub fn open_read_only(dir: impl Into<path::PathBuf>) -> Self {
+        Self {
+            collection: IndexCollection::open_read_only(dir.into().join(SUBDIRECTORY)),
+        }
+    }
+
+    /// Whether a fuzzed index has actually been built under `dir`. `false` for a
+    /// primary collection indexed before the `fuzzed_index` feature (or
+    /// `--fuzzed-index`) was used, so a caller can fall back to the on-the-fly
+    /// `ManipulatedReader` approach instead of a query silently coming back empty.
+    pub fn exists(dir: impl AsRef<path::Path>) -> bool {
+        dir.as_ref().join(SUBDIRECTORY).is_dir()
+    }
+
+    /// Add `item`, alongside its leet-speak-folded form, to the index.
+    pub fn add(&self, item: &[u8]) -> io::Result<()> {
+        let fuzzed: String = string::convert_to_fuzzy_string(&String::from_utf8_lossy(item)).collect();
+
+        let mut line = fuzzed.into_bytes();
+        line.push(SEPARATOR);
+        line.extend_from_slice(item);
+
+        self.collection.add(&line)
+    }
+
+    /// Flush and post-process every index file; see [`IndexCollection::finalize`].
+    pub fn finalize(self) -> io::Result<IndexStats> {
+        self.collection.finalize()
+    }
+}
+
+/// Split a stored `fuzzed\toriginal` line back into its two halves, or `None` if it
+/// is missing the separator - which should not happen for a line this index wrote
+/// itself.
+pub(crate) fn split_fuzzed_line(line: &str) -> Option<(&str, &str)> {
+    line.split_once(SEPARATOR as char)
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+    use std::{fs, path};
+
+    #[test]
+    fn add_stores_the_line_alongside_its_fuzzed_form() {
+        let dir = path::PathBuf::from(TEST_DIR).join("fuzzed_index_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let index = FuzzedIndex::<3, 1>::new(dir.clone());
+        index.add(b"P4ssw0rd").expect("Failed to add line.");
+        index.finalize().expect("Failed to finalize fuzzed index.");
+
+        assert!(FuzzedIndex::<3, 1>::exists(&dir));
+
+        let key = super::super::indices_of::<3, 1>(b"password")
+            .next()
+            .expect("Expected at least one index key for the fuzzed line.");
+
+        let collection = IndexCollection::<3, 1>::new(dir.join(SUBDIRECTORY));
+        let lines: Vec<Vec<u8>> = collection
+            .iter_lines_for_key(&key)
+            .expect("Failed to iterate key.")
+            .collect::<io::Result<_>>()
+            .expect("Failed to read a line.");
+        assert_eq!(lines, vec![b"password\tP4ssw0rd".to_vec()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn exists_is_false_until_a_fuzzed_index_has_been_built() {
+        let dir = path::PathBuf::from(TEST_DIR).join("fuzzed_index_missing_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(!FuzzedIndex::<3, 1>::exists(&dir));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,211 @@
+//! Remove empty, orphaned, and stale temporary files left behind in an index
+//! directory by an interrupted or otherwise unclean previous run.
+
+use std::{fs, io, path};
+
+use crate::index_key_path::key_for_file_name;
+
+use super::index_collection::LOCK_FILE_NAME;
+use super::{CHECKPOINT_FILE_NAME, MANIFEST_FILE_NAME};
+
+/// Name of the top-N frequency report written by [`super::FrequencyReport::write`]
+/// under the `frequency` feature; named directly rather than importing
+/// `FREQUENCY_REPORT_FILE_NAME`, since garbage collection has to recognise the file
+/// regardless of whether this binary was built with that feature enabled.
+const FREQUENCY_REPORT_FILE_NAME: &str = "frequency_report.json";
+
+/// Sidecar suffixes appended to an otherwise-valid index file name; see
+/// [`crate::offsets_path_for_key`], [`crate::fst_path_for_key`] and
+/// [`crate::wal_path_for_key`].
+pub(crate) const SIDECAR_SUFFIXES: &[&str] = &[".offsets", ".fst", ".wal"];
+
+/// Top-level file names an index directory (or one of its secondary-index
+/// sub-directories) may hold besides `subset_*.csv` index files and their sidecars,
+/// which garbage collection must never remove.
+const KNOWN_METADATA_FILES: &[&str] =
+    &[MANIFEST_FILE_NAME, CHECKPOINT_FILE_NAME, LOCK_FILE_NAME, FREQUENCY_REPORT_FILE_NAME];
+
+/// Directory names under which a `kv_storage`-backed hash-lookup index may keep its
+/// files however `sled` sees fit, rather than as `subset_*.csv`; orphan detection is
+/// skipped inside them, since a file that fails [`key_for_file_name`] there is not
+/// necessarily garbage. Zero-length index files and stale `*.tmp` files are still
+/// unambiguous regardless of backend, so those are still cleaned up.
+pub(crate) const OPAQUE_BACKEND_DIR_NAMES: &[&str] = &["md5", "sha1", "ntlm"];
+
+/// What [`garbage_collect`] removed from an index directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GcReport {
+    /// Zero-length `subset_*.csv` index files removed, alongside any sidecars found
+    /// next to them.
+    pub empty: Vec<path::PathBuf>,
+    /// Files whose names are neither a recognised index file, one of its sidecars,
+    /// nor known metadata, removed as orphaned.
+    pub orphaned: Vec<path::PathBuf>,
+    /// Stale `*.tmp` temp files removed.
+    pub temp: Vec<path::PathBuf>,
+}
+
+impl GcReport {
+    /// The total number of files removed.
+    pub fn total(&self) -> usize {
+        self.empty.len() + self.orphaned.len() + self.temp.len()
+    }
+}
+
+/// Whether `file_name` is recognised as belonging to an index directory: a
+/// `subset_*.csv` index file, one of its sidecars, or known metadata.
+fn is_recognised(file_name: &str) -> bool {
+    if KNOWN_METADATA_FILES.contains(&file_name) {
+        return true;
+    }
+
+    if key_for_file_name(file_name).is_some() {
+        return true;
+    }
+
+    SIDECAR_SUFFIXES.iter().any(|suffix| {
+        file_name
+            .strip_suffix(suffix)
+            .is_some_and(|stem| key_for_file_name(stem).is_some())
+    })
+}
+
+/// Recursively remove zero-length index files (and their sidecars), orphaned files,
+/// and stale `*.tmp` files under `dir`, descending into every sub-directory - the
+/// first-character shards `subset_*.csv` files are nested under, and the
+/// sub-directories secondary indices and hash-lookup indices keep their own copy of
+/// the same layout in.
+pub fn garbage_collect(dir: impl AsRef<path::Path>) -> io::Result<GcReport> {
+    let mut report = GcReport::default();
+    garbage_collect_into(dir.as_ref(), true, &mut report)?;
+    Ok(report)
+}
+
+fn garbage_collect_into(dir: &path::Path, scan_orphans: bool, report: &mut GcReport) -> io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)?.filter_map(Result::ok) {
+        let path = entry.path();
+
+        if path.is_dir() {
+            let scan_orphans = scan_orphans
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_none_or(|name| !OPAQUE_BACKEND_DIR_NAMES.contains(&name));
+
+            garbage_collect_into(&path, scan_orphans, report)?;
+            continue;
+        }
+
+        // May already have been removed as a sidecar of a zero-length index file
+        // visited earlier in this same directory.
+        if !path.is_file() {
+            continue;
+        }
+
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+
+        if file_name.ends_with(".tmp") {
+            fs::remove_file(&path)?;
+            report.temp.push(path);
+            continue;
+        }
+
+        if key_for_file_name(file_name).is_some() && path.metadata()?.len() == 0 {
+            remove_with_sidecars(&path)?;
+            report.empty.push(path);
+            continue;
+        }
+
+        if scan_orphans && !is_recognised(file_name) {
+            fs::remove_file(&path)?;
+            report.orphaned.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove `path` and any `.offsets`/`.fst`/`.wal` sidecars alongside it.
+fn remove_with_sidecars(path: &path::Path) -> io::Result<()> {
+    fs::remove_file(path)?;
+
+    for suffix in SIDECAR_SUFFIXES {
+        let mut sidecar = path.as_os_str().to_owned();
+        sidecar.push(suffix);
+        let sidecar = path::PathBuf::from(sidecar);
+        if sidecar.is_file() {
+            fs::remove_file(sidecar)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+
+    #[test]
+    fn removes_empty_index_files_orphans_and_temp_files_but_leaves_the_rest() {
+        let dir = path::PathBuf::from(TEST_DIR).join("gc_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("p")).unwrap();
+
+        // A healthy index file, which must survive.
+        fs::write(dir.join("p").join("subset_password.csv"), b"password\n").unwrap();
+        fs::write(dir.join("p").join("subset_password.csv.offsets"), b"stub").unwrap();
+
+        // An empty index file, whose sidecar must be removed alongside it.
+        fs::write(dir.join("p").join("subset_pancake.csv"), b"").unwrap();
+        fs::write(dir.join("p").join("subset_pancake.csv.offsets"), b"stub").unwrap();
+
+        // Metadata that must survive untouched.
+        fs::write(dir.join(MANIFEST_FILE_NAME), b"{}").unwrap();
+        fs::write(dir.join(LOCK_FILE_NAME), b"").unwrap();
+
+        // Cruft that must be removed.
+        fs::write(dir.join("p").join("not_an_index_file.txt"), b"junk").unwrap();
+        fs::write(dir.join("run_3.tmp"), b"stale").unwrap();
+
+        let report = garbage_collect(&dir).expect("garbage collection failed");
+
+        assert_eq!(report.empty, vec![dir.join("p").join("subset_pancake.csv")]);
+        assert_eq!(report.orphaned, vec![dir.join("p").join("not_an_index_file.txt")]);
+        assert_eq!(report.temp, vec![dir.join("run_3.tmp")]);
+        assert_eq!(report.total(), 3);
+
+        assert!(dir.join("p").join("subset_password.csv").is_file());
+        assert!(dir.join("p").join("subset_password.csv.offsets").is_file());
+        assert!(dir.join(MANIFEST_FILE_NAME).is_file());
+        assert!(dir.join(LOCK_FILE_NAME).is_file());
+        assert!(!dir.join("p").join("subset_pancake.csv.offsets").is_file());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn leaves_opaque_backend_directories_alone_beyond_the_unambiguous_cleanups() {
+        let dir = path::PathBuf::from(TEST_DIR).join("gc_opaque_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("md5")).unwrap();
+
+        // `sled` internals: not `subset_*.csv`-shaped, but must not be touched.
+        fs::write(dir.join("md5").join("db"), b"not actually empty or an index file").unwrap();
+        fs::write(dir.join("md5").join("conf"), b"sled config").unwrap();
+
+        let report = garbage_collect(&dir).expect("garbage collection failed");
+
+        assert!(report.orphaned.is_empty());
+        assert!(dir.join("md5").join("db").is_file());
+        assert!(dir.join("md5").join("conf").is_file());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,156 @@
+//! A secondary index mapping precomputed password hashes to their plaintext, keyed by
+//! hash prefix, so a client holding just a hash can look up whether its plaintext
+//! exists in the dump without hashing every candidate at query time.
+
+use std::{
+    io::{self, BufRead},
+    path, sync,
+};
+
+use crate::hash::HashAlgorithm;
+
+use super::{Storage, StorageBackend};
+
+/// The number of leading hex characters of a hash used as its bucket key.
+///
+/// Unlike [`super::IndexCollection`]'s `LENGTH` const generic, this is a plain
+/// constant rather than tunable per deployment: hash digests are already uniformly
+/// distributed, so there is no equivalent of a "common word" bucket to size around.
+pub const HASH_INDEX_KEY_LENGTH: usize = 4;
+
+/// A secondary index mapping hash prefixes to `hash_hex\tplaintext` lines, one
+/// instance per [`HashAlgorithm`].
+///
+/// [`Storage`] is reused as-is for the on-disk layout, so the same sharding and
+/// lookup-by-key machinery that backs the primary plaintext index also backs this
+/// one; only the key derivation (a hash prefix, rather than a position or common-word
+/// substring of the plaintext) and the line format differ.
+pub struct HashIndex {
+    algorithm: HashAlgorithm,
+    storage: sync::Arc<dyn Storage>,
+}
+
+impl HashIndex {
+    /// Open (or create) the hash index for `algorithm`, rooted at `dir`, backed by
+    /// [`super::FileStorage`].
+    pub fn new(algorithm: HashAlgorithm, dir: impl Into<path::PathBuf>) -> Self {
+        Self::with_backend(algorithm, dir, StorageBackend::File)
+    }
+
+    /// Open (or create) the hash index for `algorithm`, rooted at `dir`, backed by
+    /// `backend`.
+    ///
+    /// A large dump can produce a hash prefix bucket for nearly every possible
+    /// [`HASH_INDEX_KEY_LENGTH`]-character prefix, so [`StorageBackend::Kv`] is
+    /// worth choosing here to keep that many buckets out of the filesystem.
+    pub fn with_backend(algorithm: HashAlgorithm, dir: impl Into<path::PathBuf>, backend: StorageBackend) -> Self {
+        Self {
+            algorithm,
+            storage: backend.build(dir.into().join(algorithm.as_str())),
+        }
+    }
+
+    /// Add `plaintext` to the index, under this algorithm's digest of it.
+    pub fn add(&self, plaintext: &[u8]) -> io::Result<()> {
+        let hash_hex = self.algorithm.digest_hex(plaintext);
+
+        let mut line = hash_hex.clone().into_bytes();
+        line.push(b'\t');
+        line.extend_from_slice(plaintext);
+        line.push(b'\n');
+
+        self.storage.append(&bucket_key(&hash_hex), &line)
+    }
+
+    /// Look up `hash_hex` (case-insensitive), returning the plaintext that produced
+    /// it if it was added to this index, or `None` if it was not found.
+    pub fn lookup(&self, hash_hex: &str) -> io::Result<Option<Vec<u8>>> {
+        let hash_hex = hash_hex.to_ascii_lowercase();
+
+        let reader = match self.storage.open_for_read(&bucket_key(&hash_hex)) {
+            Ok(reader) => reader,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        for line in io::BufReader::new(reader).split(b'\n') {
+            let line = line?;
+            let Some(tab) = line.iter().position(|&byte| byte == b'\t') else {
+                continue;
+            };
+
+            if line[..tab] == *hash_hex.as_bytes() {
+                return Ok(Some(line[tab + 1..].to_vec()));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// The bucket key for a hash: its first [`HASH_INDEX_KEY_LENGTH`] hex characters.
+fn bucket_key(hash_hex: &str) -> String {
+    hash_hex.chars().take(HASH_INDEX_KEY_LENGTH).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_add_and_lookup() {
+        let dir = std::env::temp_dir().join(format!(
+            "rockyou2024-hash-index-test-{pid}",
+            pid = std::process::id()
+        ));
+        let index = HashIndex::new(HashAlgorithm::Md5, &dir);
+
+        index.add(b"password").expect("Failed to add plaintext.");
+        index.add(b"letmein").expect("Failed to add plaintext.");
+
+        let found = index
+            .lookup(&HashAlgorithm::Md5.digest_hex(b"password"))
+            .expect("Failed to look up hash.");
+        assert_eq!(found, Some(b"password".to_vec()));
+
+        let missing = index
+            .lookup(&HashAlgorithm::Md5.digest_hex(b"not-in-the-dump"))
+            .expect("Failed to look up hash.");
+        assert_eq!(missing, None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn lookup_is_case_insensitive() {
+        let dir = std::env::temp_dir().join(format!(
+            "rockyou2024-hash-index-test-case-{pid}",
+            pid = std::process::id()
+        ));
+        let index = HashIndex::new(HashAlgorithm::Md5, &dir);
+
+        index.add(b"password").expect("Failed to add plaintext.");
+
+        let hash_hex = HashAlgorithm::Md5.digest_hex(b"password").to_uppercase();
+        assert_eq!(
+            index.lookup(&hash_hex).expect("Failed to look up hash."),
+            Some(b"password".to_vec())
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn lookup_returns_none_for_an_unknown_bucket() {
+        let dir = std::env::temp_dir().join(format!(
+            "rockyou2024-hash-index-test-empty-{pid}",
+            pid = std::process::id()
+        ));
+        let index = HashIndex::new(HashAlgorithm::Sha1, &dir);
+
+        assert_eq!(
+            index.lookup("ffffffffffffffffffffffffffffffffffffffff").expect("Failed to look up hash."),
+            None
+        );
+    }
+}
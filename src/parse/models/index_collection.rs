@@ -2,10 +2,163 @@
 //!
 
 use hashbrown::HashMap;
-use std::{io, ops::DerefMut, path, sync::RwLock};
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::{self, Write},
+    ops::DerefMut,
+    path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
-use super::{indices_of, IndexFile};
-use crate::config::DEFAULT_MAX_BUFFER;
+#[cfg(feature = "search_lru")]
+use std::sync::atomic::AtomicUsize;
+
+use fs2::FileExt;
+
+use super::{indices_of, DurabilityPolicy, IndexFile, IndexManifest, MANIFEST_FILE_NAME};
+use crate::config::{self, DEFAULT_MAX_BUFFER};
+use crate::path_for_key;
+
+#[cfg(feature = "sqlite_export")]
+use super::{FileStorage, Storage};
+
+#[cfg(all(feature = "search", feature = "case_folded_index"))]
+use super::CaseFoldedIndex;
+#[cfg(all(feature = "search", feature = "fuzzed_index"))]
+use super::FuzzedIndex;
+#[cfg(all(feature = "search", feature = "phonetic_index"))]
+use super::PhoneticIndex;
+
+const LOG_TARGET: &str = "IndexCollection";
+
+/// Name of the advisory lockfile a write-capable [`IndexCollection`] holds an
+/// exclusive [`fs2`] lock on for as long as it is open; see
+/// [`IndexCollection::lock_file`].
+pub(crate) const LOCK_FILE_NAME: &str = ".lock";
+
+/// Block until an exclusive advisory lock on `dir`'s [`LOCK_FILE_NAME`] is acquired,
+/// creating both `dir` and the lockfile if they do not already exist.
+///
+/// Returns `None` instead of failing if `dir` or the lockfile could not be created,
+/// or the lock could not be taken (e.g. a read-only mount, or a filesystem that does
+/// not support `flock`) - the lock is advisory and best-effort, not a hard
+/// requirement to index.
+fn acquire_write_lock(dir: &path::Path) -> Option<fs::File> {
+    if let Err(err) = fs::create_dir_all(dir) {
+        crate::warn!(
+            target: LOG_TARGET,
+            "Failed to create index directory {dir:?} for locking: {err}",
+        );
+        return None;
+    }
+
+    let path = dir.join(LOCK_FILE_NAME);
+    let file = match fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+    {
+        Ok(file) => file,
+        Err(err) => {
+            crate::warn!(target: LOG_TARGET, "Failed to open lockfile {path:?}: {err}");
+            return None;
+        }
+    };
+
+    crate::debug!(target: LOG_TARGET, "Waiting for an exclusive lock on {path:?}...");
+
+    if let Err(err) = file.lock_exclusive() {
+        crate::warn!(
+            target: LOG_TARGET,
+            "Failed to acquire an exclusive lock on {path:?}: {err}",
+        );
+        return None;
+    }
+
+    Some(file)
+}
+
+/// Recursively visit every file under `src`, calling `link_file` with its path and
+/// the corresponding path under `dst` (creating directories as needed), skipping
+/// `LOCK_FILE_NAME` and `MANIFEST_FILE_NAME` (the lock is process-local and
+/// meaningless once copied, and the manifest is rewritten separately with checksums
+/// for the copy, not the original).
+///
+/// Returns the number of files visited.
+fn walk_dir_recursive(
+    src: &path::Path,
+    dst: &path::Path,
+    link_file: &mut impl FnMut(&path::Path, &path::Path) -> io::Result<()>,
+) -> io::Result<usize> {
+    let mut linked = 0;
+
+    for entry in fs::read_dir(src)?.filter_map(Result::ok) {
+        let path = entry.path();
+        let file_name = entry.file_name();
+        let dest_path = dst.join(&file_name);
+
+        if path.is_dir() {
+            fs::create_dir_all(&dest_path)?;
+            linked += walk_dir_recursive(&path, &dest_path, link_file)?;
+            continue;
+        }
+
+        if matches!(file_name.to_str(), Some(LOCK_FILE_NAME) | Some(MANIFEST_FILE_NAME)) {
+            continue;
+        }
+
+        link_file(&path, &dest_path)?;
+        linked += 1;
+    }
+
+    Ok(linked)
+}
+
+/// Recursively hardlink every file under `src` into the same relative path under
+/// `dst`; see [`walk_dir_recursive`].
+///
+/// Since a hardlinked file shares its inode (and therefore its content) with the
+/// original, this is only safe when nothing can write to `src` for the lifetime of
+/// `dst` - e.g. [`super::migrate::migrate`], a one-shot offline upgrade. A
+/// destination that must stay correct while `src` keeps being written to (e.g. a
+/// [`IndexCollection::snapshot`]) needs [`copy_dir_recursive`] instead.
+///
+/// Returns the number of files linked (or copied, see below).
+pub(crate) fn hardlink_dir_recursive(src: &path::Path, dst: &path::Path) -> io::Result<usize> {
+    walk_dir_recursive(src, dst, &mut |path, dest_path| {
+        match fs::hard_link(path, dest_path) {
+            Ok(()) => Ok(()),
+            // Hard links cannot cross filesystem boundaries; fall back to an actual
+            // copy so a migration to a different disk or mount still works.
+            Err(err) if err.kind() == io::ErrorKind::CrossesDevices => {
+                fs::copy(path, dest_path).map(|_| ())
+            }
+            Err(err) => Err(err),
+        }
+    })
+}
+
+/// Recursively copy every file under `src` into the same relative path under `dst`,
+/// byte-for-byte rather than hardlinking; see [`walk_dir_recursive`].
+///
+/// Every index file this crate writes to is opened in append mode and never
+/// truncated or replaced (see `FileStorage::append`), so a hardlinked "copy" shares
+/// its inode with the original and keeps changing underneath a reader as the
+/// original is written to. Copying the bytes instead gives [`IndexCollection::snapshot`]
+/// a destination that is a true point-in-time copy, immune to writes that land on
+/// `src` after this returns.
+///
+/// Returns the number of files copied.
+pub(crate) fn copy_dir_recursive(src: &path::Path, dst: &path::Path) -> io::Result<usize> {
+    walk_dir_recursive(src, dst, &mut |path, dest_path| fs::copy(path, dest_path).map(|_| ()))
+}
 
 #[cfg(feature = "search")]
 pub type IndexCollectionResult = hashbrown::HashSet<String>;
@@ -15,10 +168,61 @@ pub type IndexCollectionResult = hashbrown::HashSet<String>;
 pub type IndexCollectionCache = lru::LruCache<String, Arc<IndexCollectionResult>>;
 
 #[cfg(feature = "search_lru")]
-pub use std::sync::Arc;
+pub use crate::config::CACHE_SIZE;
 
+/// Snapshot of the search-result cache's hit/miss/eviction counters, for tuning
+/// [`IndexCollection::set_cache_capacity`] in a long-running process such as a web
+/// service.
 #[cfg(feature = "search_lru")]
-pub use crate::config::CACHE_SIZE;
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CacheStats {
+    /// The number of searches served from the cache.
+    pub hits: usize,
+    /// The number of searches that had to scan the index files because the query
+    /// was not (yet, or no longer) cached.
+    pub misses: usize,
+    /// The number of entries evicted from the cache to make room for a new one.
+    pub evictions: usize,
+}
+
+/// Statistics about an [`IndexCollection`] returned by [`IndexCollection::finalize`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct IndexStats {
+    /// The number of index files that were post-processed.
+    pub files: usize,
+    /// The total number of bytes flushed to disk across all index files.
+    pub bytes_flushed: usize,
+}
+
+/// Handle to a background auto-flush thread spawned by
+/// [`IndexCollection::spawn_auto_flush`].
+///
+/// Dropping this handle stops the thread and waits for it to exit, just like calling
+/// [`Self::stop`] explicitly.
+pub struct AutoFlushHandle {
+    stop: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl AutoFlushHandle {
+    /// Signal the background thread to stop, and wait for it to exit.
+    pub fn stop(mut self) {
+        self.stop_and_join();
+    }
+
+    fn stop_and_join(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for AutoFlushHandle {
+    fn drop(&mut self) {
+        self.stop_and_join();
+    }
+}
 
 /// A collection of indices.
 pub struct IndexCollection<
@@ -29,33 +233,291 @@ pub struct IndexCollection<
     pub(crate) dir: path::PathBuf,
     pub(crate) indices: RwLock<HashMap<String, IndexFile<MAX_BUFFER>>>,
 
+    /// Whether this collection was opened via [`Self::open_read_only`], in which
+    /// case it never writes to `dir` and [`Drop`] skips post-processing.
+    pub(crate) read_only: bool,
+
+    /// An exclusive advisory lock on [`LOCK_FILE_NAME`] under `dir`, held for as long
+    /// as this collection is open, so that no other write-capable `IndexCollection`
+    /// for the same directory can run at the same time. `None` for a collection
+    /// opened via [`Self::open_read_only`], which never writes and so never
+    /// contends with a writer; also `None` if the lock could not be acquired, since
+    /// it is advisory (e.g. cooperating processes only) rather than a hard
+    /// requirement to operate. Released automatically when this field is dropped.
+    #[allow(dead_code)]
+    pub(crate) lock_file: Option<fs::File>,
+
+    /// How aggressively each [`IndexFile`] created by this collection forces its
+    /// writes to durable storage; see [`DurabilityPolicy`]. Applies only to
+    /// [`IndexFile`]s created from this point on - see [`Self::with_durability`].
+    pub(crate) durability: DurabilityPolicy,
+
     #[cfg(feature = "search_lru")]
     /// A cache of the previous searches.
     pub(crate) cache: RwLock<IndexCollectionCache>,
+
+    #[cfg(feature = "search_lru")]
+    /// Whether the cache is consulted and populated by searches; can be toggled at
+    /// runtime without losing the entries already cached.
+    pub(crate) cache_enabled: AtomicBool,
+
+    #[cfg(feature = "search_lru")]
+    pub(crate) cache_hits: AtomicUsize,
+    #[cfg(feature = "search_lru")]
+    pub(crate) cache_misses: AtomicUsize,
+    #[cfg(feature = "search_lru")]
+    pub(crate) cache_evictions: AtomicUsize,
+
+    #[cfg(feature = "frequency")]
+    /// Occurrence counts per line added via [`Self::add`], since deduplication
+    /// elsewhere in the collection would otherwise erase this information; see
+    /// [`Self::top_frequencies`].
+    pub(crate) counts: RwLock<HashMap<Vec<u8>, usize>>,
+
+    #[cfg(feature = "search")]
+    /// A dedicated rayon thread pool sized via [`Self::set_search_threads`], used to
+    /// run a search's parallel scan across index files instead of rayon's default
+    /// global pool. `None` until configured, in which case the global pool is used.
+    pub(crate) search_pool: RwLock<Option<Arc<rayon::ThreadPool>>>,
+
+    #[cfg(feature = "search")]
+    /// A semaphore sized via [`Self::set_search_concurrency`], bounding how many
+    /// calls to [`Self::find_lines_containing`] run at once; excess callers queue on
+    /// [`crate::search::SearchSemaphore::acquire`]. `None` until configured, in which
+    /// case searches are not limited beyond whatever the thread pool itself bounds.
+    pub(crate) search_semaphore: RwLock<Option<Arc<crate::search::SearchSemaphore>>>,
+
+    #[cfg(feature = "search")]
+    /// Coalesces concurrent, unbounded (no deadline or cancellation) searches for the
+    /// same `(query, search_style)` into a single scan, so that a burst of identical
+    /// queries arriving before the first one has populated the cache only pays for
+    /// one scan between them.
+    pub(crate) search_singleflight:
+        crate::search::Singleflight<(String, crate::search::SearchStyle), crate::search::TimedSearchResult>,
+
+    /// A pre-lowercased [`CaseFoldedIndex`] built alongside this collection, detected
+    /// once at construction via [`CaseFoldedIndex::exists`]; when present,
+    /// [`Self::find_lines_containing`] routes `CaseInsensitive` queries to it instead
+    /// of re-lowercasing every candidate index file at query time.
+    #[cfg(all(feature = "search", feature = "case_folded_index"))]
+    pub(crate) case_folded_index: Option<Arc<CaseFoldedIndex<LENGTH, DEPTH, MAX_BUFFER>>>,
+
+    /// A pre-fuzzed [`FuzzedIndex`] built alongside this collection, detected once at
+    /// construction via [`FuzzedIndex::exists`]; when present,
+    /// [`Self::find_lines_containing`] routes `Fuzzy { keyboard_adjacent: false }`
+    /// queries to it instead of re-folding every candidate index file at query time.
+    #[cfg(all(feature = "search", feature = "fuzzed_index"))]
+    pub(crate) fuzzed_index: Option<Arc<FuzzedIndex<LENGTH, DEPTH, MAX_BUFFER>>>,
+
+    /// A pre-encoded [`PhoneticIndex`] built alongside this collection, detected once
+    /// at construction via [`PhoneticIndex::exists`]; when present,
+    /// [`Self::find_lines_containing`] routes `Phonetic` queries to it instead of
+    /// re-encoding every line in a full collection scan at query time.
+    #[cfg(all(feature = "search", feature = "phonetic_index"))]
+    pub(crate) phonetic_index: Option<Arc<PhoneticIndex<LENGTH, DEPTH, MAX_BUFFER>>>,
 }
 
 impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
     IndexCollection<LENGTH, DEPTH, MAX_BUFFER>
 {
-    /// Create a new index collection.
+    /// Create a new index collection, with [`DurabilityPolicy::None`].
     pub fn new(dir: path::PathBuf) -> Self {
+        Self::new_with_read_only(dir, false, DurabilityPolicy::default())
+    }
+
+    /// Create a new index collection, forcing every [`IndexFile`] it creates to
+    /// durable storage according to `durability` instead of relying on the OS's own
+    /// write-back caching.
+    pub fn with_durability(dir: path::PathBuf, durability: DurabilityPolicy) -> Self {
+        Self::new_with_read_only(dir, false, durability)
+    }
+
+    /// Open an existing index collection for search only.
+    ///
+    /// Unlike [`Self::new`], the returned collection never creates directories or
+    /// writes index files - [`Self::add`] returns an error instead - and its
+    /// [`Drop`] implementation skips post-processing, so it is safe to open an index
+    /// that lives on a read-only filesystem.
+    pub fn open_read_only(dir: path::PathBuf) -> Self {
+        Self::new_with_read_only(dir, true, DurabilityPolicy::default())
+    }
+
+    /// Open an existing index collection for search only, like [`Self::open_read_only`],
+    /// but eagerly check its manifest and return an error immediately if it was built
+    /// with different `LENGTH`/`DEPTH` parameters than `Self`, instead of only
+    /// discovering the mismatch - as a logged warning against otherwise silently
+    /// empty results - the first time a search runs (see [`Self::validate_manifest`]).
+    ///
+    /// Prefer this over [`Self::open_read_only`] for any caller that can act on the
+    /// error before issuing a query, such as a long-lived handle opened once and
+    /// reused across many searches.
+    pub fn open_validated(dir: path::PathBuf) -> io::Result<Self> {
+        let collection = Self::open_read_only(dir);
+        collection.validate_manifest()?;
+        Ok(collection)
+    }
+
+    fn new_with_read_only(dir: path::PathBuf, read_only: bool, durability: DurabilityPolicy) -> Self {
+        let lock_file = (!read_only).then(|| acquire_write_lock(&dir)).flatten();
+
+        #[cfg(all(feature = "search", feature = "case_folded_index"))]
+        let case_folded_index = CaseFoldedIndex::<LENGTH, DEPTH, MAX_BUFFER>::exists(&dir).then(|| {
+            Arc::new(if read_only {
+                CaseFoldedIndex::open_read_only(dir.clone())
+            } else {
+                CaseFoldedIndex::new(dir.clone())
+            })
+        });
+
+        #[cfg(all(feature = "search", feature = "fuzzed_index"))]
+        let fuzzed_index = FuzzedIndex::<LENGTH, DEPTH, MAX_BUFFER>::exists(&dir).then(|| {
+            Arc::new(if read_only {
+                FuzzedIndex::open_read_only(dir.clone())
+            } else {
+                FuzzedIndex::new(dir.clone())
+            })
+        });
+
+        #[cfg(all(feature = "search", feature = "phonetic_index"))]
+        let phonetic_index = PhoneticIndex::<LENGTH, DEPTH, MAX_BUFFER>::exists(&dir).then(|| {
+            Arc::new(if read_only {
+                PhoneticIndex::open_read_only(dir.clone())
+            } else {
+                PhoneticIndex::new(dir.clone())
+            })
+        });
+
         Self {
             dir,
             indices: HashMap::default().into(),
+            read_only,
+            lock_file,
+            durability,
 
             #[cfg(feature = "search_lru")]
             cache: RwLock::new(lru::LruCache::new(std::num::NonZeroUsize::new(CACHE_SIZE).expect(
                 "Failed to create a non-zero usize from the cache size; this should be unreachable."
             ))),
+            #[cfg(feature = "search_lru")]
+            cache_enabled: AtomicBool::new(true),
+            #[cfg(feature = "search_lru")]
+            cache_hits: AtomicUsize::new(0),
+            #[cfg(feature = "search_lru")]
+            cache_misses: AtomicUsize::new(0),
+            #[cfg(feature = "search_lru")]
+            cache_evictions: AtomicUsize::new(0),
+
+            #[cfg(feature = "frequency")]
+            counts: HashMap::default().into(),
+
+            #[cfg(feature = "search")]
+            search_pool: RwLock::new(None),
+
+            #[cfg(feature = "search")]
+            search_semaphore: RwLock::new(None),
+
+            #[cfg(feature = "search")]
+            search_singleflight: crate::search::Singleflight::new(),
+
+            #[cfg(all(feature = "search", feature = "case_folded_index"))]
+            case_folded_index,
+
+            #[cfg(all(feature = "search", feature = "fuzzed_index"))]
+            fuzzed_index,
+
+            #[cfg(all(feature = "search", feature = "phonetic_index"))]
+            phonetic_index,
+        }
+    }
+
+    /// Whether the search-result cache is currently consulted and populated.
+    #[cfg(feature = "search_lru")]
+    pub fn cache_enabled(&self) -> bool {
+        self.cache_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Enable or disable the search-result cache at runtime.
+    ///
+    /// Disabling does not clear any entries already cached; re-enabling picks up
+    /// where it left off.
+    #[cfg(feature = "search_lru")]
+    pub fn set_cache_enabled(&self, enabled: bool) {
+        self.cache_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// The maximum number of entries the search-result cache will currently hold.
+    #[cfg(feature = "search_lru")]
+    pub fn cache_capacity(&self) -> usize {
+        self.cache
+            .read()
+            .expect("Failed to acquire read lock on cache; cache might be poisoned.")
+            .cap()
+            .get()
+    }
+
+    /// Resize the search-result cache, evicting the least-recently-used entries if
+    /// the new capacity is smaller than the number of entries currently cached.
+    #[cfg(feature = "search_lru")]
+    pub fn set_cache_capacity(&self, capacity: std::num::NonZeroUsize) {
+        self.cache
+            .write()
+            .expect("Failed to acquire write lock on cache; cache might be poisoned.")
+            .resize(capacity);
+    }
+
+    /// A snapshot of the cache's hit/miss/eviction counters since the collection was
+    /// created.
+    #[cfg(feature = "search_lru")]
+    pub fn cache_stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.cache_hits.load(Ordering::Relaxed),
+            misses: self.cache_misses.load(Ordering::Relaxed),
+            evictions: self.cache_evictions.load(Ordering::Relaxed),
         }
     }
 
     /// Add an item to the collection.
-    pub fn add(&self, item: Vec<u8>) -> io::Result<()> {
-        let mut indices = indices_of::<LENGTH, DEPTH>(&item);
+    ///
+    /// Returns an error if this collection was opened via [`Self::open_read_only`].
+    pub fn add(&self, item: &[u8]) -> io::Result<()> {
+        #[cfg(feature = "frequency")]
+        self.record_frequency(item);
 
-        indices
-        .try_for_each(
+        self.add_under_keys(item, indices_of::<LENGTH, DEPTH>(item))
+    }
+
+    /// Add `item` to the collection, computing its bucket keys from `key_source`
+    /// instead of from `item` itself.
+    ///
+    /// Used by field-aware ingestion (e.g. the index binary's `--format combo`) where
+    /// the line stored for retrieval differs from the field that should drive
+    /// indexing - for example indexing on the password field of an
+    /// `email:password` combo line while storing the full line so it can still be
+    /// filtered by email later.
+    ///
+    /// Returns an error if this collection was opened via [`Self::open_read_only`].
+    pub fn add_under_field(&self, item: &[u8], key_source: &[u8]) -> io::Result<()> {
+        #[cfg(feature = "frequency")]
+        self.record_frequency(item);
+
+        self.add_under_keys(item, indices_of::<LENGTH, DEPTH>(key_source))
+    }
+
+    /// Add `item` to the collection under each of `keys`, bypassing this
+    /// collection's own [`indices_of`] bucketing of `item` - used by secondary
+    /// indices such as [`super::NgramIndex`] that bucket a line under a different
+    /// scheme than [`Self::add`]'s default one.
+    ///
+    /// Returns an error if this collection was opened via [`Self::open_read_only`].
+    pub(crate) fn add_under_keys(&self, item: &[u8], mut keys: impl Iterator<Item = String>) -> io::Result<()> {
+        if self.read_only {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "Cannot add to an index collection opened with `open_read_only`.",
+            ));
+        }
+
+        keys.try_for_each(
             |index| {
                 self.assert_index_exists(&index)?;
 
@@ -63,11 +525,41 @@ impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
                     "Failed to acquire read lock on indices; indices might be poisoned."
                 ).get(&index).expect(
                     "Index does not exist in the collection after assertion, this should be unreachable."
-                ).add(item.to_owned()).map(|_| ())
+                ).add(item).map(|_| ())
             }
         )
     }
 
+    /// Record one more occurrence of `item`, for [`Self::top_frequencies`].
+    #[cfg(feature = "frequency")]
+    fn record_frequency(&self, item: &[u8]) {
+        *self
+            .counts
+            .write()
+            .expect("Failed to acquire write lock on counts; counts might be poisoned.")
+            .entry(item.to_owned())
+            .or_insert(0) += 1;
+    }
+
+    /// The `limit` lines added most often, sorted by descending count (ties broken by
+    /// the line itself, for stable output).
+    #[cfg(feature = "frequency")]
+    pub fn top_frequencies(&self, limit: usize) -> Vec<(Vec<u8>, usize)> {
+        let counts = self
+            .counts
+            .read()
+            .expect("Failed to acquire read lock on counts; counts might be poisoned.");
+
+        let mut counted: Vec<(Vec<u8>, usize)> = counts
+            .iter()
+            .map(|(line, &count)| (line.clone(), count))
+            .collect();
+
+        counted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counted.truncate(limit);
+        counted
+    }
+
     /// Add an index to the collection.
     fn assert_index_exists(&self, key: &str) -> io::Result<bool> {
         let mut indices = self
@@ -75,15 +567,174 @@ impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
             .write()
             .expect("Failed to acquire write lock on indices; indices might be poisoned.");
         if !indices.contains_key(key) {
-            let index = IndexFile::new(key.to_owned(), &self.dir)?;
+            let index = IndexFile::with_durability(key.to_owned(), &self.dir, self.durability)?;
             Ok(indices.insert(key.to_owned(), index).is_none())
         } else {
             Ok(false)
         }
     }
 
-    /// Post-process the collection.
-    fn post_process(&mut self) -> io::Result<()> {
+    /// Flush every index file's in-memory buffer to disk, without finalizing the
+    /// collection.
+    ///
+    /// Unlike [`Self::finalize`], this takes `&self` and leaves the index files and
+    /// their buffers in place, so indexing can carry on afterwards. This makes it safe
+    /// to call periodically from a long-running indexer - whether from a signal handler
+    /// to checkpoint progress before exiting, or on a timer - since it never blocks
+    /// `add` for longer than a single index file's flush, and can be called from any
+    /// thread while others are still adding to the collection.
+    ///
+    /// Returns the total number of bytes written across all index files, alongside how
+    /// many files were flushed.
+    pub fn flush_all(&self) -> io::Result<IndexStats> {
+        self.indices
+            .read()
+            .expect("Failed to acquire read lock on indices; indices might be poisoned.")
+            .values()
+            .try_fold(IndexStats::default(), |mut stats, index| {
+                stats.bytes_flushed += index.flush()?;
+                stats.files += 1;
+                Ok(stats)
+            })
+    }
+
+    /// Produce a consistent, checksummed backup of this collection's directory at
+    /// `destination`, flushing every index file's in-memory buffer first via
+    /// [`Self::flush_all`] so the copy reflects everything added so far, even
+    /// mid-run.
+    ///
+    /// Every file already on disk is copied byte-for-byte (see
+    /// [`copy_dir_recursive`]) rather than hardlinked, so `destination` stays a true
+    /// point-in-time copy even if this collection keeps being written to
+    /// afterwards; `destination` is created if it does not already exist. A
+    /// manifest recording each file's checksum at snapshot time is written into
+    /// `destination`, so [`IndexManifest::verify`] can later confirm the snapshot
+    /// has not bit-rotted independently of the live collection it was taken from.
+    pub fn snapshot(&self, destination: impl AsRef<path::Path>) -> io::Result<IndexManifest> {
+        let destination = destination.as_ref();
+        let stats = self.flush_all()?;
+
+        fs::create_dir_all(destination)?;
+        let linked = copy_dir_recursive(&self.dir, destination)?;
+
+        crate::debug!(
+            target: LOG_TARGET,
+            "Snapshotted {linked} file(s) from {dir:?} into {destination:?}.",
+            dir = self.dir,
+        );
+
+        let manifest = IndexManifest::new::<LENGTH, DEPTH>(&[] as &[path::PathBuf], stats.files, stats.bytes_flushed)?
+            .with_index_file_hashes(destination)?;
+        manifest.write(destination)?;
+
+        Ok(manifest)
+    }
+
+    /// The total number of bytes currently buffered in memory across every index file
+    /// in this collection, not yet flushed to disk.
+    pub fn buffered_bytes(&self) -> usize {
+        self.indices
+            .read()
+            .expect("Failed to acquire read lock on indices; indices might be poisoned.")
+            .values()
+            .map(|index| index.buffered_len())
+            .sum()
+    }
+
+    /// The total number of times any index file in this collection has been flushed
+    /// to disk so far, across all of [`IndexFile::add`]'s automatic overflow
+    /// flushes, [`Self::flush_all`], and [`Self::spawn_auto_flush`]'s background
+    /// flushes; useful for a progress reporter to show how often the indexer is
+    /// hitting disk.
+    pub fn total_flush_count(&self) -> usize {
+        self.indices
+            .read()
+            .expect("Failed to acquire read lock on indices; indices might be poisoned.")
+            .values()
+            .map(|index| index.flush_count())
+            .sum()
+    }
+
+    /// Flush the `max_files` index files with the largest in-memory buffers.
+    ///
+    /// Used by the background thread spawned by [`Self::spawn_auto_flush`] to smooth
+    /// out write bursts by draining the hottest buffers first, rather than flushing
+    /// every index file at once.
+    fn flush_hottest(&self, max_files: usize) -> io::Result<IndexStats> {
+        let indices = self
+            .indices
+            .read()
+            .expect("Failed to acquire read lock on indices; indices might be poisoned.");
+
+        let mut hottest: Vec<_> = indices.values().collect();
+        hottest.sort_by_key(|index| std::cmp::Reverse(index.buffered_len()));
+
+        hottest
+            .into_iter()
+            .take(max_files)
+            .filter(|index| index.buffered_len() > 0)
+            .try_fold(IndexStats::default(), |mut stats, index| {
+                stats.bytes_flushed += index.flush()?;
+                stats.files += 1;
+                Ok(stats)
+            })
+    }
+
+    /// Spawn a background thread that periodically flushes this collection's hottest
+    /// index file buffers to disk, smoothing out write bursts during indexing.
+    ///
+    /// The thread wakes at least every `interval`, and flushes the
+    /// [`config::AUTO_FLUSH_BATCH_SIZE`] largest index file buffers whenever either
+    /// `interval` has elapsed since the last flush, or [`Self::buffered_bytes`]
+    /// exceeds `byte_budget` - whichever happens first. Dropping the returned
+    /// [`AutoFlushHandle`], or calling [`AutoFlushHandle::stop`] explicitly, stops the
+    /// thread.
+    ///
+    /// The collection must be shared via [`Arc`], since the background thread
+    /// outlives this call.
+    pub fn spawn_auto_flush(self: &Arc<Self>, interval: Duration, byte_budget: usize) -> AutoFlushHandle
+    where
+        Self: Send + Sync + 'static,
+    {
+        let collection = Arc::clone(self);
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+
+        let handle = thread::spawn(move || {
+            let mut last_flush = Instant::now();
+
+            while !thread_stop.load(Ordering::Relaxed) {
+                thread::sleep(config::AUTO_FLUSH_POLL_INTERVAL.min(interval));
+
+                if thread_stop.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                let due =
+                    last_flush.elapsed() >= interval || collection.buffered_bytes() >= byte_budget;
+
+                if due {
+                    if let Err(err) = collection.flush_hottest(config::AUTO_FLUSH_BATCH_SIZE) {
+                        crate::error!(
+                            target: LOG_TARGET,
+                            "Background auto-flush failed: {err}.",
+                            err = err,
+                        );
+                    }
+                    last_flush = Instant::now();
+                }
+            }
+        });
+
+        AutoFlushHandle {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Post-process the collection, returning statistics about the files that were
+    /// flushed.
+    fn post_process(&mut self) -> io::Result<IndexStats> {
         let mut new_map = HashMap::default();
 
         // Swap the indices with a new map, so that we can consume the old map as we
@@ -98,15 +749,658 @@ impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
 
         new_map
             .into_iter()
-            .try_for_each(|(_, mut index)| index.post_process())
+            .try_fold(IndexStats::default(), |mut stats, (_, mut index)| {
+                stats.bytes_flushed += index.post_process()?;
+                stats.files += 1;
+                Ok(stats)
+            })
+    }
+
+    /// Post-process and consume the collection, returning statistics about the run.
+    ///
+    /// Unlike the [`Drop`] implementation, which is best-effort and only logs failures,
+    /// this surfaces post-processing errors to the caller. Prefer calling this
+    /// explicitly at the end of an indexing run over relying on `Drop`.
+    pub fn finalize(mut self) -> io::Result<IndexStats> {
+        self.post_process()
+    }
+
+    /// Merge every index file in this collection into a single deduplicated, sorted
+    /// wordlist at `output`, suitable for hashcat/john.
+    ///
+    /// The same line is written into more than one index file by [`indices_of`]'s
+    /// position/common-word bucketing, so merging without deduplicating would
+    /// multiply most lines several times over; a [`BTreeSet`] both deduplicates and
+    /// sorts the lines as they are collected, before they are streamed out to
+    /// `output` one at a time.
+    ///
+    /// Returns the number of unique lines written.
+    pub fn export(&self, output: impl AsRef<path::Path>) -> io::Result<usize> {
+        let mut lines = BTreeSet::new();
+        for line in self.iter_lines()? {
+            lines.insert(line?);
+        }
+
+        let mut writer = io::BufWriter::new(fs::File::create(output)?);
+        for line in &lines {
+            writer.write_all(line)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+
+        Ok(lines.len())
+    }
+
+    /// Export every index file in this collection into a single SQLite database at
+    /// `output`, with one `(key, line, count)` row per unique line per bucket and
+    /// indexes on `key` and `line`, so analysts can query the dump with ordinary SQL
+    /// tools instead of the index files' own bucket layout.
+    ///
+    /// Unlike [`Self::export`], which discards which bucket(s) a line came from to
+    /// produce one global deduplicated wordlist, this keeps each key's lines
+    /// separate, alongside how many times each line occurs within that key's index
+    /// file - which, once the file has gone through [`IndexFile::post_process`],
+    /// is always `1`, since duplicates within a single bucket are removed there.
+    ///
+    /// Returns the number of rows written.
+    #[cfg(feature = "sqlite_export")]
+    pub fn export_sqlite(&self, output: impl AsRef<path::Path>) -> io::Result<usize> {
+        let storage = FileStorage::new(&self.dir);
+
+        // Start from a clean database each time, rather than accumulating rows on
+        // top of a stale export left over from a previous run.
+        if output.as_ref().is_file() {
+            fs::remove_file(&output)?;
+        }
+
+        let mut connection = rusqlite::Connection::open(&output).map_err(sqlite_error_to_io)?;
+        connection
+            .execute_batch(
+                "CREATE TABLE lines (key TEXT NOT NULL, line TEXT NOT NULL, count INTEGER NOT NULL);
+                 CREATE INDEX idx_lines_key ON lines (key);
+                 CREATE INDEX idx_lines_line ON lines (line);",
+            )
+            .map_err(sqlite_error_to_io)?;
+
+        let transaction = connection.transaction().map_err(sqlite_error_to_io)?;
+        let mut rows = 0usize;
+        {
+            let mut statement = transaction
+                .prepare("INSERT INTO lines (key, line, count) VALUES (?1, ?2, ?3)")
+                .map_err(sqlite_error_to_io)?;
+
+            for key in storage.list_keys()? {
+                let mut counts: HashMap<Vec<u8>, usize> = HashMap::default();
+                for line in self.iter_lines_for_key(&key)? {
+                    *counts.entry(line?).or_insert(0) += 1;
+                }
+
+                for (line, count) in counts {
+                    statement
+                        .execute(rusqlite::params![
+                            key,
+                            String::from_utf8_lossy(&line).into_owned(),
+                            count as i64,
+                        ])
+                        .map_err(sqlite_error_to_io)?;
+                    rows += 1;
+                }
+            }
+        }
+        transaction.commit().map_err(sqlite_error_to_io)?;
+
+        Ok(rows)
+    }
+
+    /// Draw a uniform random sample of up to `n` lines from across every index file
+    /// in this collection, without loading the whole collection into memory.
+    ///
+    /// Uses reservoir sampling (Algorithm R): each line is read once, in whatever
+    /// order [`Self::iter_lines`] yields it, and has
+    /// an equal chance of ending up in the returned sample regardless of how many
+    /// lines come before or after it - so no index file needs to be sized up front,
+    /// unlike [`Self::export`], which has to hold every unique line in memory to
+    /// deduplicate and sort them. Handy for generating a representative test corpus
+    /// or a quick statistical sample of a large collection.
+    ///
+    /// As with [`Self::export`], the same line may be written into more than one
+    /// index file by [`indices_of`]'s position/common-word bucketing; since this
+    /// samples the raw, undeduplicated stream, such a line is proportionally more
+    /// likely to appear - including more than once in the same sample.
+    ///
+    /// Returns fewer than `n` lines if the collection holds fewer than `n` lines in
+    /// total.
+    #[cfg(feature = "sampling")]
+    pub fn sample(&self, n: usize) -> io::Result<Vec<String>> {
+        use rand::Rng;
+
+        let mut reservoir: Vec<String> = Vec::with_capacity(n);
+        let mut rng = rand::thread_rng();
+
+        for (seen, line) in self.iter_lines()?.enumerate() {
+            let line = String::from_utf8_lossy(&line?).into_owned();
+
+            if reservoir.len() < n {
+                reservoir.push(line);
+            } else {
+                let candidate = rng.gen_range(0..=seen);
+                if candidate < n {
+                    reservoir[candidate] = line;
+                }
+            }
+        }
+
+        Ok(reservoir)
+    }
+
+    /// Remove every occurrence of `line` from this collection, for takedown/GDPR-style
+    /// requests to erase a specific entry.
+    ///
+    /// [`indices_of`] gives the same candidate keys that [`Self::add`] would have
+    /// written `line` into, so only those index files are read back and rewritten,
+    /// rather than walking the whole collection like [`Self::iter_lines`] does. Each
+    /// candidate file is rewritten in place via [`IndexFile::post_process`], which also
+    /// regenerates its offsets sidecar (and its `fst_index` sidecar, if enabled), so
+    /// the collection stays consistent for subsequent searches.
+    ///
+    /// This reads and rewrites files directly on disk, bypassing any buffered writes
+    /// still held by this collection's own [`IndexFile`]s; call [`Self::flush_all`]
+    /// first if `add` may still be in flight for the same keys.
+    ///
+    /// A candidate file is never deleted and recreated as separate steps: the
+    /// filtered contents are written to a sibling temporary file first, which is
+    /// then [`fs::rename`]d over the original - the same pattern
+    /// `IndexFile::dedup_on_disk` uses - so a crash between the two leaves either
+    /// the old or the new contents intact, never an empty or missing bucket.
+    ///
+    /// Returns the number of copies removed, across all candidate index files.
+    pub fn remove_line(&self, line: &[u8]) -> io::Result<usize> {
+        let mut removed = 0;
+
+        for key in indices_of::<LENGTH, DEPTH>(line) {
+            let path = path_for_key(&key, &self.dir)?;
+            if !path.is_file() {
+                continue;
+            }
+
+            let mut remaining = Vec::new();
+            let mut removed_here = 0;
+            for existing in self.iter_lines_for_key(&key)? {
+                let existing = existing?;
+                if existing == line {
+                    removed_here += 1;
+                } else {
+                    remaining.push(existing);
+                }
+            }
+
+            if removed_here == 0 {
+                continue;
+            }
+
+            if remaining.is_empty() {
+                // Nothing to keep; a plain removal is already a single atomic step.
+                fs::remove_file(&path)?;
+            } else {
+                let mut buffer = Vec::new();
+                for existing in &remaining {
+                    buffer.extend_from_slice(existing);
+                    buffer.push(b'\n');
+                }
+
+                let mut temp_file_name =
+                    path.file_name().expect("index paths always have a file name").to_owned();
+                temp_file_name.push(".removing");
+                let temp_path = path.with_file_name(temp_file_name);
+
+                fs::write(&temp_path, &buffer)?;
+                fs::rename(&temp_path, &path)?;
+            }
+
+            IndexFile::<MAX_BUFFER>::new(key, self.dir.clone())?.post_process()?;
+            removed += removed_here;
+        }
+
+        Ok(removed)
+    }
+
+    /// Validate this collection's `LENGTH`/`DEPTH` parameters against the manifest
+    /// written for it, if any.
+    ///
+    /// Returns `Ok(())` if there is no manifest on disk yet, so that a collection
+    /// which hasn't been finalized yet is not blocked from accepting writes.
+    pub fn validate_manifest(&self) -> io::Result<()> {
+        match IndexManifest::read(&self.dir) {
+            Ok(manifest) => {
+                if let Some(mapping) = manifest.custom_substitutions_map() {
+                    crate::character::set_custom_mapping(mapping);
+                }
+
+                manifest.validate::<LENGTH, DEPTH>()
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err),
+        }
     }
 }
 
+/// Maps a [`rusqlite::Error`] to an [`io::Error`], since the rest of the crate
+/// surfaces index errors as [`io::Result`].
+#[cfg(feature = "sqlite_export")]
+fn sqlite_error_to_io(err: rusqlite::Error) -> io::Error {
+    io::Error::other(err)
+}
+
 impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize> Drop
     for IndexCollection<LENGTH, DEPTH, MAX_BUFFER>
 {
     fn drop(&mut self) {
-        self.post_process()
-            .expect("Failed to post-process the index collection.");
+        if self.read_only {
+            return;
+        }
+
+        if let Err(err) = self.post_process() {
+            crate::error!(
+                target: LOG_TARGET,
+                "Failed to post-process the index collection while dropping it: {err}. \
+                 Call `IndexCollection::finalize` explicitly to handle this error instead \
+                 of relying on `Drop`.",
+                err = err,
+            );
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod read_only_tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+
+    #[test]
+    fn open_read_only_never_creates_the_directory_and_rejects_writes() {
+        let dir = path::PathBuf::from(TEST_DIR).join("read_only_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let collection = IndexCollection::<3, 1>::open_read_only(dir.clone());
+        assert!(collection.add(b"password").is_err());
+        assert!(!dir.exists());
+
+        drop(collection);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn open_validated_rejects_a_manifest_built_with_different_parameters() {
+        let dir = path::PathBuf::from(TEST_DIR).join("open_validated_mismatch_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        IndexManifest::new::<4, 1>(&[] as &[path::PathBuf], 0, 0)
+            .unwrap()
+            .write(&dir)
+            .unwrap();
+
+        match IndexCollection::<3, 1>::open_validated(dir.clone()) {
+            Ok(_) => panic!("Expected a LENGTH mismatch to be rejected."),
+            Err(err) => assert_eq!(err.kind(), io::ErrorKind::InvalidData),
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn open_validated_succeeds_when_there_is_no_manifest_yet() {
+        let dir = path::PathBuf::from(TEST_DIR).join("open_validated_no_manifest_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(IndexCollection::<3, 1>::open_validated(dir.clone()).is_ok());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod locking_tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+
+    #[test]
+    fn a_second_write_capable_collection_blocks_until_the_first_is_dropped() {
+        let dir = path::PathBuf::from(TEST_DIR).join("locking_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let first = IndexCollection::<3, 1>::new(dir.clone());
+        let unblocked = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let dir = dir.clone();
+            let unblocked = unblocked.clone();
+            thread::spawn(move || {
+                let _second = IndexCollection::<3, 1>::new(dir);
+                unblocked.store(true, Ordering::SeqCst);
+            })
+        };
+
+        // The second collection should still be waiting on the lock a short while
+        // after being spawned, since `first` has not been dropped yet.
+        thread::sleep(Duration::from_millis(200));
+        assert!(!unblocked.load(Ordering::SeqCst));
+
+        drop(first);
+        handle.join().expect("Locking thread panicked.");
+        assert!(unblocked.load(Ordering::SeqCst));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod auto_flush_tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+
+    #[test]
+    fn spawn_auto_flush_flushes_once_the_byte_budget_is_exceeded() {
+        let dir = path::PathBuf::from(TEST_DIR).join("auto_flush_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let collection = Arc::new(IndexCollection::<3, 1, 4096>::new(dir.clone()));
+        collection.add(b"password").expect("Failed to add line.");
+        assert!(collection.buffered_bytes() > 0);
+
+        // A long interval, so the byte budget - not the timer - is what triggers the
+        // flush; a short poll interval so the test does not have to wait long for it.
+        let handle = collection.spawn_auto_flush(Duration::from_secs(3600), 1);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while collection.buffered_bytes() > 0 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        assert_eq!(collection.buffered_bytes(), 0);
+
+        handle.stop();
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod export_tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+
+    #[test]
+    fn export_deduplicates_and_sorts_lines_across_buckets() {
+        let dir = path::PathBuf::from(TEST_DIR).join("export_test");
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+
+        // "password" and "passphrase" both land in the "pas" bucket, and each is
+        // added twice, to exercise both the across-file merge and the dedup.
+        collection.add(b"password").expect("Failed to add line.");
+        collection.add(b"password").expect("Failed to add line.");
+        collection.add(b"passphrase").expect("Failed to add line.");
+        collection.add(b"letmein").expect("Failed to add line.");
+
+        collection.finalize().expect("Failed to finalize collection.");
+
+        let output = dir.join("wordlist.txt");
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+        let count = collection.export(&output).expect("Failed to export wordlist.");
+        assert_eq!(count, 3);
+
+        let contents = fs::read_to_string(&output).expect("Failed to read exported wordlist.");
+        let mut lines: Vec<&str> = contents.lines().collect();
+        lines.sort();
+        assert_eq!(lines, vec!["letmein", "passphrase", "password"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    #[cfg(feature = "sqlite_export")]
+    fn export_sqlite_writes_a_row_per_key_and_line_with_its_count() {
+        let dir = path::PathBuf::from(TEST_DIR).join("export_sqlite_test");
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+
+        collection.add(b"password").expect("Failed to add line.");
+        collection.add(b"password").expect("Failed to add line.");
+        collection.add(b"letmein").expect("Failed to add line.");
+
+        collection.finalize().expect("Failed to finalize collection.");
+
+        let output = dir.join("export.sqlite");
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+        let rows = collection.export_sqlite(&output).expect("Failed to export SQLite database.");
+        // "password" lands in the "pas" positional bucket as well as the "word"
+        // common-word bucket, so it accounts for two of these rows; "letmein" adds
+        // a third, in its own "let" bucket.
+        assert_eq!(rows, 3);
+
+        // Both additions of "password" are deduplicated within the "pas" bucket by
+        // `IndexFile::post_process`, so its count is 1, same as any other line.
+        let connection = rusqlite::Connection::open(&output).expect("Failed to open exported database.");
+        let count: i64 = connection
+            .query_row(
+                "SELECT count FROM lines WHERE key = 'pas' AND line = 'password'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("Failed to query exported database.");
+        assert_eq!(count, 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(all(test, feature = "sampling", not(feature = "skip_index_write")))]
+mod sample_tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+
+    #[test]
+    fn sample_never_returns_more_lines_than_the_collection_holds() {
+        let dir = path::PathBuf::from(TEST_DIR).join("sample_small_test");
+        let _ = fs::remove_dir_all(&dir);
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+
+        collection.add(b"password").expect("Failed to add line.");
+        collection.add(b"letmein").expect("Failed to add line.");
+        collection.finalize().expect("Failed to finalize collection.");
+
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+        let sample = collection.sample(10).expect("Failed to sample collection.");
+        // "password" lands in both the "pas" positional bucket and the "word"
+        // common-word bucket, so it is present twice in the raw, undeduplicated
+        // stream this samples from.
+        let unique: hashbrown::HashSet<&str> = sample.iter().map(String::as_str).collect();
+        assert_eq!(unique, hashbrown::HashSet::from_iter(["letmein", "password"]));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn sample_returns_exactly_n_lines_when_the_collection_holds_more() {
+        let dir = path::PathBuf::from(TEST_DIR).join("sample_large_test");
+        let _ = fs::remove_dir_all(&dir);
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+
+        for i in 0..50 {
+            collection.add(format!("password{i}").as_bytes()).expect("Failed to add line.");
+        }
+        collection.finalize().expect("Failed to finalize collection.");
+
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+        let sample = collection.sample(10).expect("Failed to sample collection.");
+        assert_eq!(sample.len(), 10);
+        for line in &sample {
+            assert!(line.starts_with("password"));
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod remove_line_tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+
+    #[test]
+    fn remove_line_deletes_every_copy_across_buckets() {
+        let dir = path::PathBuf::from(TEST_DIR).join("remove_line_test");
+        let _ = fs::remove_dir_all(&dir);
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+
+        // "password" lands in both the "pas" positional bucket and the "word"
+        // common-word bucket, so removing it should account for both copies.
+        collection.add(b"password").expect("Failed to add line.");
+        collection.add(b"letmein").expect("Failed to add line.");
+        collection.finalize().expect("Failed to finalize collection.");
+
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+        let removed = collection.remove_line(b"password").expect("Failed to remove line.");
+        assert_eq!(removed, 2);
+
+        let mut lines: Vec<Vec<u8>> = collection
+            .iter_lines()
+            .expect("Failed to iterate collection.")
+            .collect::<io::Result<_>>()
+            .expect("Failed to read a line.");
+        lines.sort();
+        assert_eq!(lines, vec![b"letmein".to_vec()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn remove_line_leaves_other_lines_in_the_same_bucket_untouched() {
+        let dir = path::PathBuf::from(TEST_DIR).join("remove_line_bucket_test");
+        let _ = fs::remove_dir_all(&dir);
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+
+        // Both land in the "pas" bucket; only "password" should be removed from it.
+        // "password" also lands in the "wor" common-word bucket, so it accounts for
+        // two of the removed copies overall.
+        collection.add(b"password").expect("Failed to add line.");
+        collection.add(b"passphrase").expect("Failed to add line.");
+        collection.finalize().expect("Failed to finalize collection.");
+
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+        let removed = collection.remove_line(b"password").expect("Failed to remove line.");
+        assert_eq!(removed, 2);
+
+        let key = super::indices_of::<3, 1>(b"passphrase")
+            .next()
+            .expect("Expected at least one index key for \"passphrase\".");
+        let lines: Vec<Vec<u8>> = collection
+            .iter_lines_for_key(&key)
+            .expect("Failed to iterate key.")
+            .collect::<io::Result<_>>()
+            .expect("Failed to read a line.");
+        assert_eq!(lines, vec![b"passphrase".to_vec()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn remove_line_leaves_no_temporary_files_behind() {
+        let dir = path::PathBuf::from(TEST_DIR).join("remove_line_temp_file_test");
+        let _ = fs::remove_dir_all(&dir);
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+
+        collection.add(b"password").expect("Failed to add line.");
+        collection.add(b"passphrase").expect("Failed to add line.");
+        collection.finalize().expect("Failed to finalize collection.");
+
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+        collection.remove_line(b"password").expect("Failed to remove line.");
+
+        let leftover_temp_files: Vec<_> = fs::read_dir(&dir)
+            .expect("Failed to read collection directory.")
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "removing"))
+            .collect();
+        assert!(leftover_temp_files.is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn remove_line_is_a_no_op_for_a_line_that_was_never_indexed() {
+        let dir = path::PathBuf::from(TEST_DIR).join("remove_line_missing_test");
+        let _ = fs::remove_dir_all(&dir);
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+
+        collection.add(b"password").expect("Failed to add line.");
+        collection.finalize().expect("Failed to finalize collection.");
+
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+        let removed = collection.remove_line(b"nonexistent").expect("Failed to remove line.");
+        assert_eq!(removed, 0);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod snapshot_tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+
+    #[test]
+    fn snapshot_is_unaffected_by_writes_to_the_live_collection_afterwards() {
+        let dir = path::PathBuf::from(TEST_DIR).join("snapshot_test");
+        let destination = path::PathBuf::from(TEST_DIR).join("snapshot_test_destination");
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(&destination);
+
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+        collection.add(b"password").expect("Failed to add line.");
+        collection.snapshot(&destination).expect("Failed to snapshot collection.");
+
+        // Written to the live collection after the snapshot was taken; a hardlinked
+        // "backup" would pick this up too, since it would share the same inode.
+        collection.add(b"letmein").expect("Failed to add line.");
+        collection.finalize().expect("Failed to finalize collection.");
+
+        let snapshot = IndexCollection::<3, 1>::open_read_only(destination.clone());
+        let lines: Vec<Vec<u8>> = snapshot
+            .iter_lines()
+            .expect("Failed to iterate snapshot.")
+            .collect::<io::Result<_>>()
+            .expect("Failed to read a line.");
+        // "password" lands in both the "pas" positional bucket and the "word"
+        // common-word bucket, so it is expected to appear twice; "letmein" appearing
+        // at all would mean the snapshot was still linked to, rather than copied
+        // from, the live collection.
+        assert_eq!(lines, vec![b"password".to_vec(), b"password".to_vec()]);
+
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(&destination);
+    }
+}
+
+#[cfg(all(test, feature = "frequency", not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+    use std::fs;
+
+    #[test]
+    fn top_frequencies_counts_and_orders_by_occurrence() {
+        let dir = path::PathBuf::from(TEST_DIR).join("frequency_test");
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+
+        collection.add(b"password").expect("Failed to add line.");
+        collection.add(b"password").expect("Failed to add line.");
+        collection.add(b"letmein").expect("Failed to add line.");
+
+        assert_eq!(
+            collection.top_frequencies(1),
+            vec![(b"password".to_vec(), 2)]
+        );
+
+        drop(collection);
+        let _ = fs::remove_dir_all(&dir);
     }
 }
@@ -1,17 +1,25 @@
 use std::{
     fs,
-    io::{self, Write},
+    io::{self, Read, Seek, Write},
     mem, path,
-    sync::Mutex,
+    str::FromStr,
+    sync::{atomic::AtomicUsize, Mutex},
 };
 
-use crate::{config::DEFAULT_MAX_BUFFER, path_for_key};
+#[cfg(feature = "fst_index")]
+use std::io::BufRead;
 
-#[cfg(feature = "deduplicate")]
-use hashbrown::HashSet;
+use crate::{config::DEFAULT_MAX_BUFFER, offsets_path_for_key, path_for_key, wal_path_for_key};
+
+use super::LineOffsetTable;
 
 #[cfg(feature = "deduplicate")]
-type FxHashSet32<T> = HashSet<T, std::hash::BuildHasherDefault<fxhash::FxHasher32>>;
+use super::spilling_dedup::SpillingDedupSet;
+
+#[cfg(feature = "fst_index")]
+use crate::fst_path_for_key;
+#[cfg(feature = "fst_index")]
+use super::FstIndexSet;
 
 /// A buffer for an index file, for a specific key.
 ///
@@ -33,9 +41,18 @@ pub struct IndexFile<const MAX_BUFFER: usize = DEFAULT_MAX_BUFFER> {
     pub(crate) dir: path::PathBuf,
 
     #[cfg(feature = "deduplicate")]
-    pub(crate) seen: Mutex<FxHashSet32<Vec<u8>>>,
+    pub(crate) seen: Mutex<SpillingDedupSet>,
 
     pub(crate) buffer: Mutex<Vec<u8>>,
+
+    /// The number of times this file's buffer has been written out to disk, whether
+    /// from a full buffer during [`Self::add`], an explicit [`Self::flush`], or
+    /// [`Self::post_process`]; see [`Self::flush_count`].
+    pub(crate) flush_count: AtomicUsize,
+
+    /// How aggressively this index file forces its writes to durable storage; see
+    /// [`DurabilityPolicy`].
+    pub(crate) durability: DurabilityPolicy,
 }
 
 impl<const MAX_BUFFER: usize> std::fmt::Debug for IndexFile<MAX_BUFFER> {
@@ -50,9 +67,73 @@ impl<const MAX_BUFFER: usize> std::fmt::Debug for IndexFile<MAX_BUFFER> {
 /// The target for the crate.
 const LOG_TARGET: &str = "IndexFile";
 
+/// How aggressively an [`IndexFile`] forces its writes to durable storage, trading
+/// indexing speed for crash safety on flaky disks or machines that lose power
+/// unexpectedly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DurabilityPolicy {
+    /// Rely on the OS's own write-back caching; the fastest option, but a crash or
+    /// power loss before the kernel flushes its page cache can lose whatever was
+    /// most recently written.
+    #[default]
+    None,
+    /// Call `fsync` after every buffer flush to disk, so at most one buffer's worth
+    /// of writes can be lost to a crash, at the cost of blocking on disk I/O far more
+    /// often.
+    FsyncOnFlush,
+    /// Call `fsync` only once, when [`IndexFile::post_process`] finalizes the file,
+    /// so indexing itself pays no extra I/O cost but the finished file is guaranteed
+    /// durable once indexing completes.
+    FsyncOnFinalize,
+    /// Append every item to a write-ahead log sidecar, `fsync`ing it before it is
+    /// added to the in-memory buffer, so a crash before the buffer is next flushed
+    /// loses nothing - the log is replayed into the main index file the next time an
+    /// [`IndexFile`] for this key is opened, then cleared. Slower than
+    /// [`Self::FsyncOnFlush`] (every item pays for an `fsync`, not just every full
+    /// buffer), but the only policy that survives a crash between flushes without
+    /// re-reading the original input.
+    Wal,
+}
+
+impl DurabilityPolicy {
+    /// This policy's name, used both as a CLI value and in log messages.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::FsyncOnFlush => "fsync-on-flush",
+            Self::FsyncOnFinalize => "fsync-on-finalize",
+            Self::Wal => "wal",
+        }
+    }
+}
+
+impl FromStr for DurabilityPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "fsync-on-flush" => Ok(Self::FsyncOnFlush),
+            "fsync-on-finalize" => Ok(Self::FsyncOnFinalize),
+            "wal" => Ok(Self::Wal),
+            other => Err(format!(
+                "Unknown durability policy {other:?}; expected 'none', 'fsync-on-flush', \
+                 'fsync-on-finalize', or 'wal'."
+            )),
+        }
+    }
+}
+
 impl<const MAX_SIZE: usize> IndexFile<MAX_SIZE> {
-    /// Creates a new instance of [`IndexFile`].
+    /// Creates a new instance of [`IndexFile`], with [`DurabilityPolicy::None`].
     pub fn new(key: String, dir: impl AsRef<path::Path>) -> io::Result<Self> {
+        Self::with_durability(key, dir, DurabilityPolicy::default())
+    }
+
+    /// Creates a new instance of [`IndexFile`], forcing its writes to durable storage
+    /// according to `durability` instead of relying on the OS's own write-back
+    /// caching.
+    pub fn with_durability(key: String, dir: impl AsRef<path::Path>, durability: DurabilityPolicy) -> io::Result<Self> {
         crate::debug!(
             target: LOG_TARGET,
             "Creating a new index for '{key}' at {dir:?}.",
@@ -61,11 +142,19 @@ impl<const MAX_SIZE: usize> IndexFile<MAX_SIZE> {
         );
 
         #[cfg(feature = "deduplicate")]
-        let seen = FxHashSet32::default().into();
+        let seen = Mutex::new(SpillingDedupSet::new(
+            dir.as_ref().join(".dedup_spill").join(&key),
+            crate::config::DEDUP_SPILL_MEMORY_BUDGET,
+        ));
 
         let buffer = Vec::with_capacity(DEFAULT_MAX_BUFFER).into();
 
         fs::create_dir_all(&dir)?;
+
+        if durability == DurabilityPolicy::Wal {
+            Self::recover_wal(&key, dir.as_ref())?;
+        }
+
         Ok(Self {
             key,
             dir: dir.as_ref().to_owned(),
@@ -74,20 +163,114 @@ impl<const MAX_SIZE: usize> IndexFile<MAX_SIZE> {
             seen,
 
             buffer,
+            flush_count: AtomicUsize::new(0),
+            durability,
         })
     }
 
+    /// Replay a write-ahead log left behind by an unclean shutdown into the main
+    /// index file for `key`, then remove it, so a fresh [`IndexFile::with_durability`]
+    /// picks up where the crashed run left off.
+    ///
+    /// [`Self::flush_buffer`] `fsync`s the main file before clearing the log, so a
+    /// crash between those two steps leaves the log still holding bytes that are
+    /// already durable in the main file; replaying it unconditionally would duplicate
+    /// them. Since the log's pending bytes are always an exact copy of whatever was
+    /// last flushed (see the comment in [`Self::flush_buffer`]), that case is detected
+    /// by checking whether the main file's tail already ends with the pending bytes,
+    /// and skipped rather than replayed.
+    fn recover_wal(key: &str, dir: &path::Path) -> io::Result<()> {
+        let wal_path = wal_path_for_key(key, dir)?;
+        if !wal_path.is_file() {
+            return Ok(());
+        }
+
+        let pending = fs::read(&wal_path)?;
+        if !pending.is_empty() {
+            let path = path_for_key(key, dir)?;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            let mut file = fs::OpenOptions::new()
+                .read(true)
+                .append(true)
+                .create(true)
+                .open(&path)?;
+            if Self::wal_already_applied(&mut file, &pending)? {
+                crate::warn!(
+                    target: LOG_TARGET,
+                    "The write-ahead log for '{key}' was already applied before the \
+                     crash that left it behind; discarding it without replaying.",
+                );
+            } else {
+                crate::warn!(
+                    target: LOG_TARGET,
+                    "Recovering {bytes} byte(s) from an unflushed write-ahead log for '{key}'.",
+                    bytes = pending.len(),
+                );
+
+                file.write_all(&pending)?;
+                file.sync_data()?;
+            }
+        }
+
+        fs::remove_file(&wal_path)
+    }
+
+    /// Whether `file` already ends with `pending`, i.e. a previous flush wrote
+    /// `pending` to the main file and `fsync`ed it before the crash that left the
+    /// write-ahead log holding a copy of the same bytes.
+    fn wal_already_applied(file: &mut fs::File, pending: &[u8]) -> io::Result<bool> {
+        let len = file.metadata()?.len();
+        let pending_len = pending.len() as u64;
+        if len < pending_len {
+            return Ok(false);
+        }
+
+        let mut tail = vec![0u8; pending.len()];
+        file.seek(io::SeekFrom::Start(len - pending_len))?;
+        file.read_exact(&mut tail)?;
+
+        Ok(tail == pending)
+    }
+
     /// Returns the path for the index file.
     pub fn path(&self) -> io::Result<path::PathBuf> {
         path_for_key(&self.key, &self.dir)
     }
 
+    /// Returns the path to this index file's write-ahead log sidecar; see
+    /// [`DurabilityPolicy::Wal`].
+    fn wal_path(&self) -> io::Result<path::PathBuf> {
+        wal_path_for_key(&self.key, &self.dir)
+    }
+
+    /// Append `item` to this index file's write-ahead log, `fsync`ing it before
+    /// returning so it is durable even if the process is killed immediately
+    /// afterwards.
+    fn append_to_wal(&self, item: &[u8]) -> io::Result<()> {
+        let path = self.wal_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = fs::OpenOptions::new().append(true).create(true).open(path)?;
+        file.write_all(item)?;
+        file.write_all(b"\n")?;
+        file.sync_data()
+    }
+
     /// Open the file associated with the key.
     pub fn open_for_write(&self) -> io::Result<fs::File> {
-        fs::OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(self.path()?)
+        let path = self.path()?;
+        // The path may be nested under a shard sub-directory that has not been
+        // created yet; see `index_key_path::path_for_key`.
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::OpenOptions::new().append(true).create(true).open(path)
     }
 
     /// Dispose of the existing index if it exists.
@@ -104,12 +287,12 @@ impl<const MAX_SIZE: usize> IndexFile<MAX_SIZE> {
 
     #[cfg(feature = "deduplicate")]
     /// Checks if the key is in the index.
-    pub fn contains(&self, key: &Vec<u8>) -> bool {
+    pub fn contains(&self, key: &[u8]) -> io::Result<bool> {
         self.seen
             .lock()
             .unwrap_or_else(|_| {
                 panic!(
-                    "The bloom filter for '{key}' is poisoned; could not continue.",
+                    "The dedup set for '{key}' is poisoned; could not continue.",
                     key = self.key
                 )
             })
@@ -121,17 +304,16 @@ impl<const MAX_SIZE: usize> IndexFile<MAX_SIZE> {
     /// Returns `true` if the the set already contains the value;
     /// otherwise it is inserted, and `false` is returned.
     #[cfg(feature = "deduplicate")]
-    pub fn contains_or_set(&self, key: Vec<u8>) -> bool {
-        !self
-            .seen
+    pub fn contains_or_set(&self, key: &[u8]) -> io::Result<bool> {
+        self.seen
             .lock()
             .unwrap_or_else(|_| {
                 panic!(
-                    "The bloom filter for '{key}' is poisoned; could not continue.",
+                    "The dedup set for '{key}' is poisoned; could not continue.",
                     key = self.key
                 )
             })
-            .insert(key)
+            .contains_or_set(key)
     }
 
     /// Adds a key to the index.
@@ -140,12 +322,12 @@ impl<const MAX_SIZE: usize> IndexFile<MAX_SIZE> {
     /// operation behind a Mutex.
     ///
     /// Returns `true` if the key was added, and `false` if it was already in the index.
-    pub fn add(&self, item: Vec<u8>) -> io::Result<bool> {
+    pub fn add(&self, item: &[u8]) -> io::Result<bool> {
         #[cfg(feature = "deduplicate")]
-        if self.contains_or_set(item.clone()) {
+        if self.contains_or_set(item)? {
             crate::debug!(
                 "The key '{item}' is already in the index for '{prefix}', skipping.",
-                item = String::from_utf8_lossy(&item),
+                item = String::from_utf8_lossy(item),
                 prefix = self.key,
             );
             return Ok(false);
@@ -154,7 +336,7 @@ impl<const MAX_SIZE: usize> IndexFile<MAX_SIZE> {
         crate::debug!(
             target: LOG_TARGET,
             "Adding the item '{item}' to the index for '{key}'.",
-            item=String::from_utf8_lossy(&item),
+            item=String::from_utf8_lossy(item),
             key=self.key,
         );
 
@@ -178,8 +360,15 @@ impl<const MAX_SIZE: usize> IndexFile<MAX_SIZE> {
             0
         };
 
+        // Recorded durably before it reaches the buffer, so a crash before the next
+        // flush cannot lose it; still under `buffer`'s lock, so a concurrent flush
+        // can never clear this entry from the log before it lands in the buffer.
+        if self.durability == DurabilityPolicy::Wal {
+            self.append_to_wal(item)?;
+        }
+
         // This key is new.
-        buffer.extend_from_slice(&item);
+        buffer.extend_from_slice(item);
         buffer.push(b'\n');
 
         Ok(true)
@@ -202,15 +391,51 @@ impl<const MAX_SIZE: usize> IndexFile<MAX_SIZE> {
         assert_eq!(buffer.len(), 0);
 
         let written = file.write(&outgoing_buffer)?;
+
+        if matches!(self.durability, DurabilityPolicy::FsyncOnFlush | DurabilityPolicy::Wal) {
+            // For `Wal`, this must happen before the log below is cleared - otherwise
+            // a crash between the two could lose data that was in neither the file
+            // nor the log. A crash in this order instead leaves the log holding a copy
+            // of bytes that are already durable in the main file; `recover_wal` detects
+            // that case and skips replaying it rather than duplicating it.
+            file.sync_data()?;
+        }
+
+        if self.durability == DurabilityPolicy::Wal {
+            let wal_path = self.wal_path()?;
+            if wal_path.is_file() {
+                fs::File::create(&wal_path)?.sync_data()?;
+            }
+        }
+
         crate::debug!(
             target: LOG_TARGET,
             "Flushed {written} bytes to {path:?}.",
             written=written,
             path=path_for_key(&self.key, &self.dir)?,
         );
+        self.flush_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
         Ok(written)
     }
 
+    /// The number of bytes currently buffered in memory, not yet flushed to disk.
+    pub fn buffered_len(&self) -> usize {
+        self.buffer
+            .lock()
+            .unwrap_or_else(|_| {
+                panic!(
+                    "The buffer for '{key}' is poisoned; could not continue.",
+                    key = self.key
+                )
+            })
+            .len()
+    }
+
+    /// The number of times this file's buffer has been written out to disk so far.
+    pub fn flush_count(&self) -> usize {
+        self.flush_count.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
     /// Flushes the buffer to the file, and returns the number of bytes written.
     pub fn flush(&self) -> io::Result<usize> {
         let mut existing_buffer = self.buffer.lock().unwrap_or_else(|_| {
@@ -223,8 +448,8 @@ impl<const MAX_SIZE: usize> IndexFile<MAX_SIZE> {
         self.flush_buffer(&mut existing_buffer)
     }
 
-    /// Post-process the index.
-    pub fn post_process(&mut self) -> io::Result<()> {
+    /// Post-process the index, returning the number of bytes flushed to disk.
+    pub fn post_process(&mut self) -> io::Result<usize> {
         crate::debug!(
             target: LOG_TARGET,
             "Post-processing the index for '{key}'.",
@@ -240,11 +465,106 @@ impl<const MAX_SIZE: usize> IndexFile<MAX_SIZE> {
             flushed=flushed,
         );
 
-        // TODO Add per-file deduplication here.
+        // With `deduplicate` enabled, duplicates are already caught at insert time by
+        // `contains_or_set`; without it, an index file may contain the same line many
+        // times over, so it is sorted and deduplicated here instead, within a bounded
+        // memory budget regardless of the file's size.
         #[cfg(not(feature = "deduplicate"))]
-        {}
+        self.dedup_on_disk()?;
+
+        self.write_offsets()?;
 
-        Ok(())
+        #[cfg(feature = "fst_index")]
+        self.write_fst_index()?;
+
+        if self.durability == DurabilityPolicy::FsyncOnFinalize {
+            let path = self.path()?;
+            if path.is_file() {
+                fs::File::open(&path)?.sync_data()?;
+            }
+        }
+
+        Ok(flushed)
+    }
+
+    /// Load this index file's finite-state-transducer sidecar, if
+    /// [`Self::post_process`] has built one for it.
+    #[cfg(feature = "fst_index")]
+    pub fn fst_index(&self) -> io::Result<Option<FstIndexSet>> {
+        let path = fst_path_for_key(&self.key, &self.dir)?;
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        FstIndexSet::open(fs::File::open(path)?).map(Some)
+    }
+
+    /// Build the finite-state-transducer sidecar for this index file's current
+    /// contents; see [`FstIndexSet`].
+    ///
+    /// This needs the full set of lines in memory to sort them, since
+    /// `FstIndexSet` must be built from lines in ascending order; it is only run
+    /// when the `fst_index` feature is enabled, as an additional read-optimised
+    /// artifact alongside the plain index file.
+    #[cfg(feature = "fst_index")]
+    fn write_fst_index(&self) -> io::Result<()> {
+        let path = self.path()?;
+        if !path.is_file() {
+            // Nothing was ever flushed to this index; there is nothing to index.
+            return Ok(());
+        }
+
+        let mut lines = io::BufReader::new(fs::File::open(&path)?)
+            .split(b'\n')
+            .collect::<Result<Vec<_>, _>>()?;
+        lines.sort_unstable();
+        lines.dedup();
+
+        FstIndexSet::build(lines, fs::File::create(fst_path_for_key(&self.key, &self.dir)?)?)
+    }
+
+    /// Sort and deduplicate this index file's lines on disk, within a bounded memory
+    /// budget, replacing its contents with the result.
+    ///
+    /// Only meaningful without the `deduplicate` feature, which already prevents
+    /// duplicates from reaching the file in the first place.
+    #[cfg(not(feature = "deduplicate"))]
+    fn dedup_on_disk(&self) -> io::Result<()> {
+        let path = self.path()?;
+        if !path.is_file() {
+            // Nothing was ever flushed to this index; there is nothing to dedup.
+            return Ok(());
+        }
+
+        let mut sorted_file_name = path.file_name().expect("index paths always have a file name").to_owned();
+        sorted_file_name.push(".sorting");
+        let sorted_path = path.with_file_name(sorted_file_name);
+
+        super::external_sort::sort_and_dedup_lines(
+            fs::File::open(&path)?,
+            fs::File::create(&sorted_path)?,
+            self.dir.join(".sort_runs").join(&self.key),
+            crate::config::EXTERNAL_SORT_RUN_SIZE,
+        )?;
+
+        fs::rename(&sorted_path, &path)
+    }
+
+    /// Build and persist the line-offset sidecar table for this index file.
+    ///
+    /// This lets a search resolve a match's byte offset to the exact line that
+    /// contains it, instead of seeking back a fixed number of bytes and scanning
+    /// forward.
+    fn write_offsets(&self) -> io::Result<()> {
+        let path = self.path()?;
+        if !path.is_file() {
+            // Nothing was ever flushed to this index; there are no offsets to record.
+            return Ok(());
+        }
+
+        let table = LineOffsetTable::build(io::BufReader::new(fs::File::open(&path)?))?;
+
+        table.write(fs::File::create(offsets_path_for_key(&self.key, &self.dir)?)?)
     }
 }
 
@@ -275,6 +595,152 @@ mod test {
 
     use crate::config::TEST_DIR;
 
+    #[test]
+    #[cfg(not(feature = "deduplicate"))]
+    fn post_process_dedups_lines_within_a_bounded_memory_budget() {
+        let key = "index_file_test_dedup";
+
+        let path = '_index_scope: {
+            let mut index = IndexFile::<256>::new(key.to_owned(), TEST_DIR).unwrap();
+            index.dispose().expect("Could not dispose of index.");
+
+            for _ in 0..3 {
+                index.add(b"banana").unwrap();
+            }
+            index.add(b"apple").unwrap();
+            index.add(b"cherry").unwrap();
+            index.add(b"apple").unwrap();
+
+            index.post_process().expect("Could not post-process index.");
+
+            index.path().expect("Could not get path.")
+        };
+
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .create(false)
+            .open(&path)
+            .expect("Could not open file.");
+
+        let lines = io::BufReader::new(file)
+            .lines()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Could not read lines from file.");
+
+        assert_eq!(lines, vec!["apple", "banana", "cherry"]);
+
+        fs::remove_file(&path).expect("Could not remove file.");
+    }
+
+    #[test]
+    fn with_durability_still_writes_lines_under_every_policy() {
+        for durability in [
+            DurabilityPolicy::None,
+            DurabilityPolicy::FsyncOnFlush,
+            DurabilityPolicy::FsyncOnFinalize,
+            DurabilityPolicy::Wal,
+        ] {
+            let key = format!("index_file_test_durability_{}", durability.as_str());
+
+            let path = '_index_scope: {
+                let mut index = IndexFile::<256>::with_durability(key.clone(), TEST_DIR, durability).unwrap();
+                index.dispose().expect("Could not dispose of index.");
+
+                index.add(b"password").unwrap();
+                index.post_process().expect("Could not post-process index.");
+
+                index.path().expect("Could not get path.")
+            };
+
+            let file = fs::OpenOptions::new()
+                .read(true)
+                .create(false)
+                .open(&path)
+                .expect("Could not open file.");
+
+            let lines = io::BufReader::new(file)
+                .lines()
+                .collect::<Result<Vec<_>, _>>()
+                .expect("Could not read lines from file.");
+
+            assert_eq!(lines, vec!["password"]);
+
+            fs::remove_file(&path).expect("Could not remove file.");
+        }
+    }
+
+    #[test]
+    fn opening_a_wal_backed_index_replays_and_clears_an_unflushed_log() {
+        let key = "index_file_test_wal_recovery";
+        let dir = path::PathBuf::from(TEST_DIR);
+
+        let index = IndexFile::<256>::with_durability(key.to_owned(), &dir, DurabilityPolicy::Wal).unwrap();
+        index.dispose().expect("Could not dispose of index.");
+        let wal_path = super::wal_path_for_key(key, &dir).expect("Could not get WAL path.");
+        let _ = fs::remove_file(&wal_path);
+
+        // Simulate a crash: an item made it into the write-ahead log, but the
+        // process died before it reached the buffer or the main file.
+        index.append_to_wal(b"password").expect("Could not write to WAL.");
+        drop(index);
+
+        assert!(wal_path.is_file());
+
+        let recovered = IndexFile::<256>::with_durability(key.to_owned(), &dir, DurabilityPolicy::Wal).unwrap();
+        assert!(!wal_path.is_file(), "The WAL should be cleared once replayed.");
+
+        let path = recovered.path().expect("Could not get path.");
+        let lines = io::BufReader::new(fs::File::open(&path).expect("Could not open file."))
+            .lines()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Could not read lines from file.");
+
+        assert_eq!(lines, vec!["password"]);
+
+        drop(recovered);
+        fs::remove_file(&path).expect("Could not remove file.");
+    }
+
+    #[test]
+    fn opening_a_wal_backed_index_does_not_duplicate_a_log_already_applied_to_the_file() {
+        let key = "index_file_test_wal_recovery_already_applied";
+        let dir = path::PathBuf::from(TEST_DIR);
+
+        let index = IndexFile::<256>::with_durability(key.to_owned(), &dir, DurabilityPolicy::Wal).unwrap();
+        index.dispose().expect("Could not dispose of index.");
+        let wal_path = super::wal_path_for_key(key, &dir).expect("Could not get WAL path.");
+        let _ = fs::remove_file(&wal_path);
+
+        // Simulate a crash between `flush_buffer`'s `fsync` of the main file and its
+        // clearing of the write-ahead log: the item is durable in the main file, but
+        // the log still holds a copy of it too.
+        index.append_to_wal(b"password").expect("Could not write to WAL.");
+        {
+            let mut file = index.open_for_write().expect("Could not open the index for writing.");
+            file.write_all(b"password\n").expect("Could not write to the index.");
+            file.sync_data().expect("Could not fsync the index.");
+        }
+        drop(index);
+
+        assert!(wal_path.is_file());
+
+        let recovered = IndexFile::<256>::with_durability(key.to_owned(), &dir, DurabilityPolicy::Wal).unwrap();
+        assert!(!wal_path.is_file(), "The WAL should be cleared once recovery has run.");
+
+        let path = recovered.path().expect("Could not get path.");
+        let lines = io::BufReader::new(fs::File::open(&path).expect("Could not open file."))
+            .lines()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Could not read lines from file.");
+
+        // The line already made it to the file before the simulated crash; replaying
+        // the log on top of it would have duplicated it.
+        assert_eq!(lines, vec!["password"]);
+
+        drop(recovered);
+        fs::remove_file(&path).expect("Could not remove file.");
+    }
+
     #[test]
     fn sequential_write() {
         let key = "index_file_test";
@@ -286,10 +752,10 @@ mod test {
 
             for i in 0..256 {
                 let key = format!("test_{:03}", i).as_bytes().to_vec();
-                index.add(key.clone()).unwrap();
+                index.add(&key).unwrap();
 
                 #[cfg(feature = "deduplicate")]
-                assert!(index.contains(&key));
+                assert!(index.contains(&key).unwrap());
             }
 
             index.path().expect("Could not get path.")
@@ -307,8 +773,8 @@ mod test {
             let key = format!("test_{:03}", i);
             let mut line = String::new();
             crate::trace!(
-                target: &(LOG_TARGET.to_owned() + "::sequential_write"),
-                "Checking for key '{key}' in the index...",
+                target: LOG_TARGET,
+                "[sequential_write] Checking for key '{key}' in the index...",
             );
             reader.read_line(&mut line).expect("Could not read line.");
             assert_eq!(line.trim(), key);
@@ -332,16 +798,16 @@ mod test {
 
             chunks.enumerate().par_bridge().for_each(|(_id, chunk)| {
                 crate::debug!(
-                    target: &(LOG_TARGET.to_owned() + "::parallel_write"),
-                    "Processing chunk {id} with {size} keys.",
+                    target: LOG_TARGET,
+                    "[parallel_write] Processing chunk {id} with {size} keys.",
                     id=_id,
                     size=chunk.len(),
                 );
                 for key in chunk {
-                    index.add(key.clone()).unwrap();
+                    index.add(key).unwrap();
 
                     #[cfg(feature = "deduplicate")]
-                    assert!(index.contains(key));
+                    assert!(index.contains(key).unwrap());
                 }
             });
 
@@ -80,14 +80,59 @@ impl<const LENGTH: usize, const DEPTH: usize> IndexOf<LENGTH, DEPTH> {
             }
         })
     }
+
+    /// Get the next n-gram, sliding across the *entire* string `stride` characters
+    /// at a time, unlike [`Self::next_by_position`] which only covers the first
+    /// `DEPTH` positions at its head. `DEPTH` has no effect on this method; used by
+    /// [`super::NgramIndex`] to bucket a line under every substring it contains,
+    /// not just the ones at its head.
+    pub fn next_by_ngram(&mut self, stride: usize) -> Option<String> {
+        if self.item.is_empty() || stride == 0 {
+            return None;
+        }
+
+        if self.index + LENGTH > self.item.len() {
+            return None;
+        }
+
+        let index: usize = self.index;
+        self.index += stride;
+
+        let result = self.item.get(index..index + LENGTH).unwrap_or_else(|| {
+            panic!(
+                "Could not substring on {:?} from {:?}..{:?}: boundary not valid.",
+                &self.item,
+                index,
+                index + LENGTH
+            )
+        });
+
+        if self.seen.contains(result) {
+            return self.next_by_ngram(stride);
+        }
+
+        self.seen.insert(result.to_owned());
+
+        Some(result.to_owned())
+    }
+}
+
+/// Fold `item` the same way [`IndexOf`] does before bucketing it: lowercased,
+/// leet-speak/keyboard-adjacency substituted, and anything without a substitution
+/// symbol dropped.
+///
+/// Exposed so [`super::rebalance`] can recompute the same key a line would bucket
+/// under without going through the whole [`IndexOf`] iterator.
+pub(crate) fn fold(item: &[u8]) -> String {
+    string::convert_to_fuzzy_string(&String::from_utf8_lossy(item))
+        .filter_map(|c| character::CharacterClass::from(c).to_substitution_symbol())
+        .collect()
 }
 
 /// Enables the conversion of a string to an index.
 impl<const LENGTH: usize, const DEPTH: usize> From<&[u8]> for IndexOf<LENGTH, DEPTH> {
     fn from(value: &[u8]) -> Self {
-        let cleaned = string::convert_to_fuzzy_string(&String::from_utf8_lossy(value))
-            .filter_map(|c| character::CharacterClass::from(c).to_substitution_symbol())
-            .collect();
+        let cleaned = fold(value);
         let matches = automatons::en_common_words::get_automaton::<LENGTH>()
             .find_iter(&cleaned)
             .collect();
@@ -166,4 +211,33 @@ mod test {
 
         assert_eq!(indices, vec!["mypa", "ypas", "pass", "word"])
     }
+
+    #[test]
+    fn by_ngram_stride_1_covers_every_position() {
+        let mut indices = indices_of::<3, 1>("P45sw0®D".as_bytes());
+        let mut collected = Vec::new();
+        while let Some(index) = indices.next_by_ngram(1) {
+            collected.push(index);
+        }
+
+        assert_eq!(collected, vec!["pas", "ass", "ssw", "swo", "wor", "ord"]);
+    }
+
+    #[test]
+    fn by_ngram_larger_stride_skips_positions() {
+        let mut indices = indices_of::<3, 1>("P45sw0®D".as_bytes());
+        let mut collected = Vec::new();
+        while let Some(index) = indices.next_by_ngram(3) {
+            collected.push(index);
+        }
+
+        assert_eq!(collected, vec!["pas", "swo"]);
+    }
+
+    #[test]
+    fn by_ngram_zero_stride_yields_nothing() {
+        let mut indices = indices_of::<3, 1>("P45sw0®D".as_bytes());
+
+        assert_eq!(indices.next_by_ngram(0), None);
+    }
 }
@@ -0,0 +1,107 @@
+//! Sidecar table recording line start offsets within an index file.
+//!
+
+use std::io::{self, BufRead, Read, Write};
+
+/// A table of byte offsets for the start of each line in an index file.
+///
+/// Built once at post-process time so that a search can resolve a match's byte
+/// offset to the exact line that contains it, instead of seeking back a fixed
+/// number of bytes and re-reading forward.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineOffsetTable {
+    /// The byte offset of the start of each line, in ascending order.
+    starts: Vec<u64>,
+}
+
+impl LineOffsetTable {
+    /// Build a table by scanning `reader` for line starts.
+    pub fn build(mut reader: impl BufRead) -> io::Result<Self> {
+        let mut starts = vec![0u64];
+        let mut pos = 0u64;
+        let mut buffer = Vec::new();
+
+        loop {
+            buffer.clear();
+            let read = reader.read_until(b'\n', &mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            pos += read as u64;
+            starts.push(pos);
+        }
+
+        // The last entry is one-past-the-end of the file; drop it unless the file
+        // was empty, in which case offset 0 is still a valid (empty) starting point.
+        if starts.len() > 1 {
+            starts.pop();
+        }
+
+        Ok(Self { starts })
+    }
+
+    /// Find the start of the line containing byte offset `pos`.
+    ///
+    /// Returns `None` if `pos` precedes every recorded line.
+    pub fn line_start_containing(&self, pos: u64) -> Option<u64> {
+        match self.starts.binary_search(&pos) {
+            Ok(index) => Some(self.starts[index]),
+            Err(0) => None,
+            Err(index) => Some(self.starts[index - 1]),
+        }
+    }
+
+    /// Serialize the table as a sequence of little-endian `u64`s.
+    pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        for &offset in &self.starts {
+            writer.write_all(&offset.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Deserialize a table previously written by [`Self::write`].
+    pub fn read(mut reader: impl Read) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        let starts = bytes
+            .chunks_exact(8)
+            .map(|chunk| {
+                u64::from_le_bytes(chunk.try_into().expect("chunk is exactly 8 bytes long"))
+            })
+            .collect();
+
+        Ok(Self { starts })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_records_every_line_start() {
+        let table = LineOffsetTable::build(io::Cursor::new(b"abc\nde\nfghi\n".as_slice()))
+            .expect("Failed to build the offset table.");
+
+        assert_eq!(table.line_start_containing(0), Some(0));
+        assert_eq!(table.line_start_containing(2), Some(0));
+        assert_eq!(table.line_start_containing(4), Some(4));
+        assert_eq!(table.line_start_containing(5), Some(4));
+        assert_eq!(table.line_start_containing(8), Some(7));
+    }
+
+    #[test]
+    fn round_trips_through_write_and_read() {
+        let table = LineOffsetTable::build(io::Cursor::new(b"abc\nde\nfghi\n".as_slice()))
+            .expect("Failed to build the offset table.");
+
+        let mut bytes = Vec::new();
+        table.write(&mut bytes).expect("Failed to write the offset table.");
+
+        let read_back =
+            LineOffsetTable::read(io::Cursor::new(bytes)).expect("Failed to read the offset table.");
+
+        assert_eq!(table, read_back);
+    }
+}
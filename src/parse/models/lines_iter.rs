@@ -0,0 +1,158 @@
+//! Iterating over every raw line persisted by an [`super::IndexCollection`].
+//!
+
+use std::io::{self, BufRead};
+
+use super::{FileStorage, Storage};
+
+/// An iterator over the lines stored under one or more keys of an
+/// [`super::IndexCollection`], returned by [`super::IndexCollection::iter_lines`] and
+/// [`super::IndexCollection::iter_lines_for_key`].
+///
+/// Lines are yielded in whatever order [`Storage::list_keys`] and each index file's
+/// own storage return them, with no deduplication - the same line may be yielded more
+/// than once if it was written into more than one index file by [`super::indices_of`]'s
+/// position/common-word bucketing, exactly the characteristic that
+/// [`super::IndexCollection::export`] and [`super::IndexCollection::sample`] already
+/// account for.
+///
+/// Lines are yielded as raw bytes, matching how [`super::IndexFile`] stores them -
+/// an index built from arbitrary wordlists cannot be assumed to be valid UTF-8 - so a
+/// caller who wants `String`s should convert them itself, lossily or otherwise.
+pub struct LinesIter {
+    storage: FileStorage,
+    keys: std::vec::IntoIter<String>,
+    current: Option<io::Split<io::BufReader<Box<dyn io::Read + Send>>>>,
+}
+
+impl LinesIter {
+    fn new(storage: FileStorage, keys: Vec<String>) -> Self {
+        Self { storage, keys: keys.into_iter(), current: None }
+    }
+}
+
+impl Iterator for LinesIter {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(split) = &mut self.current {
+                match split.next() {
+                    Some(Ok(line)) if line.is_empty() => continue,
+                    Some(Ok(line)) => return Some(Ok(line)),
+                    Some(Err(err)) => return Some(Err(err)),
+                    None => self.current = None,
+                }
+            } else {
+                let key = self.keys.next()?;
+                match self.storage.open_for_read(&key) {
+                    Ok(reader) => self.current = Some(io::BufReader::new(reader).split(b'\n')),
+                    Err(err) => return Some(Err(err)),
+                }
+            }
+        }
+    }
+}
+
+use super::IndexCollection;
+
+impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
+    IndexCollection<LENGTH, DEPTH, MAX_BUFFER>
+{
+    /// Iterate over every line persisted in this collection, across all index files.
+    ///
+    /// This is the file-walking loop that [`Self::export`], [`Self::export_sqlite`]
+    /// and [`Self::sample`] each perform internally, exposed directly for callers -
+    /// exports, analytics, migrations - that want to stream every line themselves
+    /// without reimplementing it.
+    pub fn iter_lines(&self) -> io::Result<LinesIter> {
+        let storage = FileStorage::new(&self.dir);
+        let keys = storage.list_keys()?;
+        Ok(LinesIter::new(storage, keys))
+    }
+
+    /// Iterate over the lines persisted under a single index file's key.
+    ///
+    /// The iterator's first call to `next` yields an error if `key` does not exist,
+    /// matching [`Storage::open_for_read`]'s behaviour for the underlying file being
+    /// missing.
+    pub fn iter_lines_for_key(&self, key: &str) -> io::Result<LinesIter> {
+        let storage = FileStorage::new(&self.dir);
+        Ok(LinesIter::new(storage, vec![key.to_owned()]))
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+    use std::{fs, path};
+
+    #[test]
+    fn iter_lines_yields_every_line_across_all_keys() {
+        let dir = path::PathBuf::from(TEST_DIR).join("iter_lines_test");
+        let _ = fs::remove_dir_all(&dir);
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+
+        collection.add(b"password").expect("Failed to add line.");
+        collection.add(b"letmein").expect("Failed to add line.");
+        collection.finalize().expect("Failed to finalize collection.");
+
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+        let mut lines: Vec<Vec<u8>> = collection
+            .iter_lines()
+            .expect("Failed to iterate collection.")
+            .collect::<io::Result<_>>()
+            .expect("Failed to read a line.");
+        lines.sort();
+
+        // "password" is written into both the "pas" and "word" buckets.
+        assert_eq!(lines, vec![b"letmein".to_vec(), b"password".to_vec(), b"password".to_vec()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn iter_lines_for_key_only_yields_lines_from_that_key() {
+        let dir = path::PathBuf::from(TEST_DIR).join("iter_lines_for_key_test");
+        let _ = fs::remove_dir_all(&dir);
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+
+        collection.add(b"password").expect("Failed to add line.");
+        collection.add(b"letmein").expect("Failed to add line.");
+        collection.finalize().expect("Failed to finalize collection.");
+
+        // "letmein" is normalised to its own bucket key(s) by `indices_of`, distinct
+        // from the ones "password" lands in; use whichever it actually landed in
+        // rather than assuming it matches its own literal prefix.
+        let key = super::super::indices_of::<3, 1>(b"letmein")
+            .next()
+            .expect("Expected at least one index key for \"letmein\".");
+
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+        let lines: Vec<Vec<u8>> = collection
+            .iter_lines_for_key(&key)
+            .expect("Failed to iterate key.")
+            .collect::<io::Result<_>>()
+            .expect("Failed to read a line.");
+
+        assert_eq!(lines, vec![b"letmein".to_vec()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn iter_lines_for_key_errors_for_a_key_that_does_not_exist() {
+        let dir = path::PathBuf::from(TEST_DIR).join("iter_lines_missing_key_test");
+        let _ = fs::remove_dir_all(&dir);
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+        collection.add(b"password").expect("Failed to add line.");
+        collection.finalize().expect("Failed to finalize collection.");
+
+        let collection = IndexCollection::<3, 1>::new(dir.clone());
+        let mut iter = collection.iter_lines_for_key("nonexistent").expect("Failed to iterate key.");
+        assert!(iter.next().expect("Expected an error for a missing key.").is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,305 @@
+//! On-disk manifest recording the parameters used to build an [`IndexCollection`].
+//!
+//! [`IndexCollection`]: super::IndexCollection
+
+use std::{collections::HashMap as StdHashMap, fs, io, path};
+
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use super::Storage;
+
+/// The name of the manifest file within an index directory.
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// The current on-disk format version for [`IndexManifest`].
+///
+/// Bump this whenever a change to the index file format would make an existing index
+/// unreadable by a newer binary, or vice versa.
+pub const MANIFEST_FORMAT_VERSION: u32 = 1;
+
+/// The parameters and provenance of an [`IndexCollection`], persisted alongside the
+/// index files themselves.
+///
+/// This lets a search fail loudly - instead of silently returning no results - when
+/// the `LENGTH`/`DEPTH` const generics of the [`IndexCollection`] it is opened with do
+/// not match the parameters the index was actually built with.
+///
+/// [`IndexCollection`]: super::IndexCollection
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexManifest {
+    pub format_version: u32,
+    pub index_length: usize,
+    pub index_depth: usize,
+    /// SHA-256 hashes of the source files the index was built from, as hex strings, in
+    /// the order they were indexed; empty for a source read from standard input
+    /// (`-`), since it can only be streamed once.
+    pub source_hashes: Vec<String>,
+    /// CRC-32 digests of the source files, computed as they were streamed through for
+    /// indexing rather than in a separate pass, in the same order as
+    /// [`Self::source_hashes`]; empty unless indexed with `--features checksum_source`.
+    /// A file resumed from a checkpoint partway through has no entry here, since only
+    /// the unread tail would have been checksummed.
+    #[serde(default)]
+    pub source_checksums: Vec<Option<String>>,
+    /// The number of index files written.
+    pub files: usize,
+    /// The total number of bytes flushed across all index files.
+    pub bytes_flushed: usize,
+    /// The custom character-substitution table (see [`crate::character::set_custom_mapping`])
+    /// this index was built with, if any, so that a search against it can install the
+    /// exact same table instead of the built-in leet-speak mapping.
+    #[serde(default)]
+    pub custom_substitutions: Option<Vec<(char, char)>>,
+    /// SHA-256 hashes of each index file, keyed by index key, as they were when the
+    /// manifest was written; used by [`IndexManifest::verify`] to detect index files
+    /// that have since been truncated or corrupted.
+    #[serde(default)]
+    pub index_file_hashes: StdHashMap<String, String>,
+}
+
+impl IndexManifest {
+    /// Build a manifest for an index built with the given `LENGTH`/`DEPTH` parameters.
+    pub fn new<const LENGTH: usize, const DEPTH: usize>(
+        sources: &[impl AsRef<path::Path>],
+        files: usize,
+        bytes_flushed: usize,
+    ) -> io::Result<Self> {
+        Ok(Self {
+            format_version: MANIFEST_FORMAT_VERSION,
+            index_length: LENGTH,
+            index_depth: DEPTH,
+            source_hashes: sources
+                .iter()
+                .map(|source| {
+                    if source.as_ref() == path::Path::new("-") {
+                        Ok(String::new())
+                    } else {
+                        hash_file(source)
+                    }
+                })
+                .collect::<io::Result<_>>()?,
+            source_checksums: Vec::new(),
+            files,
+            bytes_flushed,
+            custom_substitutions: None,
+            index_file_hashes: StdHashMap::new(),
+        })
+    }
+
+    /// Attach a custom character-substitution table to this manifest, so a later
+    /// search against this index can install the exact table it was built with.
+    pub fn with_custom_substitutions(mut self, mapping: Option<&HashMap<char, char>>) -> Self {
+        self.custom_substitutions =
+            mapping.map(|mapping| mapping.iter().map(|(&from, &to)| (from, to)).collect());
+        self
+    }
+
+    /// Attach the per-source-file CRC-32 digests computed while indexing, in the same
+    /// order as the sources passed to [`Self::new`]; see [`Self::source_checksums`].
+    #[cfg(feature = "checksum_source")]
+    pub fn with_source_checksums(mut self, checksums: Vec<Option<String>>) -> Self {
+        self.source_checksums = checksums;
+        self
+    }
+
+    /// Recompute and attach a SHA-256 checksum for every index file currently in
+    /// `dir`, keyed by index key, so that a later [`IndexManifest::verify`] can
+    /// detect files that have since been truncated or corrupted.
+    pub fn with_index_file_hashes(mut self, dir: impl AsRef<path::Path>) -> io::Result<Self> {
+        let dir = dir.as_ref();
+        let storage = super::FileStorage::new(dir);
+
+        self.index_file_hashes = storage
+            .list_keys()?
+            .into_iter()
+            .map(|key| {
+                let path = crate::path_for_key(&key, dir)?;
+                hash_file(&path).map(|hash| (key, hash))
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok(self)
+    }
+
+    /// This manifest's custom substitution table as a lookup map, if any.
+    pub fn custom_substitutions_map(&self) -> Option<HashMap<char, char>> {
+        self.custom_substitutions
+            .as_ref()
+            .map(|pairs| pairs.iter().copied().collect())
+    }
+
+    /// Path to the manifest file within `dir`.
+    pub fn path(dir: impl AsRef<path::Path>) -> path::PathBuf {
+        dir.as_ref().join(MANIFEST_FILE_NAME)
+    }
+
+    /// Write this manifest into `dir`.
+    pub fn write(&self, dir: impl AsRef<path::Path>) -> io::Result<()> {
+        let contents = serde_json::to_vec_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        fs::write(Self::path(dir), contents)
+    }
+
+    /// Read the manifest from `dir`.
+    pub fn read(dir: impl AsRef<path::Path>) -> io::Result<Self> {
+        let contents = fs::read(Self::path(dir))?;
+
+        serde_json::from_slice(&contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Validate that this manifest matches the given `LENGTH`/`DEPTH` parameters.
+    ///
+    /// Returns an error describing the mismatch if either the format version or the
+    /// index parameters do not match.
+    pub fn validate<const LENGTH: usize, const DEPTH: usize>(&self) -> io::Result<()> {
+        if self.format_version != MANIFEST_FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Index manifest format version {found} is not supported by this binary (expected {expected}).",
+                    found = self.format_version,
+                    expected = MANIFEST_FORMAT_VERSION,
+                ),
+            ));
+        }
+
+        if self.index_length != LENGTH || self.index_depth != DEPTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Index was built with LENGTH={found_length}, DEPTH={found_depth}, but this query is using LENGTH={LENGTH}, DEPTH={DEPTH}.",
+                    found_length = self.index_length,
+                    found_depth = self.index_depth,
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Recompute each index file's checksum and compare it against the one recorded
+    /// in [`Self::index_file_hashes`], and check that its file name's key round-trips
+    /// through [`crate::key_for_path`], to catch corruption, truncation, or a file
+    /// having been moved or renamed.
+    #[cfg(feature = "search")]
+    pub fn verify(&self, dir: impl AsRef<path::Path>) -> io::Result<VerifyReport> {
+        let dir = dir.as_ref();
+        let mut report = VerifyReport::default();
+
+        for (key, expected_hash) in &self.index_file_hashes {
+            report.checked += 1;
+
+            let path = match crate::path_for_key(key, dir) {
+                Ok(path) if path.is_file() => path,
+                _ => {
+                    report.missing.push(key.clone());
+                    continue;
+                }
+            };
+
+            if crate::key_for_path(&path).as_deref() != Some(key.as_str()) {
+                report.key_mismatches.push(key.clone());
+            }
+
+            match hash_file(&path) {
+                Ok(hash) if hash == *expected_hash => {}
+                _ => report.corrupt.push(key.clone()),
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// The outcome of [`IndexManifest::verify`].
+#[cfg(feature = "search")]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyReport {
+    /// The number of index files checked.
+    pub checked: usize,
+    /// Keys whose file exists but whose recomputed checksum does not match the one
+    /// recorded in the manifest.
+    pub corrupt: Vec<String>,
+    /// Keys recorded in the manifest whose file could not be found on disk.
+    pub missing: Vec<String>,
+    /// Keys whose file name does not round-trip back to the same key via
+    /// [`crate::key_for_path`], suggesting the file has been renamed or moved.
+    pub key_mismatches: Vec<String>,
+}
+
+#[cfg(feature = "search")]
+impl VerifyReport {
+    /// Whether every checked index file passed verification.
+    pub fn is_ok(&self) -> bool {
+        self.corrupt.is_empty() && self.missing.is_empty() && self.key_mismatches.is_empty()
+    }
+}
+
+/// Hash a file's contents with SHA-256, streaming it in chunks so the whole file is
+/// never held in memory at once.
+fn hash_file(path: impl AsRef<path::Path>) -> io::Result<String> {
+    use sha2::{Digest, Sha256};
+
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(all(test, feature = "search", not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::{models::IndexFile, path_for_key};
+
+    #[test]
+    fn verify_detects_corruption_and_missing_files() {
+        let dir = std::env::temp_dir().join(format!(
+            "rockyou2024-manifest-verify-test-{pid}",
+            pid = std::process::id()
+        ));
+
+        let untouched = IndexFile::<256>::new("untouched".to_owned(), &dir).unwrap();
+        untouched.add(b"password").unwrap();
+        untouched.flush().unwrap();
+
+        let corrupted = IndexFile::<256>::new("corrupted".to_owned(), &dir).unwrap();
+        corrupted.add(b"letmein").unwrap();
+        corrupted.flush().unwrap();
+
+        let vanishing = IndexFile::<256>::new("vanishing".to_owned(), &dir).unwrap();
+        vanishing.add(b"qwerty").unwrap();
+        vanishing.flush().unwrap();
+
+        let manifest = IndexManifest {
+            format_version: MANIFEST_FORMAT_VERSION,
+            index_length: 3,
+            index_depth: 1,
+            source_hashes: Vec::new(),
+            source_checksums: Vec::new(),
+            files: 3,
+            bytes_flushed: 0,
+            custom_substitutions: None,
+            index_file_hashes: StdHashMap::new(),
+        }
+        .with_index_file_hashes(&dir)
+        .expect("Failed to hash index files.");
+
+        // Corrupt one file, and remove another, after the manifest recorded their
+        // original checksums.
+        fs::write(path_for_key("corrupted", &dir).unwrap(), b"tampered\n").unwrap();
+        fs::remove_file(path_for_key("vanishing", &dir).unwrap()).unwrap();
+
+        let report = manifest.verify(&dir).expect("Failed to verify index directory.");
+
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.corrupt, vec!["corrupted".to_owned()]);
+        assert_eq!(report.missing, vec!["vanishing".to_owned()]);
+        assert!(report.key_mismatches.is_empty());
+        assert!(!report.is_ok());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
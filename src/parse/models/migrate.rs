@@ -0,0 +1,243 @@
+//! Upgrade an index directory built by an older version of this crate to the
+//! current on-disk layout - sharding legacy flat index files under their
+//! first-character sub-directory, and backfilling `.offsets` sidecars a pre-`v1`
+//! index never had - either in place or into a new directory.
+
+use std::{fs, io, path};
+
+use super::gc::{OPAQUE_BACKEND_DIR_NAMES, SIDECAR_SUFFIXES};
+use super::index_collection::hardlink_dir_recursive;
+use super::{IndexManifest, LineOffsetTable, MANIFEST_FORMAT_VERSION};
+use crate::index_key_path::{key_for_file_name, shard_dir_for_key};
+
+/// What [`migrate`] changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// Keys of legacy flat index files moved under their first-character shard.
+    pub sharded: Vec<String>,
+    /// Keys of index files that were missing a `.offsets` sidecar, which was built
+    /// from scratch.
+    pub offsets_rebuilt: Vec<String>,
+}
+
+impl MigrationReport {
+    /// The total number of files touched by the migration.
+    pub fn total(&self) -> usize {
+        self.sharded.len() + self.offsets_rebuilt.len()
+    }
+}
+
+/// Upgrade the index at `source` to the current on-disk layout, writing the result
+/// to `destination` and refreshing its manifest's checksums.
+///
+/// `destination` may be the same path as `source`, to migrate in place; otherwise
+/// every file under `source` is hardlinked into `destination` first (see
+/// [`hardlink_dir_recursive`]), leaving `source` untouched, before it is upgraded.
+///
+/// This only formalises the two parts of the on-disk layout that have changed
+/// since the manifest's `format_version` field was introduced - sharding and
+/// `.offsets` sidecars - both of which a fresh index already gets for free; there
+/// is no `compressed` layout to migrate into yet, since this crate does not depend
+/// on a compression library.
+pub fn migrate(
+    source: impl AsRef<path::Path>,
+    destination: impl AsRef<path::Path>,
+) -> io::Result<MigrationReport> {
+    let source = source.as_ref();
+    let destination = destination.as_ref();
+    let mut report = MigrationReport::default();
+
+    if source != destination {
+        fs::create_dir_all(destination)?;
+        hardlink_dir_recursive(source, destination)?;
+    }
+
+    shard_legacy_files(destination, &mut report)?;
+    rebuild_missing_offsets(destination, &mut report)?;
+
+    let manifest = IndexManifest::read(destination)
+        .unwrap_or_else(|_| IndexManifest {
+            format_version: MANIFEST_FORMAT_VERSION,
+            index_length: crate::config::INDEX_LENGTH,
+            index_depth: crate::config::INDEX_DEPTH,
+            source_hashes: Vec::new(),
+            source_checksums: Vec::new(),
+            files: 0,
+            bytes_flushed: 0,
+            custom_substitutions: None,
+            index_file_hashes: Default::default(),
+        })
+        .with_index_file_hashes(destination)?;
+
+    IndexManifest {
+        format_version: MANIFEST_FORMAT_VERSION,
+        ..manifest
+    }
+    .write(destination)?;
+
+    Ok(report)
+}
+
+/// Move every legacy flat `subset_*.csv` file (and its sidecars) found directly
+/// under `dir` into its first-character shard sub-directory, then recurse into
+/// every sub-directory that is not itself a shard - shard directories are, by
+/// definition, already at the correct depth - to do the same for any secondary
+/// index or hash-lookup directory that keeps its own copy of the same layout.
+fn shard_legacy_files(dir: &path::Path, report: &mut MigrationReport) -> io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    let entries = fs::read_dir(dir)?.filter_map(Result::ok).collect::<Vec<_>>();
+
+    for entry in &entries {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some(key) = key_for_file_name(file_name) else {
+            continue;
+        };
+
+        let shard_dir = shard_dir_for_key(key, dir);
+        if shard_dir == dir {
+            continue;
+        }
+
+        fs::create_dir_all(&shard_dir)?;
+        fs::rename(&path, shard_dir.join(file_name))?;
+
+        for suffix in SIDECAR_SUFFIXES {
+            let mut sidecar_name = file_name.to_owned();
+            sidecar_name.push_str(suffix);
+            let sidecar = dir.join(&sidecar_name);
+            if sidecar.is_file() {
+                fs::rename(&sidecar, shard_dir.join(&sidecar_name))?;
+            }
+        }
+
+        report.sharded.push(key.to_owned());
+    }
+
+    for entry in &entries {
+        let path = entry.path();
+        let is_shard_dir = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.chars().count() == 1);
+
+        if path.is_dir() && !is_shard_dir {
+            let is_opaque = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| OPAQUE_BACKEND_DIR_NAMES.contains(&name));
+
+            if !is_opaque {
+                shard_legacy_files(&path, report)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Recursively build a `.offsets` sidecar for every index file under `dir` that
+/// does not already have one.
+fn rebuild_missing_offsets(dir: &path::Path, report: &mut MigrationReport) -> io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)?.filter_map(Result::ok) {
+        let path = entry.path();
+
+        if path.is_dir() {
+            rebuild_missing_offsets(&path, report)?;
+            continue;
+        }
+
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some(key) = key_for_file_name(file_name) else {
+            continue;
+        };
+
+        let mut offsets_name = file_name.to_owned();
+        offsets_name.push_str(".offsets");
+        let offsets_path = path.with_file_name(offsets_name);
+        if offsets_path.is_file() {
+            continue;
+        }
+
+        let table = LineOffsetTable::build(io::BufReader::new(fs::File::open(&path)?))?;
+        table.write(fs::File::create(&offsets_path)?)?;
+        report.offsets_rebuilt.push(key.to_owned());
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+
+    #[test]
+    fn shards_legacy_flat_files_and_backfills_missing_offsets() {
+        let dir = path::PathBuf::from(TEST_DIR).join("migrate_in_place_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        // A pre-sharding flat index file, with no `.offsets` sidecar.
+        fs::write(dir.join("subset_password.csv"), b"password\nletmein\n").unwrap();
+
+        // A file that is already in the current layout, which must be left alone.
+        fs::create_dir_all(dir.join("q")).unwrap();
+        fs::write(dir.join("q").join("subset_qwerty.csv"), b"qwerty\n").unwrap();
+        fs::write(dir.join("q").join("subset_qwerty.csv.offsets"), b"stub").unwrap();
+
+        let report = migrate(&dir, &dir).expect("migration failed");
+
+        assert_eq!(report.sharded, vec!["password".to_owned()]);
+        assert_eq!(report.offsets_rebuilt, vec!["password".to_owned()]);
+
+        assert!(dir.join("p").join("subset_password.csv").is_file());
+        assert!(!dir.join("subset_password.csv").is_file());
+        assert!(dir.join("p").join("subset_password.csv.offsets").is_file());
+
+        assert_eq!(fs::read_to_string(dir.join("q").join("subset_qwerty.csv.offsets")).unwrap(), "stub");
+
+        let manifest = IndexManifest::read(&dir).expect("manifest was not written");
+        assert_eq!(manifest.format_version, MANIFEST_FORMAT_VERSION);
+        assert!(manifest.index_file_hashes.contains_key("password"));
+        assert!(manifest.index_file_hashes.contains_key("qwerty"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn migrating_into_a_new_directory_leaves_the_source_untouched() {
+        let source = path::PathBuf::from(TEST_DIR).join("migrate_source_test");
+        let destination = path::PathBuf::from(TEST_DIR).join("migrate_destination_test");
+        let _ = fs::remove_dir_all(&source);
+        let _ = fs::remove_dir_all(&destination);
+        fs::create_dir_all(&source).unwrap();
+
+        fs::write(source.join("subset_password.csv"), b"password\n").unwrap();
+
+        let report = migrate(&source, &destination).expect("migration failed");
+
+        assert_eq!(report.sharded, vec!["password".to_owned()]);
+        assert!(source.join("subset_password.csv").is_file());
+        assert!(destination.join("p").join("subset_password.csv").is_file());
+        assert!(destination.join("p").join("subset_password.csv.offsets").is_file());
+
+        let _ = fs::remove_dir_all(&source);
+        let _ = fs::remove_dir_all(&destination);
+    }
+}
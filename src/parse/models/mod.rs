@@ -8,3 +8,79 @@ pub use index_file::*;
 
 mod index_of;
 pub use index_of::*;
+pub(crate) use index_of::fold;
+
+mod manifest;
+pub use manifest::*;
+
+mod checkpoint;
+pub use checkpoint::*;
+
+mod gc;
+pub use gc::*;
+
+mod rebalance;
+pub use rebalance::*;
+
+mod migrate;
+pub use migrate::*;
+
+mod line_offsets;
+pub use line_offsets::*;
+
+mod storage;
+pub use storage::*;
+
+mod lines_iter;
+pub use lines_iter::*;
+
+#[cfg(not(feature = "deduplicate"))]
+mod external_sort;
+
+#[cfg(feature = "deduplicate")]
+mod spilling_dedup;
+
+#[cfg(feature = "fst_index")]
+mod fst_index;
+#[cfg(feature = "fst_index")]
+pub use fst_index::*;
+
+#[cfg(feature = "frequency")]
+mod frequency_report;
+#[cfg(feature = "frequency")]
+pub use frequency_report::*;
+
+#[cfg(feature = "hash_lookup")]
+mod hash_index;
+#[cfg(feature = "hash_lookup")]
+pub use hash_index::*;
+
+#[cfg(feature = "reversed_index")]
+mod reversed_index;
+#[cfg(feature = "reversed_index")]
+pub use reversed_index::*;
+
+#[cfg(feature = "case_folded_index")]
+mod case_folded_index;
+#[cfg(feature = "case_folded_index")]
+pub use case_folded_index::*;
+
+#[cfg(feature = "fuzzed_index")]
+mod fuzzed_index;
+#[cfg(feature = "fuzzed_index")]
+pub use fuzzed_index::*;
+
+#[cfg(feature = "ngram_index")]
+mod ngram_index;
+#[cfg(feature = "ngram_index")]
+pub use ngram_index::*;
+
+#[cfg(feature = "phonetic_index")]
+mod phonetic_index;
+#[cfg(feature = "phonetic_index")]
+pub use phonetic_index::*;
+
+#[cfg(feature = "symspell_index")]
+mod symspell_index;
+#[cfg(feature = "symspell_index")]
+pub use symspell_index::*;
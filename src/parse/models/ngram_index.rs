@@ -0,0 +1,133 @@
+//! A secondary index bucketing every line under all of its n-grams, not just the
+//! ones at its head, so a substring query anywhere in the line can narrow its
+//! candidate files.
+//!
+
+use std::{io, path};
+
+use crate::config::DEFAULT_MAX_BUFFER;
+
+use super::{indices_of, IndexCollection, IndexStats};
+
+/// The sub-directory an [`NgramIndex`] is nested under, relative to the primary
+/// collection's directory it accompanies - a multi-character name so it is never
+/// mistaken for one of [`super::IndexCollection`]'s own single-character shard
+/// sub-directories when that collection lists its own keys.
+const SUBDIRECTORY: &str = "ngram";
+
+/// A secondary index bucketing every line under all of its n-grams, a configurable
+/// `stride` apart, built alongside a primary [`IndexCollection`] over the same
+/// lines.
+///
+/// [`super::indices_of`] only ever buckets a line by its head - the literal
+/// characters at the start, plus whole common words - so a substring query landing
+/// mid-line has no narrower set of candidate files to scan than the whole
+/// collection. This index instead buckets a line under every substring of the
+/// primary index's key length starting `stride` characters apart, so a query whose
+/// match happens to land on one of those positions narrows the same way a prefix
+/// query already does; see
+/// [`crate::search::NgramIndex::find_lines_containing_anywhere`] for the query
+/// side, behind the `search` feature. A `stride` of `1` covers every position (and
+/// so guarantees a match will be found for any occurring substring) at the cost of
+/// the largest possible secondary index; a larger `stride` shrinks the index at the
+/// cost of only catching matches that happen to land on a bucketed position - the
+/// size/recall trade-off this index exists for.
+pub struct NgramIndex<
+    const LENGTH: usize,
+    const DEPTH: usize = 1,
+    const MAX_BUFFER: usize = DEFAULT_MAX_BUFFER,
+> {
+    pub(crate) collection: IndexCollection<LENGTH, DEPTH, MAX_BUFFER>,
+    stride: usize,
+}
+
+impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
+    NgramIndex<LENGTH, DEPTH, MAX_BUFFER>
+{
+    /// Open (or create) the n-gram index nested under `dir`, alongside the primary
+    /// collection rooted there, bucketing lines `stride` characters apart.
+    pub fn new(dir: impl Into<path::PathBuf>, stride: usize) -> Self {
+        Self {
+            collection: IndexCollection::new(dir.into().join(SUBDIRECTORY)),
+            stride,
+        }
+    }
+
+    /// Open the n-gram index nested under `dir` for reading only, without
+    /// buffering writes; see [`IndexCollection::open_read_only`].
+    pub fn open_read_only(dir: impl Into<path::PathBuf>, stride: usize) -> Self {
+        Self {
+            collection: IndexCollection::open_read_only(dir.into().join(SUBDIRECTORY)),
+            stride,
+        }
+    }
+
+    /// Whether an n-gram index has actually been built under `dir`. `false` for a
+    /// primary collection indexed before the `ngram_index` feature (or
+    /// `--ngram-stride`) was used, so a caller can fall back to a full scan instead
+    /// of a query silently coming back empty.
+    pub fn exists(dir: impl AsRef<path::Path>) -> bool {
+        dir.as_ref().join(SUBDIRECTORY).is_dir()
+    }
+
+    /// Add `item` to the index under every n-gram `self.stride` characters apart.
+    pub fn add(&self, item: &[u8]) -> io::Result<()> {
+        let mut indices = indices_of::<LENGTH, DEPTH>(item);
+        let mut keys = Vec::new();
+
+        while let Some(key) = indices.next_by_ngram(self.stride) {
+            keys.push(key);
+        }
+
+        self.collection.add_under_keys(item, keys.into_iter())
+    }
+
+    /// Flush and post-process every index file; see [`IndexCollection::finalize`].
+    pub fn finalize(self) -> io::Result<IndexStats> {
+        self.collection.finalize()
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+    use std::{fs, path};
+
+    #[test]
+    fn add_stores_the_line_under_every_stride_apart_ngram() {
+        let dir = path::PathBuf::from(TEST_DIR).join("ngram_index_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let index = NgramIndex::<3, 1>::new(dir.clone(), 3);
+        index.add(b"password").expect("Failed to add line.");
+        index.finalize().expect("Failed to finalize ngram index.");
+
+        assert!(NgramIndex::<3, 1>::exists(&dir));
+
+        // With a stride of 3, "password" is bucketed under "pas" (position 0) and
+        // "swo" (position 3), but not "wor" - that is where a stride of 1 would
+        // have caught it instead.
+        let collection = IndexCollection::<3, 1>::new(dir.join(SUBDIRECTORY));
+        for key in ["pas", "swo"] {
+            let lines: Vec<Vec<u8>> = collection
+                .iter_lines_for_key(key)
+                .expect("Failed to iterate key.")
+                .collect::<io::Result<_>>()
+                .expect("Failed to read a line.");
+            assert_eq!(lines, vec![b"password".to_vec()]);
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn exists_is_false_until_an_ngram_index_has_been_built() {
+        let dir = path::PathBuf::from(TEST_DIR).join("ngram_index_missing_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(!NgramIndex::<3, 1>::exists(&dir));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
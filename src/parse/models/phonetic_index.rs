@@ -0,0 +1,135 @@
+//! A secondary index of every line's Soundex code, so a phonetic search can be
+//! routed to it instead of computing Soundex codes for a full collection scan at
+//! query time.
+//!
+
+use std::{io, path};
+
+use crate::config::DEFAULT_MAX_BUFFER;
+use crate::string::soundex;
+
+use super::{IndexCollection, IndexStats};
+
+/// The sub-directory a [`PhoneticIndex`] is nested under, relative to the primary
+/// collection's directory it accompanies - a multi-character name so it is never
+/// mistaken for one of [`super::IndexCollection`]'s own single-character shard
+/// sub-directories when that collection lists its own keys.
+const SUBDIRECTORY: &str = "phonetic";
+
+/// The byte separating a stored line's Soundex code from its original, so a match
+/// against the code can still be resolved back to the original; see
+/// [`crate::models::CaseFoldedIndex`] for the same `key\tvalue` line format.
+const SEPARATOR: u8 = b'\t';
+
+/// A secondary index storing every line's Soundex code, built alongside a primary
+/// [`IndexCollection`] over the same lines.
+///
+/// [`SearchStyle::Phonetic`](crate::search::SearchStyle::Phonetic) normally
+/// re-encodes every candidate line in a full collection scan, since a Soundex code
+/// has no relationship to a line's literal prefix for `indices_of` to narrow by.
+/// Indexing every line's code once, at build time, and storing it as this index's own
+/// bucketing key lets a phonetic search be narrowed the same way any other search is;
+/// see [`crate::search::PhoneticIndex::find_lines_sounding_like`] for the query side,
+/// behind the `search` feature.
+///
+/// Each stored line is `code\toriginal`, so a match against the code can still be
+/// resolved back to the line's original text.
+pub struct PhoneticIndex<
+    const LENGTH: usize,
+    const DEPTH: usize = 1,
+    const MAX_BUFFER: usize = DEFAULT_MAX_BUFFER,
+> {
+    pub(crate) collection: IndexCollection<LENGTH, DEPTH, MAX_BUFFER>,
+}
+
+impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
+    PhoneticIndex<LENGTH, DEPTH, MAX_BUFFER>
+{
+    /// Open (or create) the phonetic index nested under `dir`, alongside the primary
+    /// collection rooted there.
+    pub fn new(dir: impl Into<path::PathBuf>) -> Self {
+        Self {
+            collection: IndexCollection::new(dir.into().join(SUBDIRECTORY)),
+        }
+    }
+
+    /// Open the phonetic index nested under `dir` for reading only, without
+    /// buffering writes; see [`IndexCollection::open_read_only`].
+    pub fn open_read_only(dir: impl Into<path::PathBuf>) -> Self {
+        Self {
+            collection: IndexCollection::open_read_only(dir.into().join(SUBDIRECTORY)),
+        }
+    }
+
+    /// Whether a phonetic index has actually been built under `dir`. `false` for a
+    /// primary collection indexed before the `phonetic_index` feature (or
+    /// `--phonetic-index`) was used, so a caller can fall back to a full collection
+    /// scan instead of a query silently coming back empty.
+    pub fn exists(dir: impl AsRef<path::Path>) -> bool {
+        dir.as_ref().join(SUBDIRECTORY).is_dir()
+    }
+
+    /// Add `item`, alongside its Soundex code, to the index.
+    pub fn add(&self, item: &[u8]) -> io::Result<()> {
+        let mut line = soundex(&String::from_utf8_lossy(item)).into_bytes();
+        line.push(SEPARATOR);
+        line.extend_from_slice(item);
+
+        self.collection.add(&line)
+    }
+
+    /// Flush and post-process every index file; see [`IndexCollection::finalize`].
+    pub fn finalize(self) -> io::Result<IndexStats> {
+        self.collection.finalize()
+    }
+}
+
+/// Split a stored `code\toriginal` line back into its two halves, or `None` if it is
+/// missing the separator - which should not happen for a line this index wrote
+/// itself.
+pub(crate) fn split_phonetic_line(line: &str) -> Option<(&str, &str)> {
+    line.split_once(SEPARATOR as char)
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+    use std::{fs, path};
+
+    #[test]
+    fn add_stores_the_line_alongside_its_soundex_code() {
+        let dir = path::PathBuf::from(TEST_DIR).join("phonetic_index_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let index = PhoneticIndex::<3, 1>::new(dir.clone());
+        index.add(b"jhonny123").expect("Failed to add line.");
+        index.finalize().expect("Failed to finalize phonetic index.");
+
+        assert!(PhoneticIndex::<3, 1>::exists(&dir));
+
+        let key = super::super::indices_of::<3, 1>(soundex("jhonny123").as_bytes())
+            .next()
+            .expect("Expected at least one index key for the Soundex code.");
+
+        let collection = IndexCollection::<3, 1>::new(dir.join(SUBDIRECTORY));
+        let lines: Vec<Vec<u8>> = collection
+            .iter_lines_for_key(&key)
+            .expect("Failed to iterate key.")
+            .collect::<io::Result<_>>()
+            .expect("Failed to read a line.");
+        assert_eq!(lines, vec![b"J500\tjhonny123".to_vec()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn exists_is_false_until_a_phonetic_index_has_been_built() {
+        let dir = path::PathBuf::from(TEST_DIR).join("phonetic_index_missing_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(!PhoneticIndex::<3, 1>::exists(&dir));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
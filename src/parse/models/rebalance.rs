@@ -0,0 +1,304 @@
+//! Split oversized "hot key" index files (e.g. `pas`, `123`) into longer, more
+//! specific keys, so a search narrows down to a smaller candidate file instead of
+//! always scanning one huge one.
+
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+    path,
+};
+
+use super::fold;
+use crate::index_key_path::key_for_file_name;
+use crate::path_for_key;
+
+/// A key that was split, and the longer keys its lines were redistributed into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebalancedKey {
+    /// The oversized key that was split.
+    pub key: String,
+    /// The longer keys `key`'s lines were redistributed into, in the order they
+    /// were created.
+    pub into: Vec<String>,
+}
+
+/// What [`rebalance`] split.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RebalanceReport {
+    /// Every key that was split, in the order [`rebalance`] found them.
+    pub split: Vec<RebalancedKey>,
+}
+
+impl RebalanceReport {
+    /// The total number of longer keys created across every split.
+    pub fn total_created(&self) -> usize {
+        self.split.iter().map(|split| split.into.len()).sum()
+    }
+}
+
+/// Recursively find every `subset_*.csv` index file under `dir` (including the
+/// first-character shards `subset_*.csv` files are nested under, and the
+/// sub-directories secondary indices and hash-lookup indices keep their own copy of
+/// the same layout in) whose size exceeds `threshold_bytes`, and split each one.
+///
+/// A line only moves out of its current file if it can be shown to belong to a
+/// longer key: refolding it the same way [`super::indices_of`] would, and checking
+/// that the folded line still starts with the file's own key and has at least one
+/// more character to key on. A line that reached this file via a common-word match
+/// rather than its literal prefix - or one whose folded form is exactly the key
+/// itself - is left where it is, since there is no longer key to move it to. If a
+/// split file is itself still over `threshold_bytes` afterwards, it is split again,
+/// so a single call rebalances a hot key however many levels deep it needs to go.
+pub fn rebalance(dir: impl AsRef<path::Path>, threshold_bytes: u64) -> io::Result<RebalanceReport> {
+    let dir = dir.as_ref();
+    let mut report = RebalanceReport::default();
+    rebalance_into(dir, threshold_bytes, &mut report)?;
+    Ok(report)
+}
+
+fn rebalance_into(dir: &path::Path, threshold_bytes: u64, report: &mut RebalanceReport) -> io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    let entries = fs::read_dir(dir)?.filter_map(Result::ok).collect::<Vec<_>>();
+
+    for entry in entries {
+        let path = entry.path();
+
+        if path.is_dir() {
+            rebalance_into(&path, threshold_bytes, report)?;
+            continue;
+        }
+
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some(key) = key_for_file_name(file_name) else {
+            continue;
+        };
+
+        if path.metadata()?.len() <= threshold_bytes {
+            continue;
+        }
+
+        let into = split_file(&path, key, threshold_bytes)?;
+        if !into.is_empty() {
+            report.split.push(RebalancedKey { key: key.to_owned(), into });
+        }
+    }
+
+    Ok(())
+}
+
+/// The directory a key's file was resolved from before sharding, i.e. the directory
+/// [`path_for_key`] should be given to keep placing keys derived from the same
+/// original index alongside it.
+fn index_root_for<'a>(path: &'a path::Path, key: &str) -> &'a path::Path {
+    let parent = path.parent().unwrap_or(path);
+    let is_shard_dir = key
+        .chars()
+        .next()
+        .map(|first_char| parent.file_name().and_then(|name| name.to_str()) == Some(first_char.to_string().as_str()))
+        .unwrap_or(false);
+
+    if is_shard_dir {
+        parent.parent().unwrap_or(parent)
+    } else {
+        parent
+    }
+}
+
+/// Split the oversized file at `path` (keyed under `key`) into longer keys, and
+/// recursively split any of those that are themselves still over `threshold_bytes`.
+/// Returns every longer key lines were moved into, across every level of recursion.
+fn split_file(path: &path::Path, key: &str, threshold_bytes: u64) -> io::Result<Vec<String>> {
+    let index_root = index_root_for(path, key).to_path_buf();
+
+    let mut retained = Vec::new();
+    let mut moved: hashbrown::HashMap<String, Vec<String>> = hashbrown::HashMap::new();
+
+    for line in io::BufReader::new(fs::File::open(path)?).lines() {
+        let line = line?;
+        let folded = fold(line.as_bytes());
+
+        if folded.len() > key.len() && folded.starts_with(key) {
+            let new_key = folded[..key.len() + 1].to_owned();
+            moved.entry(new_key).or_default().push(line);
+        } else {
+            retained.push(line);
+        }
+    }
+
+    if moved.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Write every redistributed line out to its new destination first, and only
+    // truncate the original file - the one operation that can lose data if not
+    // followed through - once every line that was moved out of it is durably on
+    // disk somewhere else. A crash or I/O error partway through this loop leaves
+    // `path` with its original, unsplit contents, at the cost of possibly having
+    // already appended some lines to a `new_path` that a re-run of `rebalance` will
+    // append again; that is a harmless duplicate, not a lost password entry.
+    let mut into = Vec::with_capacity(moved.len());
+    for (new_key, lines) in &moved {
+        let new_path = path_for_key(new_key, &index_root)?;
+        if let Some(dir) = new_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        append_lines(&new_path, lines)?;
+        into.push(new_key.clone());
+    }
+
+    write_lines(path, &retained)?;
+
+    let first_level_count = into.len();
+    for i in 0..first_level_count {
+        let new_key = into[i].clone();
+        let new_path = path_for_key(&new_key, &index_root)?;
+        if new_path.metadata()?.len() > threshold_bytes {
+            into.extend(split_file(&new_path, &new_key, threshold_bytes)?);
+        }
+    }
+
+    Ok(into)
+}
+
+/// Write `render` to `path` by first writing it to a sibling temporary file - named
+/// `path`'s own file name with `suffix` appended - `fsync`ing it, and only then
+/// [`fs::rename`]ing it into place, the same pattern `IndexFile::dedup_on_disk` and
+/// [`super::IndexCollection::remove_line`] use. A crash or I/O error partway through
+/// `render` therefore leaves `path`'s previous contents (or lack thereof) untouched
+/// rather than truncated or half-written.
+fn atomic_write_with(
+    path: &path::Path,
+    suffix: &str,
+    render: impl FnOnce(&mut fs::File) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut temp_file_name = path.file_name().expect("index paths always have a file name").to_owned();
+    temp_file_name.push(suffix);
+    let temp_path = path.with_file_name(temp_file_name);
+
+    let mut temp_file = fs::File::create(&temp_path)?;
+    render(&mut temp_file)?;
+    temp_file.sync_all()?;
+    drop(temp_file);
+
+    fs::rename(&temp_path, path)
+}
+
+/// Overwrite `path` with `lines`, one per line, atomically; see [`atomic_write_with`].
+fn write_lines(path: &path::Path, lines: &[String]) -> io::Result<()> {
+    atomic_write_with(path, ".retaining", |file| {
+        for line in lines {
+            writeln!(file, "{line}")?;
+        }
+        Ok(())
+    })
+}
+
+/// Append `lines` to `path`, one per line, creating it if it does not already exist.
+///
+/// The append is atomic: `path`'s existing contents plus `lines` are written to a
+/// temporary file and renamed over `path` (see [`atomic_write_with`]), rather than
+/// appended to it in place, so a crash partway through never leaves `path` with a
+/// truncated final line.
+fn append_lines(path: &path::Path, lines: &[String]) -> io::Result<()> {
+    let mut existing = Vec::new();
+    if path.is_file() {
+        io::Read::read_to_end(&mut fs::File::open(path)?, &mut existing)?;
+    }
+
+    atomic_write_with(path, ".appending", |file| {
+        file.write_all(&existing)?;
+        for line in lines {
+            writeln!(file, "{line}")?;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+
+    #[test]
+    fn splits_an_oversized_file_into_longer_keys_and_leaves_short_lines_behind() {
+        let dir = path::PathBuf::from(TEST_DIR).join("rebalance_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("p")).unwrap();
+
+        let path = dir.join("p").join("subset_pas.csv");
+        // "pas" itself cannot be split any further; "password" and "pastry" can.
+        fs::write(&path, b"pas\npassword\npastry\npassword\n").unwrap();
+
+        // Large enough that the two split-off files (18 and 7 bytes respectively)
+        // don't themselves need splitting again, but smaller than the original
+        // (29 bytes) so it does.
+        let report = rebalance(&dir, 20).expect("rebalance failed");
+
+        assert_eq!(report.split.len(), 1);
+        assert_eq!(report.split[0].key, "pas");
+        assert_eq!(
+            report.split[0].into.iter().cloned().collect::<hashbrown::HashSet<_>>(),
+            ["pass", "past"].into_iter().map(String::from).collect::<hashbrown::HashSet<_>>()
+        );
+
+        let retained = fs::read_to_string(&path).unwrap();
+        assert_eq!(retained, "pas\n");
+
+        let pass_path = dir.join("p").join("subset_pass.csv");
+        let pass_lines = fs::read_to_string(&pass_path).unwrap();
+        assert_eq!(pass_lines.lines().collect::<Vec<_>>(), vec!["password", "password"]);
+
+        let past_path = dir.join("p").join("subset_past.csv");
+        let past_lines = fs::read_to_string(&past_path).unwrap();
+        assert_eq!(past_lines.lines().collect::<Vec<_>>(), vec!["pastry"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn splitting_a_file_leaves_no_temporary_files_behind() {
+        let dir = path::PathBuf::from(TEST_DIR).join("rebalance_temp_file_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("p")).unwrap();
+
+        let path = dir.join("p").join("subset_pas.csv");
+        fs::write(&path, b"pas\npassword\npastry\npassword\n").unwrap();
+
+        rebalance(&dir, 20).expect("rebalance failed");
+
+        let leftover_temp_files: Vec<_> = fs::read_dir(dir.join("p"))
+            .expect("Failed to read the shard directory.")
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                let file_name = entry.file_name();
+                let file_name = file_name.to_string_lossy();
+                file_name.ends_with(".retaining") || file_name.ends_with(".appending")
+            })
+            .collect();
+        assert!(leftover_temp_files.is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_file_under_the_threshold_is_left_untouched() {
+        let dir = path::PathBuf::from(TEST_DIR).join("rebalance_below_threshold_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("p")).unwrap();
+
+        let path = dir.join("p").join("subset_pas.csv");
+        fs::write(&path, b"password\n").unwrap();
+
+        let report = rebalance(&dir, 1024).expect("rebalance failed");
+
+        assert!(report.split.is_empty());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "password\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,116 @@
+//! A secondary index of every line reversed, so an anchored-suffix query can be
+//! routed to it instead of falling back to a full scan of the primary index.
+//!
+
+use std::{io, path};
+
+use crate::config::DEFAULT_MAX_BUFFER;
+
+use super::{IndexCollection, IndexStats};
+
+/// The sub-directory a [`ReversedIndex`] is nested under, relative to the primary
+/// collection's directory it accompanies - a multi-character name so it is never
+/// mistaken for one of [`super::IndexCollection`]'s own single-character shard
+/// sub-directories when that collection lists its own keys.
+const SUBDIRECTORY: &str = "reversed";
+
+/// A secondary index storing every line reversed, built alongside a primary
+/// [`IndexCollection`] over the same lines.
+///
+/// [`super::indices_of`] only ever buckets a line by its head - the literal
+/// characters at the start, plus whole common words - so a query anchored to the
+/// *end* of a line, like the suffix wildcard `"*2024!"`, has no narrower set of
+/// candidate files to scan than the whole collection. Indexing every line reversed
+/// turns such a suffix into the head of the reversed line, so the same bucketing
+/// narrows a suffix query the same way it already narrows a prefix one; see
+/// [`crate::search::ReversedIndex::find_lines_ending_with`] for the query side,
+/// behind the `search` feature.
+pub struct ReversedIndex<
+    const LENGTH: usize,
+    const DEPTH: usize = 1,
+    const MAX_BUFFER: usize = DEFAULT_MAX_BUFFER,
+> {
+    pub(crate) collection: IndexCollection<LENGTH, DEPTH, MAX_BUFFER>,
+}
+
+impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
+    ReversedIndex<LENGTH, DEPTH, MAX_BUFFER>
+{
+    /// Open (or create) the reversed index nested under `dir`, alongside the primary
+    /// collection rooted there.
+    pub fn new(dir: impl Into<path::PathBuf>) -> Self {
+        Self {
+            collection: IndexCollection::new(dir.into().join(SUBDIRECTORY)),
+        }
+    }
+
+    /// Open the reversed index nested under `dir` for reading only, without
+    /// buffering writes; see [`IndexCollection::open_read_only`].
+    pub fn open_read_only(dir: impl Into<path::PathBuf>) -> Self {
+        Self {
+            collection: IndexCollection::open_read_only(dir.into().join(SUBDIRECTORY)),
+        }
+    }
+
+    /// Whether a reversed index has actually been built under `dir`. `false` for a
+    /// primary collection indexed before the `reversed_index` feature (or `--reversed-
+    /// index`) was used, so a caller can fall back to a full scan instead of a
+    /// suffix query silently coming back empty.
+    pub fn exists(dir: impl AsRef<path::Path>) -> bool {
+        dir.as_ref().join(SUBDIRECTORY).is_dir()
+    }
+
+    /// Add `item`'s reversal to the index.
+    pub fn add(&self, item: &[u8]) -> io::Result<()> {
+        let reversed: Vec<u8> = item.iter().rev().copied().collect();
+        self.collection.add(&reversed)
+    }
+
+    /// Flush and post-process every index file; see [`IndexCollection::finalize`].
+    pub fn finalize(self) -> io::Result<IndexStats> {
+        self.collection.finalize()
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+    use std::{fs, path};
+
+    #[test]
+    fn add_stores_each_line_reversed_under_the_reversed_subdirectory() {
+        let dir = path::PathBuf::from(TEST_DIR).join("reversed_index_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let index = ReversedIndex::<3, 1>::new(dir.clone());
+        index.add(b"password").expect("Failed to add line.");
+        index.finalize().expect("Failed to finalize reversed index.");
+
+        assert!(ReversedIndex::<3, 1>::exists(&dir));
+
+        let key = super::super::indices_of::<3, 1>(b"drowssap")
+            .next()
+            .expect("Expected at least one index key for the reversed line.");
+
+        let collection = IndexCollection::<3, 1>::new(dir.join(SUBDIRECTORY));
+        let lines: Vec<Vec<u8>> = collection
+            .iter_lines_for_key(&key)
+            .expect("Failed to iterate key.")
+            .collect::<io::Result<_>>()
+            .expect("Failed to read a line.");
+        assert_eq!(lines, vec![b"drowssap".to_vec()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn exists_is_false_until_a_reversed_index_has_been_built() {
+        let dir = path::PathBuf::from(TEST_DIR).join("reversed_index_missing_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(!ReversedIndex::<3, 1>::exists(&dir));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
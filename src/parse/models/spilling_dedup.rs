@@ -0,0 +1,207 @@
+//! A memory-bounded set for deduplicating lines at insert time, backing
+//! [`super::IndexFile`]'s `deduplicate`-feature `seen` set.
+//!
+//! Keeping every line ever seen by a single index file in an in-memory `HashSet`
+//! does not scale: a hot bucket can end up holding a large fraction of the whole
+//! dump. [`SpillingDedupSet`] instead keeps lines in memory only up to a configured
+//! byte budget; once that budget is exceeded, the resident set is sorted and spilled
+//! to a run file on disk, alongside a bloom filter summarising its contents, and the
+//! resident set is cleared. A later membership check consults the resident set
+//! first, then each spilled run's bloom filter to cheaply rule out runs that cannot
+//! contain the item - a run file is only read from disk when its bloom filter says
+//! the item might be in it.
+
+use std::{
+    cmp::Ordering,
+    fs,
+    io::{self, BufRead, BufWriter, Write},
+    path,
+};
+
+use bloomfilter::Bloom;
+use hashbrown::HashSet;
+
+type FxHashSet32<T> = HashSet<T, std::hash::BuildHasherDefault<fxhash::FxHasher32>>;
+
+/// A false-positive rate for each run's bloom filter, traded off against the memory
+/// the filters themselves consume.
+const RUN_BLOOM_FALSE_POSITIVE_RATE: f64 = 0.01;
+
+/// One run of lines already spilled to disk: sorted, internally deduplicated, and
+/// accompanied by a bloom filter over its contents.
+struct SpillRun {
+    path: path::PathBuf,
+    bloom: Bloom<[u8]>,
+}
+
+impl SpillRun {
+    /// Whether `item` is present in this run.
+    ///
+    /// The bloom filter can only produce false positives, never false negatives, so
+    /// a "maybe" is followed up with a scan of the (sorted) run file, stopping as
+    /// soon as the scan passes where `item` would sort.
+    fn contains(&self, item: &[u8]) -> io::Result<bool> {
+        if !self.bloom.check(item) {
+            return Ok(false);
+        }
+
+        for line in io::BufReader::new(fs::File::open(&self.path)?).split(b'\n') {
+            match line?.as_slice().cmp(item) {
+                Ordering::Equal => return Ok(true),
+                Ordering::Greater => break,
+                Ordering::Less => continue,
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+/// A set of byte-string lines that deduplicates at insert time within a bounded
+/// memory budget, spilling to disk under `dir` once that budget is exceeded.
+pub struct SpillingDedupSet {
+    dir: path::PathBuf,
+    budget: usize,
+    buffered_bytes: usize,
+    resident: FxHashSet32<Vec<u8>>,
+    runs: Vec<SpillRun>,
+}
+
+impl SpillingDedupSet {
+    /// Create a new set that spills to `dir` once more than `budget` bytes of lines
+    /// are held in memory at once. `dir` is created lazily, only once a spill
+    /// actually happens.
+    pub fn new(dir: impl Into<path::PathBuf>, budget: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            budget,
+            buffered_bytes: 0,
+            resident: FxHashSet32::default(),
+            runs: Vec::new(),
+        }
+    }
+
+    /// Checks if `item` is already in the set, and if not, adds it.
+    ///
+    /// Returns `true` if the set already contained `item`; otherwise it is inserted
+    /// and `false` is returned.
+    pub fn contains_or_set(&mut self, item: &[u8]) -> io::Result<bool> {
+        if self.resident.contains(item) {
+            return Ok(true);
+        }
+
+        for run in &self.runs {
+            if run.contains(item)? {
+                return Ok(true);
+            }
+        }
+
+        self.buffered_bytes += item.len();
+        self.resident.insert(item.to_owned());
+
+        if self.buffered_bytes >= self.budget {
+            self.spill()?;
+        }
+
+        Ok(false)
+    }
+
+    /// Checks if `item` is already in the set, without adding it.
+    pub fn contains(&self, item: &[u8]) -> io::Result<bool> {
+        if self.resident.contains(item) {
+            return Ok(true);
+        }
+
+        for run in &self.runs {
+            if run.contains(item)? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Sort the resident set, write it to a new run file, and clear it.
+    fn spill(&mut self) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        let mut lines: Vec<Vec<u8>> = self.resident.drain().collect();
+        lines.sort_unstable();
+
+        let path = self.dir.join(format!("run_{index}.tmp", index = self.runs.len()));
+        let mut bloom = Bloom::new_for_fp_rate(lines.len().max(1), RUN_BLOOM_FALSE_POSITIVE_RATE);
+
+        let mut writer = BufWriter::new(fs::File::create(&path)?);
+        for line in &lines {
+            bloom.set(line.as_slice());
+            writer.write_all(line)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+
+        self.runs.push(SpillRun { path, bloom });
+        self.buffered_bytes = 0;
+
+        Ok(())
+    }
+}
+
+impl Default for SpillingDedupSet {
+    /// An empty set that spills to a directory named after the current process, with
+    /// the crate's default memory budget; used when an [`super::IndexFile`] is
+    /// opened purely for reading, where nothing is ever inserted.
+    fn default() -> Self {
+        Self::new(path::PathBuf::new(), crate::config::DEDUP_SPILL_MEMORY_BUDGET)
+    }
+}
+
+impl Drop for SpillingDedupSet {
+    // Best-effort; there is no way to surface an error from `Drop`, and a leftover
+    // spill directory does not affect correctness of anything reading the index
+    // files themselves.
+    fn drop(&mut self) {
+        if !self.runs.is_empty() {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spill_dir(name: &str) -> path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "rockyou2024-spilling-dedup-test-{pid}-{name}",
+            pid = std::process::id()
+        ))
+    }
+
+    #[test]
+    fn contains_or_set_reports_duplicates_within_the_resident_set() {
+        let mut set = SpillingDedupSet::new(spill_dir("resident"), 4096);
+
+        assert!(!set.contains_or_set(b"password").unwrap());
+        assert!(set.contains_or_set(b"password").unwrap());
+        assert!(!set.contains_or_set(b"letmein").unwrap());
+    }
+
+    #[test]
+    fn contains_or_set_reports_duplicates_after_spilling_to_disk() {
+        let dir = spill_dir("spilled");
+        let mut set = SpillingDedupSet::new(&dir, 1);
+
+        assert!(!set.contains_or_set(b"password").unwrap());
+        // The budget of 1 byte is exceeded as soon as the first line is buffered, so
+        // this second insertion forces a spill of "password" to disk before "banana"
+        // is buffered.
+        assert!(!set.contains_or_set(b"banana").unwrap());
+
+        assert!(set.contains(b"password").unwrap());
+        assert!(set.contains_or_set(b"password").unwrap());
+        assert!(!set.contains_or_set(b"cherry").unwrap());
+
+        drop(set);
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,313 @@
+//! Abstraction over how an [`IndexFile`](super::IndexFile)'s entries are persisted.
+//!
+
+use std::{fs, io, path, str::FromStr, sync::Arc};
+
+use crate::{index_key_path::key_for_file_name, path_for_key};
+
+/// Abstracts an index entry's persistence so that backends other than flat CSV files
+/// on disk - an embedded key-value store, or plain memory for tests - can be swapped
+/// in without touching `IndexFile`'s buffering or `IndexCollection`'s search logic.
+pub trait Storage: std::fmt::Debug + Send + Sync {
+    /// Append `data` to the entry for `key`, creating it if it does not exist yet.
+    fn append(&self, key: &str, data: &[u8]) -> io::Result<()>;
+
+    /// Open the entry for `key` for reading.
+    fn open_for_read(&self, key: &str) -> io::Result<Box<dyn io::Read + Send>>;
+
+    /// Remove the entry for `key`, if it exists.
+    fn remove(&self, key: &str) -> io::Result<()>;
+
+    /// List every key currently persisted by this backend.
+    fn list_keys(&self) -> io::Result<Vec<String>>;
+}
+
+/// Which [`Storage`] implementation to root a directory in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StorageBackend {
+    /// One file per key; see [`FileStorage`]. The default, and the only backend
+    /// available without the `kv_storage` feature.
+    #[default]
+    File,
+    /// A single embedded key-value store; see [`KvStorage`]. Requires the
+    /// `kv_storage` feature.
+    Kv,
+}
+
+impl StorageBackend {
+    /// This backend's name, used both as a CLI value and in log messages.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Kv => "kv",
+        }
+    }
+
+    /// Build a [`Storage`] of this kind, rooted at `dir`.
+    ///
+    /// Falls back to [`FileStorage`] with a warning if [`Self::Kv`] is selected but
+    /// the `kv_storage` feature was not enabled at compile time.
+    pub fn build(self, dir: impl Into<path::PathBuf>) -> Arc<dyn Storage> {
+        let dir = dir.into();
+        match self {
+            Self::File => FileStorage::shared(dir),
+            #[cfg(feature = "kv_storage")]
+            Self::Kv => KvStorage::shared(dir),
+            #[cfg(not(feature = "kv_storage"))]
+            Self::Kv => {
+                crate::warn!(
+                    "The 'kv' storage backend was selected, but this binary was built \
+                     without the 'kv_storage' feature; falling back to 'file'."
+                );
+                FileStorage::shared(dir)
+            }
+        }
+    }
+}
+
+impl FromStr for StorageBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "file" => Ok(Self::File),
+            "kv" => Ok(Self::Kv),
+            other => Err(format!("Unknown storage backend {other:?}; expected 'file' or 'kv'.")),
+        }
+    }
+}
+
+/// Extracts the index key from a directory entry's file name, if it is one.
+fn key_for_entry(entry: &fs::DirEntry) -> Option<String> {
+    key_for_file_name(entry.file_name().to_str()?).map(ToOwned::to_owned)
+}
+
+/// The default [`Storage`] backend: one file per key, named via
+/// [`crate::path_for_key`] and nested under a first-character shard sub-directory.
+#[derive(Debug, Clone)]
+pub struct FileStorage {
+    dir: path::PathBuf,
+}
+
+impl FileStorage {
+    /// Create a new [`FileStorage`] rooted at `dir`.
+    pub fn new(dir: impl Into<path::PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Create a new [`FileStorage`] rooted at `dir`, wrapped in an [`Arc`] so it can
+    /// be shared between index files.
+    pub fn shared(dir: impl Into<path::PathBuf>) -> Arc<dyn Storage> {
+        Arc::new(Self::new(dir))
+    }
+}
+
+impl Storage for FileStorage {
+    fn append(&self, key: &str, data: &[u8]) -> io::Result<()> {
+        use io::Write;
+
+        fs::create_dir_all(&self.dir)?;
+
+        let path = path_for_key(key, &self.dir)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)?
+            .write_all(data)
+    }
+
+    fn open_for_read(&self, key: &str) -> io::Result<Box<dyn io::Read + Send>> {
+        Ok(Box::new(fs::File::open(path_for_key(key, &self.dir)?)?))
+    }
+
+    fn remove(&self, key: &str) -> io::Result<()> {
+        let path = path_for_key(key, &self.dir)?;
+        if path.is_file() {
+            fs::remove_file(path)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn list_keys(&self) -> io::Result<Vec<String>> {
+        if !self.dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut keys = Vec::new();
+        for entry in fs::read_dir(&self.dir)?.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.is_dir() {
+                // A first-character shard sub-directory; see
+                // `index_key_path::path_for_key`.
+                for shard_entry in fs::read_dir(&path)?.filter_map(Result::ok) {
+                    if let Some(key) = key_for_entry(&shard_entry) {
+                        keys.push(key);
+                    }
+                }
+            } else if let Some(key) = key_for_entry(&entry) {
+                keys.push(key);
+            }
+        }
+
+        Ok(keys)
+    }
+}
+
+/// A [`Storage`] backend that keeps every key in a single embedded key-value store
+/// instead of one file per key, so a collection with a very large number of distinct
+/// keys does not end up as a directory of millions of tiny files the way
+/// [`FileStorage`] would leave it.
+#[cfg(feature = "kv_storage")]
+#[derive(Debug, Clone)]
+pub struct KvStorage {
+    db: sled::Db,
+}
+
+#[cfg(feature = "kv_storage")]
+impl KvStorage {
+    /// Open (or create) a key-value store rooted at `dir`.
+    pub fn new(dir: impl AsRef<path::Path>) -> io::Result<Self> {
+        Ok(Self {
+            db: sled::open(dir).map_err(sled_error_to_io)?,
+        })
+    }
+
+    /// Create a new [`KvStorage`] rooted at `dir`, wrapped in an [`Arc`] so it can be
+    /// shared between index files.
+    ///
+    /// # Panics
+    /// Unlike [`FileStorage::shared`], opening the underlying database cannot be
+    /// deferred to first use, so this panics if `dir` cannot be opened as a
+    /// key-value store.
+    pub fn shared(dir: impl AsRef<path::Path>) -> Arc<dyn Storage> {
+        let path = dir.as_ref();
+        Arc::new(
+            Self::new(path)
+                .unwrap_or_else(|err| panic!("Failed to open the key-value store at {path:?}: {err}")),
+        )
+    }
+}
+
+#[cfg(feature = "kv_storage")]
+impl Storage for KvStorage {
+    fn append(&self, key: &str, data: &[u8]) -> io::Result<()> {
+        self.db
+            .update_and_fetch(key, |existing| {
+                let mut combined = existing.map(<[u8]>::to_vec).unwrap_or_default();
+                combined.extend_from_slice(data);
+                Some(combined)
+            })
+            .map_err(sled_error_to_io)?;
+        Ok(())
+    }
+
+    fn open_for_read(&self, key: &str) -> io::Result<Box<dyn io::Read + Send>> {
+        match self.db.get(key).map_err(sled_error_to_io)? {
+            Some(value) => Ok(Box::new(io::Cursor::new(value.to_vec()))),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No entry for key {key:?} in the key-value store."),
+            )),
+        }
+    }
+
+    fn remove(&self, key: &str) -> io::Result<()> {
+        self.db.remove(key).map_err(sled_error_to_io)?;
+        Ok(())
+    }
+
+    fn list_keys(&self) -> io::Result<Vec<String>> {
+        self.db
+            .iter()
+            .keys()
+            .map(|result| {
+                result
+                    .map_err(sled_error_to_io)
+                    .map(|key| String::from_utf8_lossy(&key).into_owned())
+            })
+            .collect()
+    }
+}
+
+/// Maps a [`sled::Error`] to an [`io::Error`], since the rest of the crate surfaces
+/// index errors as [`io::Result`].
+#[cfg(feature = "kv_storage")]
+fn sled_error_to_io(err: sled::Error) -> io::Error {
+    io::Error::other(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn round_trips_append_and_read() {
+        let dir = std::env::temp_dir().join(format!(
+            "rockyou2024-storage-test-{pid}",
+            pid = std::process::id()
+        ));
+        let storage = FileStorage::new(&dir);
+
+        storage.append("abc", b"hello\n").expect("Failed to append.");
+        storage.append("abc", b"world\n").expect("Failed to append.");
+
+        let mut contents = String::new();
+        storage
+            .open_for_read("abc")
+            .expect("Failed to open for read.")
+            .read_to_string(&mut contents)
+            .expect("Failed to read.");
+        assert_eq!(contents, "hello\nworld\n");
+
+        assert_eq!(storage.list_keys().expect("Failed to list keys."), vec!["abc"]);
+
+        storage.remove("abc").expect("Failed to remove.");
+        assert!(storage.open_for_read("abc").is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn storage_backend_round_trips_through_its_string_form() {
+        assert_eq!(StorageBackend::from_str("file"), Ok(StorageBackend::File));
+        assert_eq!(StorageBackend::from_str("KV"), Ok(StorageBackend::Kv));
+        assert!(StorageBackend::from_str("rocksdb").is_err());
+
+        assert_eq!(StorageBackend::File.as_str(), "file");
+        assert_eq!(StorageBackend::Kv.as_str(), "kv");
+    }
+
+    #[cfg(feature = "kv_storage")]
+    #[test]
+    fn kv_storage_round_trips_append_and_read() {
+        let dir = std::env::temp_dir().join(format!(
+            "rockyou2024-kv-storage-test-{pid}",
+            pid = std::process::id()
+        ));
+        let storage = KvStorage::new(&dir).expect("Failed to open the key-value store.");
+
+        storage.append("abc", b"hello\n").expect("Failed to append.");
+        storage.append("abc", b"world\n").expect("Failed to append.");
+
+        let mut contents = String::new();
+        storage
+            .open_for_read("abc")
+            .expect("Failed to open for read.")
+            .read_to_string(&mut contents)
+            .expect("Failed to read.");
+        assert_eq!(contents, "hello\nworld\n");
+
+        assert_eq!(storage.list_keys().expect("Failed to list keys."), vec!["abc"]);
+
+        storage.remove("abc").expect("Failed to remove.");
+        assert!(storage.open_for_read("abc").is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
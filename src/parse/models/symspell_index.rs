@@ -0,0 +1,180 @@
+//! A secondary index bucketing every line under all of its own deletion variants, a
+//! SymSpell-style precomputed dictionary for near-O(1) bounded edit-distance lookups.
+//!
+
+use std::{io, path};
+
+use hashbrown::HashSet;
+
+use crate::config::DEFAULT_MAX_BUFFER;
+
+use super::{IndexCollection, IndexStats};
+
+/// The sub-directory a [`SymSpellIndex`] is nested under, relative to the primary
+/// collection's directory it accompanies - a multi-character name so it is never
+/// mistaken for one of [`super::IndexCollection`]'s own single-character shard
+/// sub-directories when that collection lists its own keys.
+const SUBDIRECTORY: &str = "symspell";
+
+/// Every string reachable from `word` by deleting up to `max_distance` characters,
+/// including `word` itself.
+///
+/// This is the classic SymSpell trick: a substitution has no bounded-size deletion
+/// closure (it could turn a character into any of an entire alphabet), but a
+/// deletion does, so precomputing every indexed line's own deletion variants once,
+/// at build time, turns a bounded edit-distance query into a set of exact-match
+/// dictionary lookups against the query's own (much smaller) deletion variants,
+/// rather than [`crate::search::expand_by_edit_distance`]'s per-query
+/// deletion-and-substitution expansion across the whole
+/// [`crate::search::SUBSTITUTION_ALPHABET`].
+pub(crate) fn deletion_variants(word: &str, max_distance: usize) -> HashSet<String> {
+    let mut seen = HashSet::new();
+    seen.insert(word.to_owned());
+
+    let mut frontier = seen.clone();
+    for _ in 0..max_distance {
+        let mut next_frontier = HashSet::new();
+        for candidate in &frontier {
+            let chars: Vec<char> = candidate.chars().collect();
+            for skip in 0..chars.len() {
+                let deleted = chars
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, &c)| (i != skip).then_some(c))
+                    .collect::<String>();
+
+                if seen.insert(deleted.clone()) {
+                    next_frontier.insert(deleted);
+                }
+            }
+        }
+        frontier = next_frontier;
+    }
+
+    seen
+}
+
+/// A secondary index bucketing every line under every one of its own deletion
+/// variants up to `max_distance` deletions, built alongside a primary
+/// [`IndexCollection`] over the same lines.
+///
+/// [`crate::search::SearchStyle::EditDistance`] normally scans every candidate index
+/// file, comparing each line against the query with
+/// [`crate::search::bounded_levenshtein_distance`] on the fly. Precomputing every
+/// line's deletion variants once, at build time, lets a query instead be resolved by
+/// generating its own (far smaller) deletion variants and looking each up directly;
+/// see [`crate::search::SymSpellIndex::find_lines_within_edit_distance`] for the
+/// query side, behind the `search` feature.
+///
+/// A query for `max_distance` greater than the one this index was built with cannot
+/// be answered correctly (its deletion variants may miss lines further away than
+/// this index's own deletion closure reaches), so a caller should keep track of the
+/// `max_distance` a given index was built with, the same way [`super::NgramIndex`]
+/// requires its `stride` to be passed back in at open time.
+pub struct SymSpellIndex<
+    const LENGTH: usize,
+    const DEPTH: usize = 1,
+    const MAX_BUFFER: usize = DEFAULT_MAX_BUFFER,
+> {
+    pub(crate) collection: IndexCollection<LENGTH, DEPTH, MAX_BUFFER>,
+    max_distance: usize,
+}
+
+impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
+    SymSpellIndex<LENGTH, DEPTH, MAX_BUFFER>
+{
+    /// Open (or create) the SymSpell index nested under `dir`, alongside the primary
+    /// collection rooted there, bucketing lines under deletion variants up to
+    /// `max_distance` deletions away.
+    pub fn new(dir: impl Into<path::PathBuf>, max_distance: usize) -> Self {
+        Self {
+            collection: IndexCollection::new(dir.into().join(SUBDIRECTORY)),
+            max_distance,
+        }
+    }
+
+    /// Open the SymSpell index nested under `dir` for reading only, without
+    /// buffering writes; see [`IndexCollection::open_read_only`].
+    pub fn open_read_only(dir: impl Into<path::PathBuf>, max_distance: usize) -> Self {
+        Self {
+            collection: IndexCollection::open_read_only(dir.into().join(SUBDIRECTORY)),
+            max_distance,
+        }
+    }
+
+    /// Whether a SymSpell index has actually been built under `dir`. `false` for a
+    /// primary collection indexed before the `symspell_index` feature (or
+    /// `--symspell-max-distance`) was used, so a caller can fall back to
+    /// [`crate::search::expand_by_edit_distance`] instead of a query silently coming
+    /// back empty.
+    pub fn exists(dir: impl AsRef<path::Path>) -> bool {
+        dir.as_ref().join(SUBDIRECTORY).is_dir()
+    }
+
+    /// The maximum edit distance this index was built to answer.
+    pub fn max_distance(&self) -> usize {
+        self.max_distance
+    }
+
+    /// Add `item` to the index under every one of its deletion variants.
+    pub fn add(&self, item: &[u8]) -> io::Result<()> {
+        let keys = deletion_variants(&String::from_utf8_lossy(item), self.max_distance);
+
+        self.collection.add_under_keys(item, keys.into_iter())
+    }
+
+    /// Flush and post-process every index file; see [`IndexCollection::finalize`].
+    pub fn finalize(self) -> io::Result<IndexStats> {
+        self.collection.finalize()
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+    use std::{fs, path};
+
+    #[test]
+    fn deletion_variants_includes_the_word_and_its_single_deletions() {
+        let variants = deletion_variants("ab", 1);
+        assert!(variants.contains("ab"));
+        assert!(variants.contains("a"));
+        assert!(variants.contains("b"));
+        assert_eq!(variants.len(), 3);
+    }
+
+    #[test]
+    fn add_stores_the_line_under_every_deletion_variant() {
+        let dir = path::PathBuf::from(TEST_DIR).join("symspell_index_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let index = SymSpellIndex::<3, 1>::new(dir.clone(), 1);
+        index.add(b"cat").expect("Failed to add line.");
+        index.finalize().expect("Failed to finalize symspell index.");
+
+        assert!(SymSpellIndex::<3, 1>::exists(&dir));
+
+        let collection = IndexCollection::<3, 1>::new(dir.join(SUBDIRECTORY));
+        for key in ["cat", "at", "ct", "ca"] {
+            let lines: Vec<Vec<u8>> = collection
+                .iter_lines_for_key(key)
+                .expect("Failed to iterate key.")
+                .collect::<io::Result<_>>()
+                .expect("Failed to read a line.");
+            assert_eq!(lines, vec![b"cat".to_vec()]);
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn exists_is_false_until_a_symspell_index_has_been_built() {
+        let dir = path::PathBuf::from(TEST_DIR).join("symspell_index_missing_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(!SymSpellIndex::<3, 1>::exists(&dir));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,66 @@
+//! Adding a case-insensitive search method to the case-folded index.
+//!
+
+use crate::models::{split_folded_line, CaseFoldedIndex, IndexCollectionResult};
+
+use super::super::SearchStyle;
+
+impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
+    CaseFoldedIndex<LENGTH, DEPTH, MAX_BUFFER>
+{
+    /// Find every line containing `query`, case-insensitively, using this
+    /// pre-lowercased index to avoid re-lowercasing every candidate index file on the
+    /// fly the way [`crate::models::IndexCollection::find_lines_containing`] with
+    /// [`SearchStyle::CaseInsensitive`] would.
+    ///
+    /// A caller performing a case-insensitive search should route it here instead,
+    /// once [`CaseFoldedIndex::exists`] confirms this index was actually built
+    /// alongside the primary collection.
+    ///
+    /// `query` is lowercased and searched for as a [`SearchStyle::Strict`] substring
+    /// against this index's stored lowercased halves; each hit is then resolved back
+    /// to its original casing.
+    pub fn find_lines_containing_case_insensitively(
+        &self,
+        query: &str,
+        max_results: Option<usize>,
+    ) -> IndexCollectionResult {
+        let lowered_query = query.to_ascii_lowercase();
+
+        self.collection
+            .find_lines_containing(&lowered_query, SearchStyle::Strict, max_results)
+            .iter()
+            .filter_map(|line| split_folded_line(line).map(|(_lowered, original)| original.to_owned()))
+            .collect()
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+    use std::{fs, path};
+
+    #[test]
+    fn find_lines_containing_case_insensitively_restores_the_original_casing() {
+        let dir = path::PathBuf::from(TEST_DIR).join("case_folded_index_search_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let index = CaseFoldedIndex::<3, 1>::new(dir.clone());
+        index.add(b"Password").expect("Failed to add line.");
+        index.add(b"PASSWORD1").expect("Failed to add line.");
+        index.add(b"hello").expect("Failed to add line.");
+        index.finalize().expect("Failed to finalize case-folded index.");
+
+        let index = CaseFoldedIndex::<3, 1>::open_read_only(dir.clone());
+        let mut results: Vec<String> = index
+            .find_lines_containing_case_insensitively("password", None)
+            .into_iter()
+            .collect();
+        results.sort();
+
+        assert_eq!(results, vec!["PASSWORD1".to_string(), "Password".to_string()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
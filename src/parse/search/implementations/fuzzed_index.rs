@@ -0,0 +1,64 @@
+//! Adding a fuzzy search method to the fuzzed index.
+//!
+
+use crate::models::{split_fuzzed_line, FuzzedIndex, IndexCollectionResult};
+use crate::string;
+
+use super::super::SearchStyle;
+
+impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
+    FuzzedIndex<LENGTH, DEPTH, MAX_BUFFER>
+{
+    /// Find every line matching `query` once leet-speak substitutions have been
+    /// folded away on both sides, using this pre-fuzzed index to avoid re-folding
+    /// every candidate index file on the fly the way
+    /// [`crate::models::IndexCollection::find_lines_containing`] with
+    /// [`SearchStyle::Fuzzy { keyboard_adjacent: false }`](SearchStyle::Fuzzy) would.
+    ///
+    /// A caller performing such a fuzzy search should route it here instead, once
+    /// [`FuzzedIndex::exists`] confirms this index was actually built alongside the
+    /// primary collection.
+    ///
+    /// `query` is folded and searched for as a [`SearchStyle::Strict`] substring
+    /// against this index's stored fuzzed halves; each hit is then resolved back to
+    /// its original text.
+    pub fn find_lines_containing_fuzzily(&self, query: &str, max_results: Option<usize>) -> IndexCollectionResult {
+        let fuzzed_query: String = string::convert_to_fuzzy_string(query).collect();
+
+        self.collection
+            .find_lines_containing(&fuzzed_query, SearchStyle::Strict, max_results)
+            .iter()
+            .filter_map(|line| split_fuzzed_line(line).map(|(_fuzzed, original)| original.to_owned()))
+            .collect()
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+    use std::{fs, path};
+
+    #[test]
+    fn find_lines_containing_fuzzily_restores_the_original_text() {
+        let dir = path::PathBuf::from(TEST_DIR).join("fuzzed_index_search_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let index = FuzzedIndex::<3, 1>::new(dir.clone());
+        index.add(b"P4ssw0rd").expect("Failed to add line.");
+        index.add(b"password1").expect("Failed to add line.");
+        index.add(b"hello").expect("Failed to add line.");
+        index.finalize().expect("Failed to finalize fuzzed index.");
+
+        let index = FuzzedIndex::<3, 1>::open_read_only(dir.clone());
+        let mut results: Vec<String> = index
+            .find_lines_containing_fuzzily("password", None)
+            .into_iter()
+            .collect();
+        results.sort();
+
+        assert_eq!(results, vec!["P4ssw0rd".to_string(), "password1".to_string()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
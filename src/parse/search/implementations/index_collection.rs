@@ -1,24 +1,28 @@
 //! Adding search related methods to the index collection.
 //!
 
-use super::super::SearchStyle;
+use super::super::{
+    score_line, trigram_similarity_from_sets, trigrams, CancellationToken, Cursor, Page,
+    ResultOrder, ScoredLine, SearchSemaphore, SearchStyle, TimedSearchResult, TrigramMatch,
+};
 use crate::{
-    models::{indices_of, IndexCollection, IndexCollectionResult, IndexFile},
+    models::{fold, indices_of, FileStorage, IndexCollection, IndexCollectionResult, IndexFile, Storage},
     path_for_key,
+    search::expand_by_edit_distance,
 };
 use hashbrown::HashSet;
 use rayon::prelude::*;
 use std::io;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "lru")]
-use std::sync::{Arc, RwLock, RwLockWriteGuard};
+use std::sync::{RwLock, RwLockWriteGuard};
 
 #[cfg(feature = "lru")]
 use crate::models::IndexCollectionCache;
 
-#[cfg(feature = "lru")]
-use crate::config::CACHE_SIZE;
-
 const LOG_TARGET: &str = "IndexCollection::search_for";
 
 #[cfg(feature = "lru")]
@@ -37,9 +41,10 @@ fn reset_cache_on_poisoned(
         "Failed to acquire lock on cache; cache might be poisoned: {err:?}. Resetting cache...",
         err = err
     );
-    **err.get_mut() = lru::LruCache::new(std::num::NonZeroUsize::new(CACHE_SIZE).expect(
-        "Failed to create a non-zero usize from the cache size; this should be unreachable.",
-    ));
+    // Preserve the configured capacity across the reset, in case it was changed at
+    // runtime via `IndexCollection::set_cache_capacity`.
+    let capacity = err.get_mut().cap();
+    **err.get_mut() = lru::LruCache::new(capacity);
     cache.clear_poison();
 
     // Cache is now empty, so we can just return None.
@@ -49,42 +54,358 @@ fn reset_cache_on_poisoned(
 impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
     IndexCollection<LENGTH, DEPTH, MAX_BUFFER>
 {
+    /// The number of threads a search currently uses, if [`Self::set_search_threads`]
+    /// has configured a dedicated pool; `None` means rayon's default global pool is
+    /// used instead.
+    pub fn search_threads(&self) -> Option<usize> {
+        self.search_pool
+            .read()
+            .expect("Failed to acquire read lock on search pool; search pool might be poisoned.")
+            .as_ref()
+            .map(|pool| pool.current_num_threads())
+    }
+
+    /// Configure how many threads [`Self::find_lines_containing`] uses to scan index
+    /// files in parallel, by building a dedicated rayon thread pool of that size.
+    ///
+    /// Pass `None` to go back to using rayon's default global pool.
+    pub fn set_search_threads(
+        &self,
+        threads: Option<usize>,
+    ) -> Result<(), rayon::ThreadPoolBuildError> {
+        let pool = threads
+            .map(|threads| rayon::ThreadPoolBuilder::new().num_threads(threads).build())
+            .transpose()?
+            .map(Arc::new);
+
+        *self
+            .search_pool
+            .write()
+            .expect("Failed to acquire write lock on search pool; search pool might be poisoned.") =
+            pool;
+
+        Ok(())
+    }
+
+    /// The number of searches [`Self::find_lines_containing`] currently allows to run
+    /// at once, if [`Self::set_search_concurrency`] has configured a limit; `None`
+    /// means searches are not limited beyond whatever the thread pool itself bounds.
+    pub fn search_concurrency(&self) -> Option<usize> {
+        self.search_semaphore
+            .read()
+            .expect(
+                "Failed to acquire read lock on search semaphore; search semaphore might be \
+                 poisoned.",
+            )
+            .as_ref()
+            .map(|semaphore| semaphore.capacity())
+    }
+
+    /// Bound how many calls to [`Self::find_lines_containing`] run at once, by
+    /// building a [`SearchSemaphore`] with that many permits; callers beyond the
+    /// limit block until a permit frees up, queueing rather than piling onto rayon
+    /// and starving indexing or other searches.
+    ///
+    /// Pass `None` to remove the limit.
+    pub fn set_search_concurrency(&self, concurrency: Option<usize>) {
+        *self.search_semaphore.write().expect(
+            "Failed to acquire write lock on search semaphore; search semaphore might be \
+             poisoned.",
+        ) = concurrency.map(|permits| Arc::new(SearchSemaphore::new(permits)));
+    }
+
     /// Search for a string in the index.
     ///
     /// This will return a list of index files where the string could be found.
-    pub fn index_files_for(&self, query: &str) -> Vec<IndexFile<MAX_BUFFER>> {
-        indices_of::<{ LENGTH }, { DEPTH }>(query.as_bytes())
-            .map(|key| {
-                // We could cache the index files, but that would create all sorts of race conditions.
-                // Instead, we'll just create them on the fly.
-                // Since we are just searching for the index, performance should not be a concern.
-                (
-                    key.clone(),
-                    path_for_key(&key, &self.dir).and_then(IndexFile::<{ MAX_BUFFER }>::from_path),
-                )
-            })
-            .filter_map(|(key, result)| match result {
-                Ok(index) => Some(index),
-                Err(error) => {
-                    crate::debug!(
-                        target: LOG_TARGET,
-                        "No index for {key:?}, error: {error:?}",
-                        error = error
-                    );
-                    None
-                }
+    pub fn index_files_for(&self, query: &str, search_style: SearchStyle) -> Vec<IndexFile<MAX_BUFFER>> {
+        match search_style {
+            SearchStyle::Wildcard => self.index_files_for_wildcard(query),
+            SearchStyle::EditDistance { max_distance } => {
+                self.index_files_for_edit_distance(query, max_distance)
+            }
+            // A Soundex code has no relationship to a line's literal prefix (the
+            // bucketing `indices_of` narrows by), so without a `PhoneticIndex`
+            // sidecar to route to instead (see `find_lines_containing` below), the
+            // only correct fallback is scanning every index file.
+            SearchStyle::Phonetic => self.all_index_files(),
+            _ => self.index_files_for_keys(self.keys_with_rebalanced_descendants(query.as_bytes()).into_iter()),
+        }
+    }
+
+    /// [`indices_of`]'s keys for `source`, plus every longer key
+    /// [`crate::models::rebalance`] may have since split the position-0 key into, so
+    /// a hot key that has been rebalanced into longer, more specific files is still
+    /// found.
+    ///
+    /// Only the position-0 key can be extended this way: it is the only one
+    /// guaranteed to be a literal, foldable prefix of `source`, which is what
+    /// `rebalance` splits on. A key found at a later offset (`DEPTH` > 1) or via a
+    /// common-word match has no such relationship to `source` and is left alone.
+    fn keys_with_rebalanced_descendants(&self, source: &[u8]) -> Vec<String> {
+        let mut keys = indices_of::<{ LENGTH }, { DEPTH }>(source).collect::<Vec<_>>();
+
+        if let Some(base) = keys.iter().find(|key| key.len() == LENGTH).cloned() {
+            let folded = fold(source);
+            if folded.starts_with(&base) {
+                keys.extend((LENGTH + 1..=folded.len()).map(|end| folded[..end].to_owned()));
+            }
+        }
+
+        keys
+    }
+
+    /// Search for the index files that could contain a match within `max_distance`
+    /// edits of `query`.
+    ///
+    /// A near-variant of `query` may fall into a different bucket than `query`
+    /// itself, since bucketing only looks at a string's literal prefix; expanding the
+    /// query by [`expand_by_edit_distance`] before bucketing covers those buckets too.
+    fn index_files_for_edit_distance(
+        &self,
+        query: &str,
+        max_distance: usize,
+    ) -> Vec<IndexFile<MAX_BUFFER>> {
+        let keys = expand_by_edit_distance(query, max_distance)
+            .into_iter()
+            .flat_map(|candidate| self.keys_with_rebalanced_descendants(candidate.as_bytes()))
+            .collect::<hashbrown::HashSet<_>>();
+
+        self.index_files_for_keys(keys.into_iter())
+    }
+
+    /// Search for the index files that could contain a wildcard query's matches.
+    ///
+    /// The bucketing scheme in [`indices_of`] only ever looks at the literal
+    /// characters at the very start of a string, so the literal, wildcard-free prefix
+    /// of `query` (everything before the first `*` or `?`) yields the same candidate
+    /// buckets a real match would. If that prefix is empty, or too short to narrow
+    /// anything down, every index file is scanned instead.
+    fn index_files_for_wildcard(&self, query: &str) -> Vec<IndexFile<MAX_BUFFER>> {
+        let prefix = query.split(['*', '?']).next().unwrap_or_default();
+
+        let candidates = if prefix.is_empty() {
+            Vec::new()
+        } else {
+            self.index_files_for_keys(self.keys_with_rebalanced_descendants(prefix.as_bytes()).into_iter())
+        };
+
+        if candidates.is_empty() {
+            self.all_index_files()
+        } else {
+            candidates
+        }
+    }
+
+    /// Build the [`IndexFile`]s for a set of keys, skipping any that do not (yet)
+    /// exist on disk.
+    fn index_files_for_keys(&self, keys: impl Iterator<Item = String>) -> Vec<IndexFile<MAX_BUFFER>> {
+        keys.map(|key| {
+            // We could cache the index files, but that would create all sorts of race conditions.
+            // Instead, we'll just create them on the fly.
+            // Since we are just searching for the index, performance should not be a concern.
+            (
+                key.clone(),
+                path_for_key(&key, &self.dir).and_then(IndexFile::<{ MAX_BUFFER }>::from_path),
+            )
+        })
+        .filter_map(|(key, result)| match result {
+            Ok(index) => Some(index),
+            Err(error) => {
+                crate::debug!(
+                    target: LOG_TARGET,
+                    "No index for {key:?}, error: {error:?}",
+                    error = error
+                );
+                None
+            }
+        })
+        .collect()
+    }
+
+    /// Every index file currently persisted for this collection, regardless of key;
+    /// the fallback used when a wildcard query's literal prefix cannot narrow down
+    /// the search.
+    fn all_index_files(&self) -> Vec<IndexFile<MAX_BUFFER>> {
+        FileStorage::new(&self.dir)
+            .list_keys()
+            .map(|keys| self.index_files_for_keys(keys.into_iter()))
+            .unwrap_or_else(|error| {
+                crate::debug!(
+                    target: LOG_TARGET,
+                    "Failed to list index files in {dir:?}: {error:?}",
+                    dir = self.dir,
+                    error = error
+                );
+                Vec::new()
             })
-            .collect()
     }
 
     /// Search for a query in the whole index collection.
+    ///
+    /// `max_results`, if provided, stops scanning once that many lines have been
+    /// found, rather than always exhausting every candidate index file - useful for a
+    /// broad query (e.g. a common substring) where the caller only wants a handful of
+    /// examples. A search that hits the cap is treated as truncated, the same as a
+    /// search that ran out of time, and is never written to the LRU cache.
     pub fn find_lines_containing(
         &self,
         query: &str,
         search_style: SearchStyle,
+        max_results: Option<usize>,
     ) -> IndexCollectionReturn {
+        // A tiny planner: if a pre-normalised variant of the collection was built and
+        // attached for this exact search style, route to it instead of scanning
+        // (and re-transforming) every candidate file in the primary collection.
+        // The case-folded sidecar was built with plain ASCII lowercasing (see
+        // `CaseFoldedIndex::add`), so it can only correctly answer a non-Unicode
+        // query; a Unicode-aware query falls through to the generic scan below.
+        #[cfg(feature = "case_folded_index")]
+        if search_style == (SearchStyle::CaseInsensitive { unicode: false }) {
+            if let Some(case_folded_index) = &self.case_folded_index {
+                return case_folded_index
+                    .find_lines_containing_case_insensitively(query, max_results)
+                    .into();
+            }
+        }
+
+        #[cfg(feature = "fuzzed_index")]
+        if let SearchStyle::Fuzzy { keyboard_adjacent: false } = search_style {
+            if let Some(fuzzed_index) = &self.fuzzed_index {
+                return fuzzed_index.find_lines_containing_fuzzily(query, max_results).into();
+            }
+        }
+
+        #[cfg(feature = "phonetic_index")]
+        if search_style == SearchStyle::Phonetic {
+            if let Some(phonetic_index) = &self.phonetic_index {
+                return phonetic_index.find_lines_sounding_like(query, max_results).into();
+            }
+        }
+
+        self.search(query, search_style, None, None, max_results, None).lines
+    }
+
+    /// Search for a query in the whole index collection, aborting any index files not
+    /// yet scanned once `timeout` elapses.
+    ///
+    /// Whatever lines had already been found in the index files scanned before the
+    /// deadline are returned, together with `truncated: true`, rather than an error;
+    /// a caller that wants a hard failure on timeout should treat `truncated` as one.
+    /// Pass `None` to search without a deadline, equivalent to
+    /// [`Self::find_lines_containing`].
+    ///
+    /// A truncated result is never written to the LRU cache, since a partial result
+    /// would otherwise be handed back to a later, unbounded query for the same
+    /// `query`.
+    pub fn find_lines_containing_with_timeout(
+        &self,
+        query: &str,
+        search_style: SearchStyle,
+        timeout: Option<Duration>,
+    ) -> TimedSearchResult {
+        self.search(
+            query,
+            search_style,
+            timeout.map(|timeout| Instant::now() + timeout),
+            None,
+            None,
+            None,
+        )
+    }
+
+    /// Search for a query in the whole index collection, aborting any index files not
+    /// yet scanned as soon as `cancellation` is cancelled.
+    ///
+    /// This is the mechanism a long-lived front end (e.g. `server-grpc`'s streaming
+    /// `Search` RPC) should use to give up on a search whose caller has gone away,
+    /// rather than paying for a scan nobody is waiting on any more. As with
+    /// [`Self::find_lines_containing_with_timeout`], whatever lines had already been
+    /// found are returned together with `truncated: true`, and a truncated result is
+    /// never written to the LRU cache.
+    pub fn find_lines_containing_with_cancellation(
+        &self,
+        query: &str,
+        search_style: SearchStyle,
+        cancellation: CancellationToken,
+    ) -> TimedSearchResult {
+        self.search(query, search_style, None, Some(cancellation), None, None)
+    }
+
+    /// Search for a query in the whole index collection, capping how many lines any
+    /// single index file may contribute.
+    ///
+    /// A hot key (e.g. `"123"`) can otherwise dominate a broad query's results if one
+    /// index file alone holds far more matches than any other; `max_results_per_file`
+    /// stops each file's scan once it has contributed that many lines, and
+    /// [`TimedSearchResult::truncated_files`] reports which index file keys were
+    /// capped, so a caller can decide whether to go back and page into one of them
+    /// specifically. As with the other bounded searches, a result that capped any
+    /// file is treated as truncated, and is never written to the LRU cache.
+    pub fn find_lines_containing_with_per_file_limit(
+        &self,
+        query: &str,
+        search_style: SearchStyle,
+        max_results_per_file: Option<usize>,
+    ) -> TimedSearchResult {
+        self.search(query, search_style, None, None, None, max_results_per_file)
+    }
+
+    /// Search for a query in the whole index collection, then keep only the lines
+    /// whose email field matches `domain` (see [`crate::search::line_matches_domain`]).
+    ///
+    /// Intended for a collection built with `--format combo --combo-keep-email`,
+    /// where a caller wants results restricted to a single victim domain (e.g.
+    /// `"example.com"`) rather than every line the query itself matched.
+    /// `combo_delimiter` must match the delimiter the collection was indexed with. A
+    /// line from a plain index, or a combo line whose email field was not kept, never
+    /// matches and so is filtered out.
+    pub fn find_lines_containing_with_domain_filter(
+        &self,
+        query: &str,
+        search_style: SearchStyle,
+        max_results: Option<usize>,
+        combo_delimiter: char,
+        domain: &str,
+    ) -> Vec<String> {
+        crate::search::filter_lines_by_domain(
+            self.find_lines_containing(query, search_style, max_results).iter(),
+            combo_delimiter,
+            domain,
+        )
+    }
+
+    /// Shared implementation behind [`Self::find_lines_containing`],
+    /// [`Self::find_lines_containing_with_timeout`],
+    /// [`Self::find_lines_containing_with_cancellation`] and
+    /// [`Self::find_lines_containing_with_per_file_limit`].
+    fn search(
+        &self,
+        query: &str,
+        search_style: SearchStyle,
+        deadline: Option<Instant>,
+        cancellation: Option<CancellationToken>,
+        max_results: Option<usize>,
+        max_results_per_file: Option<usize>,
+    ) -> TimedSearchResult {
+        let _span = tracing::info_span!(
+            "query",
+            query,
+            search_style = ?search_style,
+        )
+        .entered();
+
+        if let Err(err) = self.validate_manifest() {
+            crate::error!(
+                target: LOG_TARGET,
+                "Index manifest validation failed for {dir:?}: {err}. The index may have \
+                 been built with different parameters; results may be empty or incorrect.",
+                dir = self.dir,
+                err = err,
+            );
+        }
+
         #[cfg(feature = "lru")]
-        {
+        if self.cache_enabled() {
             if let Some(cache_hit) = self
                 .cache
                 .write()
@@ -100,7 +421,12 @@ impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
                     query = query,
                     count = cache_hit.len()
                 );
-                return cache_hit;
+                self.cache_hits.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                return TimedSearchResult {
+                    lines: cache_hit,
+                    truncated: false,
+                    truncated_files: Vec::new(),
+                };
             }
 
             crate::debug!(
@@ -108,7 +434,66 @@ impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
                 "Cache miss for {query:?} in the index collection.",
                 query = query
             );
+            self.cache_misses.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+
+        // A plain, unbounded search has no deadline, cancellation or result cap for
+        // the singleflight registry to accidentally leak between callers, so it is
+        // the only case coalesced: if ten clients are all waiting on the same
+        // `(query, search_style)` right now, only one of them actually scans.
+        // Timeout-, cancellation-, max_results- and max_results_per_file-bounded
+        // searches always run uncoalesced, so one caller's limits can never affect
+        // another caller's result.
+        if deadline.is_none()
+            && cancellation.is_none()
+            && max_results.is_none()
+            && max_results_per_file.is_none()
+        {
+            self.search_singleflight.run((query.to_owned(), search_style), || {
+                self.run_search_and_cache(query, search_style, None, None, None, None)
+            })
+        } else {
+            self.run_search_and_cache(
+                query,
+                search_style,
+                deadline,
+                cancellation,
+                max_results,
+                max_results_per_file,
+            )
         }
+    }
+
+    /// Scan every index file matching `query`/`search_style` and, unless the search
+    /// was truncated, write the result into the LRU cache.
+    ///
+    /// Factored out of [`Self::search`] so that a plain, unbounded search can be
+    /// routed through [`Self::search_singleflight`] while timeout-, cancellation-,
+    /// max_results- and max_results_per_file-bounded searches call this directly,
+    /// uncoalesced.
+    fn run_search_and_cache(
+        &self,
+        query: &str,
+        search_style: SearchStyle,
+        deadline: Option<Instant>,
+        cancellation: Option<CancellationToken>,
+        max_results: Option<usize>,
+        max_results_per_file: Option<usize>,
+    ) -> TimedSearchResult {
+        // Queue behind any other search already using up the configured concurrency
+        // limit, rather than letting an unbounded burst of fuzzy queries starve
+        // indexing or other searches by fanning out across rayon all at once. Held
+        // for the rest of this function, so the permit is released once this search's
+        // results are in hand.
+        let search_semaphore = self
+            .search_semaphore
+            .read()
+            .expect(
+                "Failed to acquire read lock on search semaphore; search semaphore might be \
+                 poisoned.",
+            )
+            .clone();
+        let _permit = search_semaphore.as_ref().map(|semaphore| semaphore.acquire());
 
         crate::debug!(
             target: LOG_TARGET,
@@ -116,50 +501,121 @@ impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
             query = query
         );
 
-        let index_files = self.index_files_for(query);
+        let index_files = self.index_files_for(query, search_style);
 
-        let chunks_count = usize::max(1, usize::min(index_files.len(), rayon::max_num_threads()));
+        // Only used when no dedicated pool has been configured via
+        // `set_search_threads`, in which case rayon's default global pool applies.
+        let default_num_threads = rayon::max_num_threads();
 
-        let results: IndexCollectionReturn = index_files
-            .par_chunks(chunks_count)
-            .map(|index| {
-                index.iter().try_fold(HashSet::new(), |mut acc, index| {
-                    crate::debug!(
-                        target: LOG_TARGET,
-                        "Searching for {query:?} in {index:?}",
-                        query = query,
-                        index = index.key
-                    );
+        let truncated = AtomicBool::new(false);
+        // Tracks how many lines have been found across every chunk so far, so that
+        // once `max_results` is reached, chunks still working stop scanning further
+        // index files rather than exhausting every candidate.
+        let found_count = AtomicUsize::new(0);
+        // Keys of the index files whose contribution was cut short by
+        // `max_results_per_file`; reported back via `TimedSearchResult::truncated_files`.
+        let truncated_files = Mutex::new(Vec::new());
 
-                    let acc_len = acc.len();
-
-                    index
-                        .find_lines_containing(&[query], search_style)?
-                        .filter_map(
-                            // We are only interested in the lines that are okay.
-                            |line| line.ok(),
-                        )
-                        .for_each(|line| {
-                            acc.insert(line);
-                        });
-
-                    crate::debug!(
-                        target: LOG_TARGET,
-                        "Found {count} lines for {query:?} in {index:?}.",
-                        count = acc.len() - acc_len,
-                        query = query,
-                        index = index.key
-                    );
+        let run_search = |num_threads: usize| -> IndexCollectionReturn {
+            let chunks_count = usize::max(1, usize::min(index_files.len(), num_threads));
+
+            index_files
+                .par_chunks(chunks_count)
+                .map(|index| {
+                    index.iter().try_fold(HashSet::new(), |mut acc, index| {
+                        if deadline.is_some_and(|deadline| Instant::now() >= deadline)
+                            || cancellation.as_ref().is_some_and(CancellationToken::is_cancelled)
+                            || max_results
+                                .is_some_and(|max_results| found_count.load(Ordering::Relaxed) >= max_results)
+                        {
+                            // Time is up, the caller gave up on us, or we already have
+                            // enough lines; keep whatever this chunk has already found
+                            // rather than discarding it, and flag the overall result
+                            // as truncated instead of erroring out.
+                            truncated.store(true, Ordering::Relaxed);
+                            return Ok(acc);
+                        }
+
+                        let _span = tracing::debug_span!("index_file", index = index.key).entered();
+
+                        crate::debug!(
+                            target: LOG_TARGET,
+                            "Searching for {query:?} in {index:?}",
+                            query = query,
+                            index = index.key
+                        );
+
+                        let acc_len = acc.len();
+                        let remaining_overall =
+                            max_results.map(|max_results| max_results.saturating_sub(found_count.load(Ordering::Relaxed)));
+                        let remaining = match (remaining_overall, max_results_per_file) {
+                            (Some(overall), Some(per_file)) => Some(overall.min(per_file)),
+                            (Some(overall), None) => Some(overall),
+                            (None, Some(per_file)) => Some(per_file),
+                            (None, None) => None,
+                        };
+
+                        // Counted separately from the growth of `acc`, since a file
+                        // whose lines all happen to already be present (e.g. the same
+                        // line indexed under more than one key) would otherwise look
+                        // like it contributed nothing, even though its own scan did
+                        // hit `max_results_per_file`.
+                        let mut scanned_in_file = 0usize;
+
+                        index
+                            .find_lines_containing(&[query], search_style, cancellation.as_ref(), remaining)?
+                            .filter_map(
+                                // We are only interested in the lines that are okay.
+                                |line| line.ok(),
+                            )
+                            .for_each(|line| {
+                                scanned_in_file += 1;
+                                acc.insert(line);
+                            });
 
-                    Ok(acc)
+                        found_count.fetch_add(acc.len() - acc_len, Ordering::Relaxed);
+
+                        if max_results_per_file.is_some_and(|per_file| scanned_in_file >= per_file) {
+                            // This file alone hit its cap; it may hold further matches
+                            // that were never scanned, so flag it and the overall result
+                            // as truncated, the same as a deadline or cancellation would.
+                            truncated.store(true, Ordering::Relaxed);
+                            truncated_files
+                                .lock()
+                                .expect("Failed to acquire lock on truncated files; should be uncontended.")
+                                .push(index.key.clone());
+                        }
+
+                        crate::debug!(
+                            target: LOG_TARGET,
+                            "Found {count} lines for {query:?} in {index:?}.",
+                            count = scanned_in_file,
+                            query = query,
+                            index = index.key
+                        );
+
+                        Ok(acc)
+                    })
                 })
-            })
-            .filter_map(|result: io::Result<HashSet<String>>| result.ok())
-            .reduce(HashSet::new, |acc, set| acc.union(&set).cloned().collect())
-            .into(); // Convert to an Arc.
+                .filter_map(|result: io::Result<HashSet<String>>| result.ok())
+                .reduce(HashSet::new, |acc, set| acc.union(&set).cloned().collect())
+                .into() // Convert to an Arc.
+        };
 
-        #[cfg(feature = "lru")]
+        let results: IndexCollectionReturn = match self
+            .search_pool
+            .read()
+            .expect("Failed to acquire read lock on search pool; search pool might be poisoned.")
+            .as_ref()
         {
+            Some(pool) => pool.install(|| run_search(pool.current_num_threads())),
+            None => run_search(default_num_threads),
+        };
+
+        let truncated = truncated.load(Ordering::Relaxed);
+
+        #[cfg(feature = "lru")]
+        if self.cache_enabled() && !truncated {
             crate::debug!(
                 target: LOG_TARGET,
                 "Caching the {count} results found for key {query:?}.",
@@ -169,7 +625,14 @@ impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
             self.cache
                 .write()
                 .map(|mut cache| {
-                    cache.put(query.to_owned(), Arc::clone(&results));
+                    // `push` (rather than `put`) tells us whether an entry was evicted to
+                    // make room; if the evicted key differs from the one we just inserted,
+                    // it was pushed out due to capacity rather than merely replaced.
+                    if let Some((evicted_key, _)) = cache.push(query.to_owned(), Arc::clone(&results)) {
+                        if evicted_key != query {
+                            self.cache_evictions.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        }
+                    }
                 })
                 .unwrap_or_else(
                     // A cache is just a cache; if it's poisoned, we'll just reset it.
@@ -179,16 +642,116 @@ impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
                 );
         }
 
-        results
+        let truncated_files = truncated_files
+            .into_inner()
+            .expect("Failed to acquire lock on truncated files; should be uncontended.");
+
+        TimedSearchResult { lines: results, truncated, truncated_files }
+    }
+
+    /// Search for a query in the whole index collection, ranked by relevance.
+    ///
+    /// Unlike [`Self::find_lines_containing`], which returns an unordered
+    /// [`IndexCollectionReturn`], this scores every line against `query` via
+    /// [`score_line`] and returns them sorted with the closest matches first: an
+    /// exact match, then a prefix match, then any other substring match, then the
+    /// remainder ordered by edit distance. Useful when a caller only wants to look at
+    /// the first handful of a broad query's results and cares which ones are the
+    /// closest match, rather than merely which were found first.
+    pub fn find_lines_containing_ranked(
+        &self,
+        query: &str,
+        search_style: SearchStyle,
+        max_results: Option<usize>,
+    ) -> Vec<ScoredLine> {
+        let mut scored: Vec<ScoredLine> = self
+            .find_lines_containing(query, search_style, max_results)
+            .iter()
+            .map(|line| ScoredLine { line: line.clone(), score: score_line(query, line) })
+            .collect();
+
+        scored.sort_by_key(|scored| scored.score);
+        scored
+    }
+
+    /// Rank every line in the collection by trigram overlap with `query`, and
+    /// return the `top_k` most similar.
+    ///
+    /// Unlike [`Self::find_lines_containing_ranked`], which only scores lines an
+    /// existing [`SearchStyle`] search already found, this scans every line in the
+    /// collection: two variants of the same password (`"passw0rd!"` vs
+    /// `"P4ssword"`) usually share no literal prefix or edit-distance neighbourhood
+    /// for a bucketed search to narrow by, but still overlap heavily in trigrams.
+    /// Useful for finding what other leaked passwords are close variants of a
+    /// given one.
+    pub fn find_lines_similar_to(&self, query: &str, top_k: usize) -> io::Result<Vec<TrigramMatch>> {
+        let query_trigrams = trigrams(query);
+
+        let mut seen = HashSet::new();
+        let mut scored = Vec::new();
+
+        for line in self.iter_lines()? {
+            let line = String::from_utf8_lossy(&line?).into_owned();
+
+            if !seen.insert(line.clone()) {
+                continue;
+            }
+
+            let similarity = trigram_similarity_from_sets(&query_trigrams, &trigrams(&line));
+            scored.push(TrigramMatch { line, similarity });
+        }
+
+        scored.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+        scored.truncate(top_k);
+
+        Ok(scored)
+    }
+
+    /// Search for a query in the whole index collection, lazily.
+    ///
+    /// Unlike [`Self::find_lines_containing`], which scans every matching index file
+    /// up front and collects the results into a `HashSet`, this streams lines out one
+    /// at a time as each index file is scanned, so a broad query (e.g. `"123"`) does
+    /// not have to hold every match in memory at once. Index files that fail to open
+    /// are skipped, and duplicate lines across index files are not deduplicated.
+    ///
+    /// This does not participate in the LRU cache, since there is nothing to cache
+    /// until the iterator has been fully drained.
+    pub fn find_lines_containing_iter<'query>(
+        &self,
+        query: &'query str,
+        search_style: SearchStyle,
+    ) -> impl Iterator<Item = String> + 'query {
+        self.index_files_for(query, search_style)
+            .into_iter()
+            .flat_map(move |index| {
+                index
+                    .find_lines_containing(&[query], search_style, None, None)
+                    .map_err(|error| {
+                        crate::debug!(
+                            target: LOG_TARGET,
+                            "Failed to search {index:?}, error: {error:?}",
+                            index = index.key,
+                            error = error,
+                        );
+                    })
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Result::ok)
+            })
     }
 
     /// Search for a query in the whole index collection.
     ///
     /// This method will return a paginated list of results; the offset and limit
-    /// parameters are used to determine which results to return.
+    /// parameters are used to determine which results to return. `order` is applied
+    /// before paginating, so that repeated calls with the same offset return the same
+    /// lines rather than whatever order the underlying `HashSet` happened to iterate
+    /// in that time - pass [`ResultOrder::Unsorted`] to opt back out of that
+    /// determinism if it isn't needed.
     ///
-    /// Contrary to `find_lines_containing`, this method will return an owned
-    /// `HashSet` of strings, instead of an `Arc`, since the results won't be reused.
+    /// Contrary to `find_lines_containing`, this method will return an owned `Vec` of
+    /// strings, instead of an `Arc`, since the results won't be reused.
     ///
     /// # Note
     ///
@@ -199,15 +762,48 @@ impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
         &self,
         query: &str,
         search_style: SearchStyle,
+        order: ResultOrder,
         offset: usize,
         limit: usize,
-    ) -> HashSet<String> {
-        self.find_lines_containing(query, search_style)
+    ) -> Vec<String> {
+        let mut lines: Vec<String> =
+            self.find_lines_containing(query, search_style, None).iter().cloned().collect();
+        order.sort(query, &mut lines);
+
+        lines.into_iter().skip(offset).take(limit).collect()
+    }
+
+    /// Search for a query in the whole index collection, returning a stable page of
+    /// results.
+    ///
+    /// Unlike [`Self::find_lines_containing_paginated`] prior to `order` being added,
+    /// this always sorted the results before paginating, so that walking pages via
+    /// the returned [`Page::next_cursor`] yields each result exactly once; passing
+    /// [`ResultOrder::Unsorted`] here gives up that guarantee, the same as it does for
+    /// [`Self::find_lines_containing_paginated`].
+    pub fn find_lines_containing_page(
+        &self,
+        query: &str,
+        search_style: SearchStyle,
+        order: ResultOrder,
+        cursor: Cursor,
+        limit: usize,
+    ) -> Page {
+        let mut sorted: Vec<String> = self
+            .find_lines_containing(query, search_style, None)
             .iter()
-            .skip(offset)
-            .take(limit)
             .cloned()
-            .collect()
+            .collect();
+        order.sort(query, &mut sorted);
+
+        let offset = cursor.into();
+        let items: Vec<String> = sorted.iter().skip(offset).take(limit).cloned().collect();
+        let next_offset = offset + items.len();
+
+        Page {
+            items,
+            next_cursor: (next_offset < sorted.len()).then(|| next_offset.into()),
+        }
     }
 }
 
@@ -231,7 +827,7 @@ mod tests {
                     );
                 }
                 let index = IndexCollection::<3, 1>::new(path);
-                let actual = index.index_files_for($query).into_iter().map(
+                let actual = index.index_files_for($query, SearchStyle::Strict).into_iter().map(
                     |index| index.key.clone()
                 ).collect::<HashSet<_>>();
 
@@ -250,7 +846,7 @@ mod tests {
             fn $name() {
                 let path = path::PathBuf::from(TEST_MOCK_INDEX);
                 let index = IndexCollection::<$length, $depth>::new(path);
-                let actual = index.find_lines_containing($query, $search_style);
+                let actual = index.find_lines_containing($query, $search_style, None);
 
                 let expected = $expected
                     .into_iter()
@@ -300,4 +896,463 @@ mod tests {
             "thispassword",
         ]
     );
+
+    create_search_test!(
+        wildcard_search<3, 1>("password*", SearchStyle::Wildcard) == [
+            "password13",
+            "passwordz",
+            "password5",
+            "password75",
+            "password1992",
+            "password12",
+            "password",
+            "password1994",
+            "password1!",
+            "password2",
+            "password123",
+            "passwords",
+            "password4",
+            "password3",
+            "password.",
+            "password11",
+            "password7",
+            "password!",
+            "password1",
+        ]
+    );
+
+    #[test]
+    fn wildcard_search_without_a_narrowing_prefix_falls_back_to_a_full_scan() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        let actual = index.find_lines_containing("*password*", SearchStyle::Wildcard, None);
+        assert!(actual.contains("thisispassword"));
+        assert!(actual.contains("$password$"));
+    }
+
+    create_search_test!(
+        exact_search<3, 1>("password", SearchStyle::Exact) == ["password"]
+    );
+
+    #[test]
+    fn edit_distance_search_expands_across_buckets() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        // "xassword" is a single substitution away from "password", which would fall
+        // into the "xas" bucket rather than "pas" - it should still be found by
+        // expanding the candidate buckets before scanning.
+        let actual =
+            index.find_lines_containing("xassword", SearchStyle::EditDistance { max_distance: 1 }, None);
+        assert!(actual.contains("password"));
+    }
+
+    #[test]
+    #[cfg(not(feature = "skip_index_write"))]
+    fn unicode_case_insensitive_search_matches_differently_cased_accents() {
+        use crate::config::TEST_DIR;
+        use std::fs;
+
+        let dir = path::PathBuf::from(TEST_DIR).join("unicode_case_insensitive_search_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let index = IndexCollection::<3, 1>::new(dir.clone());
+        index.add("PELÉ".as_bytes()).expect("Failed to add line.");
+        index.finalize().expect("Failed to finalize collection.");
+
+        let index = IndexCollection::<3, 1>::new(dir.clone());
+
+        // Plain ASCII case-insensitivity does not fold the accented letter.
+        let ascii_only =
+            index.find_lines_containing("pelé", SearchStyle::CaseInsensitive { unicode: false }, None);
+        assert!(!ascii_only.contains("PELÉ"));
+
+        let unicode_aware =
+            index.find_lines_containing("pelé", SearchStyle::CaseInsensitive { unicode: true }, None);
+        assert!(unicode_aware.contains("PELÉ"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn paginated_search_is_stable_and_covers_all_results() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        let mut collected = Vec::new();
+        let mut cursor = Cursor::START;
+        loop {
+            let page = index.find_lines_containing_page(
+                "password",
+                SearchStyle::Strict,
+                ResultOrder::Lexicographic,
+                cursor,
+                5,
+            );
+            collected.extend(page.items);
+
+            match page.next_cursor {
+                Some(next) => cursor = next,
+                None => break,
+            }
+        }
+
+        let mut expected: Vec<String> = index
+            .find_lines_containing("password", SearchStyle::Strict, None)
+            .iter()
+            .cloned()
+            .collect();
+        expected.sort_unstable();
+
+        assert_eq!(collected, expected);
+    }
+
+    #[test]
+    fn timed_search_with_no_timeout_matches_an_untimed_search() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        let timed = index.find_lines_containing_with_timeout("password", SearchStyle::Strict, None);
+        assert!(!timed.truncated);
+
+        let untimed = index.find_lines_containing("password", SearchStyle::Strict, None);
+
+        #[cfg(feature = "lru")]
+        assert_eq!(timed.lines.as_ref(), untimed.as_ref());
+
+        #[cfg(not(feature = "lru"))]
+        assert_eq!(timed.lines, untimed);
+    }
+
+    #[test]
+    fn timed_search_with_an_elapsed_timeout_returns_a_truncated_result() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        let timed = index.find_lines_containing_with_timeout(
+            "password",
+            SearchStyle::Strict,
+            Some(Duration::ZERO),
+        );
+
+        assert!(timed.truncated);
+    }
+
+    #[test]
+    fn cancelled_search_returns_a_truncated_result() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+
+        let cancelled =
+            index.find_lines_containing_with_cancellation("password", SearchStyle::Strict, cancellation);
+
+        assert!(cancelled.truncated);
+    }
+
+    #[test]
+    fn an_uncancelled_search_matches_an_uncancelled_search() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        let cancellation = CancellationToken::new();
+        let cancelled = index.find_lines_containing_with_cancellation(
+            "password",
+            SearchStyle::Strict,
+            cancellation,
+        );
+        assert!(!cancelled.truncated);
+
+        let uncancelled = index.find_lines_containing("password", SearchStyle::Strict, None);
+
+        #[cfg(feature = "lru")]
+        assert_eq!(cancelled.lines.as_ref(), uncancelled.as_ref());
+
+        #[cfg(not(feature = "lru"))]
+        assert_eq!(cancelled.lines, uncancelled);
+    }
+
+    #[test]
+    fn streaming_search_yields_the_same_lines_as_the_collected_search() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        let streamed: HashSet<String> = index
+            .find_lines_containing_iter("password", SearchStyle::Strict)
+            .collect();
+
+        let collected = index.find_lines_containing("password", SearchStyle::Strict, None);
+
+        #[cfg(feature = "lru")]
+        assert_eq!(&streamed, collected.as_ref());
+
+        #[cfg(not(feature = "lru"))]
+        assert_eq!(streamed, collected);
+    }
+
+    #[test]
+    fn paginated_lexicographic_search_returns_the_same_page_on_repeated_calls() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        let first = index.find_lines_containing_paginated(
+            "password",
+            SearchStyle::Strict,
+            ResultOrder::Lexicographic,
+            0,
+            5,
+        );
+        let second = index.find_lines_containing_paginated(
+            "password",
+            SearchStyle::Strict,
+            ResultOrder::Lexicographic,
+            0,
+            5,
+        );
+
+        assert_eq!(first, second);
+        assert!(first.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn paginated_search_orders_by_length_when_asked() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        let by_length = index.find_lines_containing_paginated(
+            "password",
+            SearchStyle::Strict,
+            ResultOrder::Length,
+            0,
+            5,
+        );
+
+        assert!(by_length.windows(2).all(|pair| pair[0].len() <= pair[1].len()));
+    }
+
+    #[test]
+    fn paginated_search_orders_by_score_when_asked() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        let by_score = index.find_lines_containing_paginated(
+            "password",
+            SearchStyle::Strict,
+            ResultOrder::Score,
+            0,
+            5,
+        );
+
+        assert_eq!(by_score.first().map(String::as_str), Some("password"));
+    }
+
+    #[test]
+    fn cursor_round_trips_through_its_token() {
+        let cursor = Cursor::from(42);
+        assert_eq!(Cursor::decode(&cursor.encode()), Some(cursor));
+    }
+
+    #[test]
+    fn set_search_threads_configures_a_dedicated_pool_used_by_search() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+        assert_eq!(index.search_threads(), None);
+
+        index.set_search_threads(Some(2)).expect("Failed to configure search threads.");
+        assert_eq!(index.search_threads(), Some(2));
+
+        let actual = index.find_lines_containing("password", SearchStyle::Exact, None);
+        assert!(actual.contains("password"));
+
+        index.set_search_threads(None).expect("Failed to reset search threads.");
+        assert_eq!(index.search_threads(), None);
+    }
+
+    #[test]
+    fn set_search_concurrency_configures_a_semaphore_used_by_search() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+        assert_eq!(index.search_concurrency(), None);
+
+        index.set_search_concurrency(Some(2));
+        assert_eq!(index.search_concurrency(), Some(2));
+
+        let actual = index.find_lines_containing("password", SearchStyle::Exact, None);
+        assert!(actual.contains("password"));
+
+        index.set_search_concurrency(None);
+        assert_eq!(index.search_concurrency(), None);
+    }
+
+    #[test]
+    fn concurrent_identical_searches_are_coalesced_and_agree() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = Arc::new(IndexCollection::<3, 1>::new(path));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let index = Arc::clone(&index);
+                std::thread::spawn(move || index.find_lines_containing("password", SearchStyle::Exact, None))
+            })
+            .collect();
+
+        let mut results = handles.into_iter().map(|handle| handle.join().expect("Failed to join a search thread."));
+
+        let first = results.next().expect("Spawned at least one thread.");
+        assert!(first.contains("password"));
+        assert!(results.all(|result| result == first));
+    }
+
+    #[test]
+    fn max_results_stops_short_of_scanning_every_candidate() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        // A capped search is never cached, so run it before the uncapped search below
+        // to avoid the latter's full result being served back for the capped query.
+        let limited = index.find_lines_containing("password", SearchStyle::Strict, Some(1));
+        let unlimited = index.find_lines_containing("password", SearchStyle::Strict, None);
+
+        assert_eq!(limited.len(), 1);
+        assert!(unlimited.len() > 1);
+        assert!(limited.iter().all(|line| unlimited.contains(line)));
+    }
+
+    #[test]
+    fn max_results_beyond_the_true_count_matches_an_unlimited_search() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        let unlimited = index.find_lines_containing("password", SearchStyle::Strict, None);
+        let limited = index.find_lines_containing("password", SearchStyle::Strict, Some(1_000_000));
+
+        #[cfg(feature = "lru")]
+        assert_eq!(limited.as_ref(), unlimited.as_ref());
+
+        #[cfg(not(feature = "lru"))]
+        assert_eq!(limited, unlimited);
+    }
+
+    #[test]
+    fn per_file_limit_caps_each_files_contribution_and_reports_which_were_capped() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        // "password" maps to the "pas" and "wor" buckets, both of which hold more
+        // than one match, so capping each file to 1 result should truncate both -
+        // even though the two files index some of the same lines, so the combined,
+        // deduplicated result may still be shorter than the sum of each file's cap.
+        let limited = index.find_lines_containing_with_per_file_limit(
+            "password",
+            SearchStyle::Strict,
+            Some(1),
+        );
+
+        assert!(limited.truncated);
+        assert_eq!(limited.truncated_files.len(), 2);
+        assert!(limited.truncated_files.contains(&"pas".to_string()));
+        assert!(limited.truncated_files.contains(&"wor".to_string()));
+        assert!(!limited.lines.is_empty());
+
+        let unlimited = index.find_lines_containing("password", SearchStyle::Strict, None);
+        assert!(limited.lines.len() < unlimited.len());
+    }
+
+    #[test]
+    fn per_file_limit_beyond_the_true_count_matches_an_unlimited_search() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        let unlimited = index.find_lines_containing("password", SearchStyle::Strict, None);
+        let limited = index.find_lines_containing_with_per_file_limit(
+            "password",
+            SearchStyle::Strict,
+            Some(1_000_000),
+        );
+
+        assert!(!limited.truncated);
+        assert!(limited.truncated_files.is_empty());
+
+        #[cfg(feature = "lru")]
+        assert_eq!(limited.lines.as_ref(), unlimited.as_ref());
+
+        #[cfg(not(feature = "lru"))]
+        assert_eq!(limited.lines, unlimited);
+    }
+
+    #[test]
+    fn ranked_search_orders_the_exact_match_before_everything_else() {
+        use super::super::super::MatchScore;
+
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        let ranked = index.find_lines_containing_ranked("password", SearchStyle::Strict, None);
+
+        let unranked = index.find_lines_containing("password", SearchStyle::Strict, None);
+        assert_eq!(ranked.len(), unranked.len());
+
+        assert_eq!(ranked.first().expect("At least one result.").line, "password");
+        assert_eq!(ranked.first().expect("At least one result.").score, MatchScore::Exact);
+
+        assert!(ranked.windows(2).all(|pair| pair[0].score <= pair[1].score));
+    }
+
+    #[test]
+    fn find_lines_similar_to_ranks_the_closest_variants_first() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        let top = index.find_lines_similar_to("password", 3).expect("Failed to search.");
+
+        assert_eq!(top.len(), 3);
+        assert_eq!(top[0].line, "password");
+        assert_eq!(top[0].similarity, 1.0);
+        assert!(top.windows(2).all(|pair| pair[0].similarity >= pair[1].similarity));
+    }
+
+    #[test]
+    fn find_lines_similar_to_deduplicates_lines_shared_across_buckets() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        // "password" is stored under more than one bucket - it must still only be
+        // scored, and returned, once.
+        let top = index.find_lines_similar_to("password", 200).expect("Failed to search.");
+        let occurrences = top.iter().filter(|scored| scored.line == "password").count();
+
+        assert_eq!(occurrences, 1);
+    }
+
+    #[cfg(feature = "lru")]
+    #[test]
+    fn cache_stats_track_hits_and_misses() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+
+        index.find_lines_containing("password", SearchStyle::Strict, None);
+        index.find_lines_containing("password", SearchStyle::Strict, None);
+
+        let stats = index.cache_stats();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 1);
+    }
+
+    #[cfg(feature = "lru")]
+    #[test]
+    fn disabling_the_cache_prevents_hits() {
+        let path = path::PathBuf::from(TEST_MOCK_INDEX);
+        let index = IndexCollection::<3, 1>::new(path);
+        index.set_cache_enabled(false);
+
+        index.find_lines_containing("password", SearchStyle::Strict, None);
+        index.find_lines_containing("password", SearchStyle::Strict, None);
+
+        let stats = index.cache_stats();
+        assert_eq!(stats.hits, 0);
+        assert_eq!(stats.misses, 0);
+    }
 }
@@ -1,7 +1,10 @@
 use std::{io, path};
 
-use super::super::{LinesScanner, SearchStyle};
-use crate::{key_for_path, models::IndexFile};
+use super::super::{
+    CancellationToken, EditDistanceScanner, ExactScanner, LinesScanner, PhoneticScanner,
+    SearchStyle, WildcardScanner,
+};
+use crate::{key_for_path, models::IndexFile, models::LineOffsetTable, offsets_path_for_key};
 
 impl<const MAX_BUFFER: usize> IndexFile<MAX_BUFFER> {
     /// Create an [`IndexFile`] from an existing file.
@@ -41,6 +44,8 @@ impl<const MAX_BUFFER: usize> IndexFile<MAX_BUFFER> {
             #[cfg(feature = "deduplicate")]
             seen: Default::default(),
             buffer: Default::default(),
+            flush_count: Default::default(),
+            durability: Default::default(),
         })
     }
 
@@ -53,12 +58,53 @@ impl<const MAX_BUFFER: usize> IndexFile<MAX_BUFFER> {
     }
 
     /// Search for some keys in the index file.
+    ///
+    /// `cancellation` and `max_results`, if provided, are only honoured by the
+    /// [`LinesScanner`] used for [`SearchStyle::Strict`], [`SearchStyle::CaseInsensitive`]
+    /// and [`SearchStyle::Fuzzy`]; the wildcard, exact, edit-distance and phonetic
+    /// scanners run to completion regardless.
     pub fn find_lines_containing(
         &self,
         keys: &[&str],
         search_style: SearchStyle,
-    ) -> Result<LinesScanner<std::fs::File>, io::Error> {
-        LinesScanner::new(|| self.open_for_read(), keys, search_style)
+        cancellation: Option<&CancellationToken>,
+        max_results: Option<usize>,
+    ) -> Result<Box<dyn Iterator<Item = io::Result<String>>>, io::Error> {
+        if let SearchStyle::Wildcard = search_style {
+            return WildcardScanner::new(self.open_for_read()?, keys)
+                .map(|scanner| Box::new(scanner) as Box<dyn Iterator<Item = io::Result<String>>>);
+        }
+
+        if let SearchStyle::Exact = search_style {
+            return Ok(Box::new(ExactScanner::new(self.open_for_read()?, keys)));
+        }
+
+        if let SearchStyle::EditDistance { max_distance } = search_style {
+            return Ok(Box::new(EditDistanceScanner::new(
+                self.open_for_read()?,
+                keys,
+                max_distance,
+            )));
+        }
+
+        if let SearchStyle::Phonetic = search_style {
+            return Ok(Box::new(PhoneticScanner::new(self.open_for_read()?, keys)));
+        }
+
+        let offsets = offsets_path_for_key(&self.key, &self.dir)
+            .and_then(std::fs::File::open)
+            .and_then(LineOffsetTable::read)
+            .ok();
+
+        LinesScanner::new(
+            || self.open_for_read(),
+            keys,
+            search_style,
+            offsets,
+            cancellation.cloned(),
+            max_results,
+        )
+        .map(|scanner| Box::new(scanner) as Box<dyn Iterator<Item = io::Result<String>>>)
     }
 }
 
@@ -80,7 +126,7 @@ mod test {
                 let index = IndexFile::<{ MAX_INDEX_BUFFER_SIZE }>::from_path(&path)
                     .expect("The index file for 'pas' could not be found, or could not be read.");
                 let scanner = index
-                    .find_lines_containing($query, $search_style)
+                    .find_lines_containing($query, $search_style, None, None)
                     .expect("The scanner could not be created.");
                 let lines = scanner
                     .collect::<Result<HashSet<_>, _>>()
@@ -115,7 +161,7 @@ mod test {
     );
 
     create_search_test!(
-        case_insensitive_search(&["password"], SearchStyle::CaseInsensitive)
+        case_insensitive_search(&["password"], SearchStyle::CaseInsensitive { unicode: false })
             == [
                 "**password**",
                 "password1992",
@@ -138,7 +184,12 @@ mod test {
     );
 
     create_search_test!(
-        fuzzy_search(&["password"], SearchStyle::Fuzzy)
+        fuzzy_search(
+            &["password"],
+            SearchStyle::Fuzzy {
+                keyboard_adjacent: false
+            }
+        )
             == [
                 "0password0",
                 "**password**",
@@ -161,4 +212,80 @@ mod test {
                 "Password"
             ]
     );
+
+    create_search_test!(
+        wildcard_search(&["password*"], SearchStyle::Wildcard)
+            == [
+                "password",
+                "password1",
+                "password2",
+                "password123",
+                "passwordz",
+                "password75",
+                "password1994",
+                "password1992",
+                "password1!",
+                "password12",
+                "password3",
+            ]
+    );
+
+    create_search_test!(
+        wildcard_search_with_question_mark(&["passw?rd"], SearchStyle::Wildcard)
+            == ["password", "passw0rd"]
+    );
+
+    create_search_test!(
+        exact_search(&["password"], SearchStyle::Exact) == ["password"]
+    );
+
+    create_search_test!(
+        edit_distance_search(
+            &["password"],
+            SearchStyle::EditDistance { max_distance: 1 }
+        ) == [
+            "password",
+            "password1",
+            "password2",
+            "passwordz",
+            "1password",
+            "password3",
+            "Password",
+            "passw0rd",
+            "passwors",
+            "paseword",
+        ]
+    );
+
+    create_search_test!(
+        exact_search_is_case_sensitive(&["Password"], SearchStyle::Exact) == ["Password"]
+    );
+
+    create_search_test!(
+        fuzzy_keyboard_adjacent_search(
+            &["oassword"],
+            SearchStyle::Fuzzy {
+                keyboard_adjacent: true
+            }
+        ) == [
+            "0password0",
+            "**password**",
+            "password",
+            "password2",
+            "password123",
+            "password1!",
+            "passwors",
+            "password1994",
+            "password12",
+            "mypassword",
+            "1password",
+            "password1992",
+            "passwordz",
+            "PASSWORD",
+            "password75",
+            "password3",
+            "password1",
+            "Password"
+        ]
+    );
 }
@@ -1,2 +1,22 @@
 mod index_collection;
 mod index_file;
+
+#[cfg(feature = "reversed_index")]
+mod reversed_index;
+
+#[cfg(feature = "case_folded_index")]
+mod case_folded_index;
+
+#[cfg(feature = "fuzzed_index")]
+mod fuzzed_index;
+
+#[cfg(feature = "ngram_index")]
+mod ngram_index;
+
+#[cfg(feature = "phonetic_index")]
+mod phonetic_index;
+
+#[cfg(feature = "symspell_index")]
+mod symspell_index;
+
+pub(crate) use index_collection::IndexCollectionReturn;
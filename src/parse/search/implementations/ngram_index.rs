@@ -0,0 +1,53 @@
+//! Adding an anywhere-in-the-line search method to the n-gram index.
+//!
+
+use crate::models::{IndexCollectionResult, NgramIndex};
+
+use super::super::SearchStyle;
+
+impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
+    NgramIndex<LENGTH, DEPTH, MAX_BUFFER>
+{
+    /// Find every line containing `query` anywhere, using this n-gram index to
+    /// narrow the candidate files the same way an ordinary prefix query already
+    /// narrows [`crate::models::IndexCollection::find_lines_containing`]'s.
+    ///
+    /// This only reliably finds a match when the index was built with a `stride`
+    /// of `1`: with every position bucketed, `query`'s own head - the same key a
+    /// [`SearchStyle::Strict`] search would derive for it - is guaranteed to be
+    /// one of the keys the matching line was stored under. A larger `stride` only
+    /// catches a match whose position happens to land on a bucketed one; that
+    /// missed recall is the trade-off a larger stride makes for a smaller index.
+    pub fn find_lines_containing_anywhere(&self, query: &str, max_results: Option<usize>) -> IndexCollectionResult {
+        self.collection
+            .find_lines_containing(query, SearchStyle::Strict, max_results)
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+    use std::{fs, path};
+
+    #[test]
+    fn find_lines_containing_anywhere_finds_a_mid_line_match_with_stride_1() {
+        let dir = path::PathBuf::from(TEST_DIR).join("ngram_index_search_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let index = NgramIndex::<3, 1>::new(dir.clone(), 1);
+        index.add(b"letmein2024").expect("Failed to add line.");
+        index.add(b"hello").expect("Failed to add line.");
+        index.finalize().expect("Failed to finalize ngram index.");
+
+        let index = NgramIndex::<3, 1>::open_read_only(dir.clone(), 1);
+        let results: Vec<String> = index.find_lines_containing_anywhere("mein20", None).into_iter().collect();
+
+        assert_eq!(results, vec!["letmein2024".to_string()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
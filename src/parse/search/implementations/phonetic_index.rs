@@ -0,0 +1,60 @@
+//! Adding a phonetic search method to the phonetic index.
+//!
+
+use crate::models::{split_phonetic_line, IndexCollectionResult, PhoneticIndex};
+use crate::string::soundex;
+
+use super::super::SearchStyle;
+
+impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
+    PhoneticIndex<LENGTH, DEPTH, MAX_BUFFER>
+{
+    /// Find every line that sounds like `query`, using this pre-encoded index to
+    /// avoid re-encoding every line in the collection on the fly the way
+    /// [`crate::models::IndexCollection::find_lines_containing`] with
+    /// [`SearchStyle::Phonetic`] would.
+    ///
+    /// A caller performing a phonetic search should route it here instead, once
+    /// [`PhoneticIndex::exists`] confirms this index was actually built alongside
+    /// the primary collection.
+    ///
+    /// `query` is encoded to its Soundex code and searched for as a
+    /// [`SearchStyle::Strict`] substring against this index's stored codes; each hit
+    /// is then resolved back to its original text.
+    pub fn find_lines_sounding_like(&self, query: &str, max_results: Option<usize>) -> IndexCollectionResult {
+        let code = soundex(query);
+
+        self.collection
+            .find_lines_containing(&code, SearchStyle::Strict, max_results)
+            .iter()
+            .filter_map(|line| split_phonetic_line(line).map(|(_code, original)| original.to_owned()))
+            .collect()
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+    use std::{fs, path};
+
+    #[test]
+    fn find_lines_sounding_like_finds_misspelled_variants() {
+        let dir = path::PathBuf::from(TEST_DIR).join("phonetic_index_search_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let index = PhoneticIndex::<3, 1>::new(dir.clone());
+        index.add(b"jhonny123").expect("Failed to add line.");
+        index.add(b"johnny123").expect("Failed to add line.");
+        index.add(b"hello").expect("Failed to add line.");
+        index.finalize().expect("Failed to finalize phonetic index.");
+
+        let index = PhoneticIndex::<3, 1>::open_read_only(dir.clone());
+        let mut results: Vec<String> = index.find_lines_sounding_like("johnny123", None).into_iter().collect();
+        results.sort();
+
+        assert_eq!(results, vec!["jhonny123".to_string(), "johnny123".to_string()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,66 @@
+//! Adding an anchored-suffix search method to the reversed index.
+//!
+
+use crate::models::{IndexCollectionResult, ReversedIndex};
+
+use super::super::SearchStyle;
+
+impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
+    ReversedIndex<LENGTH, DEPTH, MAX_BUFFER>
+{
+    /// Find every line ending with `suffix`, using this reversed index to narrow the
+    /// candidate files the same way an ordinary prefix query already narrows
+    /// [`crate::models::IndexCollection::find_lines_containing`]'s.
+    ///
+    /// A caller building a suffix-anchored wildcard query (e.g. `"*2024!"`) should
+    /// route it here instead of `find_lines_containing(..., SearchStyle::Wildcard,
+    /// ...)`, which has no literal prefix to narrow its own search by and falls back
+    /// to scanning every index file in the primary collection.
+    ///
+    /// `suffix` is reversed and searched for as a [`SearchStyle::Strict`] substring
+    /// against this index's own (reversed) lines, so the candidate files are the same
+    /// ones a prefix search for the reversed suffix would use; matches are then
+    /// reversed back and filtered down to those that actually end with `suffix`,
+    /// since a substring match's position within the reversed line is not itself
+    /// checked.
+    pub fn find_lines_ending_with(&self, suffix: &str, max_results: Option<usize>) -> IndexCollectionResult {
+        let reversed_suffix: String = suffix.chars().rev().collect();
+
+        self.collection
+            .find_lines_containing(&reversed_suffix, SearchStyle::Strict, max_results)
+            .iter()
+            .filter_map(|reversed_line| {
+                let line: String = reversed_line.chars().rev().collect();
+                line.ends_with(suffix).then_some(line)
+            })
+            .collect()
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+    use std::{fs, path};
+
+    #[test]
+    fn find_lines_ending_with_only_returns_lines_anchored_at_the_suffix() {
+        let dir = path::PathBuf::from(TEST_DIR).join("reversed_index_search_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let index = ReversedIndex::<3, 1>::new(dir.clone());
+        index.add(b"summer2024!").expect("Failed to add line.");
+        index.add(b"winter2024!").expect("Failed to add line.");
+        // Contains "2024!" but does not end with it - must not be returned.
+        index.add(b"2024!winter").expect("Failed to add line.");
+        index.finalize().expect("Failed to finalize reversed index.");
+
+        let index = ReversedIndex::<3, 1>::open_read_only(dir.clone());
+        let mut results: Vec<String> = index.find_lines_ending_with("2024!", None).into_iter().collect();
+        results.sort();
+
+        assert_eq!(results, vec!["summer2024!".to_string(), "winter2024!".to_string()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
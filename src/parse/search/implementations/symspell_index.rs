@@ -0,0 +1,88 @@
+//! Adding a bounded edit-distance search method to the SymSpell index.
+//!
+
+use crate::models::{deletion_variants, IndexCollectionResult, SymSpellIndex};
+
+use super::super::bounded_levenshtein_distance;
+
+impl<const LENGTH: usize, const DEPTH: usize, const MAX_BUFFER: usize>
+    SymSpellIndex<LENGTH, DEPTH, MAX_BUFFER>
+{
+    /// Find every line within `max_distance` edits of `query`, using this
+    /// precomputed deletion dictionary to avoid
+    /// [`crate::search::expand_by_edit_distance`]'s per-query
+    /// deletion-and-substitution expansion followed by a scan of every candidate
+    /// index file.
+    ///
+    /// `max_distance` is capped to the distance this index was built with (see
+    /// [`SymSpellIndex::max_distance`]); a caller after a larger distance than that
+    /// should fall back to [`crate::models::IndexCollection::find_lines_containing`]
+    /// with [`crate::search::SearchStyle::EditDistance`] instead, since this index's
+    /// own deletion variants would not reach far enough to answer it correctly.
+    ///
+    /// `query`'s own deletion variants are looked up directly against the lines
+    /// bucketed under them; each hit is then re-verified with
+    /// [`crate::search::bounded_levenshtein_distance`], since two lines sharing a
+    /// deletion variant are not necessarily within `max_distance` of each other (nor,
+    /// necessarily, of `query`).
+    pub fn find_lines_within_edit_distance(
+        &self,
+        query: &str,
+        max_distance: usize,
+        max_results: Option<usize>,
+    ) -> IndexCollectionResult {
+        let max_distance = max_distance.min(self.max_distance());
+
+        let mut results = IndexCollectionResult::new();
+
+        'variants: for variant in deletion_variants(query, max_distance) {
+            let Ok(lines) = self.collection.iter_lines_for_key(&variant) else {
+                continue;
+            };
+
+            for line in lines {
+                let Ok(line) = line else { continue };
+                let line = String::from_utf8_lossy(&line).into_owned();
+
+                if bounded_levenshtein_distance(query, &line, max_distance).is_some()
+                    && results.insert(line)
+                    && max_results.is_some_and(|max_results| results.len() >= max_results)
+                {
+                    break 'variants;
+                }
+            }
+        }
+
+        results
+    }
+}
+
+#[cfg(all(test, not(feature = "skip_index_write")))]
+mod tests {
+    use super::*;
+    use crate::config::TEST_DIR;
+    use std::{fs, path};
+
+    #[test]
+    fn find_lines_within_edit_distance_finds_near_matches() {
+        let dir = path::PathBuf::from(TEST_DIR).join("symspell_index_search_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let index = SymSpellIndex::<3, 1>::new(dir.clone(), 2);
+        index.add(b"password").expect("Failed to add line.");
+        index.add(b"password1").expect("Failed to add line.");
+        index.add(b"hello").expect("Failed to add line.");
+        index.finalize().expect("Failed to finalize symspell index.");
+
+        let index = SymSpellIndex::<3, 1>::open_read_only(dir.clone(), 2);
+        let mut results: Vec<String> = index
+            .find_lines_within_edit_distance("password", 1, None)
+            .into_iter()
+            .collect();
+        results.sort();
+
+        assert_eq!(results, vec!["password".to_string(), "password1".to_string()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
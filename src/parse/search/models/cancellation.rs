@@ -0,0 +1,53 @@
+//! A cooperative cancellation flag shared between a search's caller and the code
+//! actually scanning index files, so the caller can ask a search in progress to stop
+//! early.
+//!
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A cheaply-cloned handle to a shared cancellation flag.
+///
+/// Cloning a [`CancellationToken`] does not create an independent flag; every clone
+/// observes [`Self::cancel`] called through any other clone. This is what lets a web
+/// handler hold on to one end while [`crate::models::IndexCollection`]'s search code
+/// checks the other.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a new, not-yet-cancelled token.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request that the search holding this token's other clones stop as soon as it
+    /// next checks.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`Self::cancel`] has been called on this token or any of its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_clone_observes_cancellation_through_the_original() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+
+        assert!(!token.is_cancelled());
+        assert!(!clone.is_cancelled());
+
+        clone.cancel();
+
+        assert!(token.is_cancelled());
+        assert!(clone.is_cancelled());
+    }
+}
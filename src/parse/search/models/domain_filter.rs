@@ -0,0 +1,65 @@
+//! Restricting search results to lines whose email field matches a domain, for
+//! collections indexed with `--format combo --combo-keep-email` (see
+//! `rockyou2024-index`'s `--format` flag).
+
+/// Whether `line`'s field before `delimiter` ends in an `@domain` matching `domain`.
+///
+/// A line from a plain index, or a combo line stored without its email field kept
+/// (`--combo-keep-email` was not passed at index time), never contains `delimiter`
+/// before an `@` and so never matches.
+pub fn line_matches_domain(line: &str, delimiter: char, domain: &str) -> bool {
+    let Some((email, _)) = line.split_once(delimiter) else {
+        return false;
+    };
+
+    email.rsplit_once('@').is_some_and(|(_, host)| host == domain)
+}
+
+/// Keep only the lines in `lines` whose email field matches `domain`; see
+/// [`line_matches_domain`].
+pub fn filter_lines_by_domain<'a>(
+    lines: impl IntoIterator<Item = &'a String>,
+    delimiter: char,
+    domain: &str,
+) -> Vec<String> {
+    lines.into_iter().filter(|line| line_matches_domain(line, delimiter, domain)).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_a_line_whose_email_ends_in_the_domain() {
+        assert!(line_matches_domain("alice@example.com:hunter2", ':', "example.com"));
+    }
+
+    #[test]
+    fn rejects_a_line_from_a_different_domain() {
+        assert!(!line_matches_domain("alice@example.org:hunter2", ':', "example.com"));
+    }
+
+    #[test]
+    fn rejects_a_line_without_the_delimiter() {
+        assert!(!line_matches_domain("hunter2", ':', "example.com"));
+    }
+
+    #[test]
+    fn rejects_a_line_whose_field_before_the_delimiter_has_no_at_sign() {
+        assert!(!line_matches_domain("alice:hunter2", ':', "example.com"));
+    }
+
+    #[test]
+    fn filter_keeps_only_matching_lines() {
+        let lines = vec![
+            "alice@example.com:hunter2".to_owned(),
+            "bob@example.org:letmein".to_owned(),
+            "carol@example.com:qwerty".to_owned(),
+        ];
+
+        assert_eq!(
+            filter_lines_by_domain(&lines, ':', "example.com"),
+            vec!["alice@example.com:hunter2".to_owned(), "carol@example.com:qwerty".to_owned()]
+        );
+    }
+}
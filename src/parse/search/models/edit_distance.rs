@@ -0,0 +1,195 @@
+//! Bounded edit-distance (Levenshtein) matching and candidate index-key expansion.
+
+use std::io::{self, BufReader, Read};
+
+use hashbrown::HashSet;
+
+use super::LossyLines;
+
+/// The alphabet substitutions are drawn from; matches the lowercase alphanumeric
+/// form index keys are folded down to by [`crate::string::convert_to_fuzzy_string`].
+const SUBSTITUTION_ALPHABET: &[char] = &[
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
+    't', 'u', 'v', 'w', 'x', 'y', 'z', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
+];
+
+/// The Levenshtein distance between `a` and `b`, or `None` if it is greater than
+/// `max_distance`.
+///
+/// This bails out early once every entry in the current row of the DP table exceeds
+/// `max_distance`, since no cheaper path can exist from there.
+pub fn bounded_levenshtein_distance(a: &str, b: &str, max_distance: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.len().abs_diff(b.len()) > max_distance {
+        return None;
+    }
+
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, &a_char) in a.iter().enumerate() {
+        let mut current_row = vec![i + 1];
+
+        for (j, &b_char) in b.iter().enumerate() {
+            let cost = usize::from(a_char != b_char);
+            current_row.push(
+                (current_row[j] + 1) // insertion
+                    .min(previous_row[j + 1] + 1) // deletion
+                    .min(previous_row[j] + cost), // substitution
+            );
+        }
+
+        if current_row.iter().min().is_some_and(|&min| min > max_distance) {
+            return None;
+        }
+
+        previous_row = current_row;
+    }
+
+    previous_row
+        .last()
+        .copied()
+        .filter(|&distance| distance <= max_distance)
+}
+
+/// Every string reachable from `query` by deleting or substituting a single
+/// character.
+///
+/// Insertions are deliberately not generated: expanding by every possible inserted
+/// character would blow up the candidate set without meaningfully improving recall,
+/// since a query's own characters are already covered by the other candidates'
+/// bucketing.
+fn one_edit_away(query: &str) -> Vec<String> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut candidates = Vec::new();
+
+    for skip in 0..chars.len() {
+        candidates.push(
+            chars
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &c)| (i != skip).then_some(c))
+                .collect::<String>(),
+        );
+    }
+
+    for i in 0..chars.len() {
+        for &c in SUBSTITUTION_ALPHABET {
+            if c == chars[i] {
+                continue;
+            }
+
+            let mut mutated = chars.clone();
+            mutated[i] = c;
+            candidates.push(mutated.into_iter().collect());
+        }
+    }
+
+    candidates
+}
+
+/// Expand `query` into every string within `max_distance` deletions/substitutions of
+/// it, including `query` itself.
+///
+/// This is used to derive the set of candidate index buckets for
+/// [`super::SearchStyle::EditDistance`]: since indexing buckets a string by its
+/// literal prefix, a query's near-variants may live in different buckets than the
+/// query itself.
+pub fn expand_by_edit_distance(query: &str, max_distance: usize) -> HashSet<String> {
+    let mut seen = HashSet::new();
+    seen.insert(query.to_owned());
+
+    let mut frontier = seen.clone();
+    for _ in 0..max_distance {
+        let mut next_frontier = HashSet::new();
+        for candidate in &frontier {
+            for mutated in one_edit_away(candidate) {
+                if seen.insert(mutated.clone()) {
+                    next_frontier.insert(mutated);
+                }
+            }
+        }
+        frontier = next_frontier;
+    }
+
+    seen
+}
+
+/// A scanner that yields every line within a bounded edit distance of one of a set of
+/// keys.
+pub struct EditDistanceScanner<R: Read> {
+    lines: LossyLines<R>,
+    keys: Vec<String>,
+    max_distance: usize,
+}
+
+impl<R: Read> EditDistanceScanner<R> {
+    /// Create a new scanner matching lines within `max_distance` edits of any of
+    /// `keys`.
+    pub fn new(reader: BufReader<R>, keys: &[&str], max_distance: usize) -> Self {
+        Self {
+            lines: LossyLines::new(reader),
+            keys: keys.iter().map(ToString::to_string).collect(),
+            max_distance,
+        }
+    }
+}
+
+impl<R: Read> Iterator for EditDistanceScanner<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(error) => return Some(Err(error)),
+            };
+
+            let is_match = self
+                .keys
+                .iter()
+                .any(|key| bounded_levenshtein_distance(key, &line, self.max_distance).is_some());
+
+            if is_match {
+                return Some(Ok(line));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_strings_have_zero_distance() {
+        assert_eq!(bounded_levenshtein_distance("password", "password", 2), Some(0));
+    }
+
+    #[test]
+    fn single_substitution_is_within_distance_one() {
+        assert_eq!(bounded_levenshtein_distance("password", "pa5sword", 1), Some(1));
+    }
+
+    #[test]
+    fn distances_beyond_the_bound_are_none() {
+        assert_eq!(bounded_levenshtein_distance("password", "hunter2", 2), None);
+    }
+
+    #[test]
+    fn one_edit_away_includes_deletions_and_substitutions_only() {
+        let candidates: HashSet<String> = one_edit_away("ab").into_iter().collect();
+        assert!(candidates.contains("b")); // Deletion of 'a'.
+        assert!(candidates.contains("a")); // Deletion of 'b'.
+        assert!(candidates.contains("cb")); // Substitution of 'a'.
+        assert!(!candidates.contains("abc")); // No insertions.
+    }
+
+    #[test]
+    fn expand_by_edit_distance_includes_the_original_query() {
+        let expanded = expand_by_edit_distance("pas", 1);
+        assert!(expanded.contains("pas"));
+        assert!(expanded.len() > 1);
+    }
+}
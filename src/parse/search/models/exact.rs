@@ -0,0 +1,41 @@
+//! Scan an index file's lines for an exact, whole-line match.
+
+use std::io::{self, BufReader, Read};
+
+use super::LossyLines;
+
+/// A scanner that yields every line equal to one of a set of keys.
+///
+/// Unlike [`super::LinesScanner`], which matches a substring anywhere in a line, this
+/// only yields lines that match a key in full; the comparison is case-sensitive.
+pub struct ExactScanner<R: Read> {
+    lines: LossyLines<R>,
+    keys: Vec<String>,
+}
+
+impl<R: Read> ExactScanner<R> {
+    /// Create a new scanner that matches lines equal to any of `keys`.
+    pub fn new(reader: BufReader<R>, keys: &[&str]) -> Self {
+        Self {
+            lines: LossyLines::new(reader),
+            keys: keys.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+impl<R: Read> Iterator for ExactScanner<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(error) => return Some(Err(error)),
+            };
+
+            if self.keys.iter().any(|key| key == &line) {
+                return Some(Ok(line));
+            }
+        }
+    }
+}
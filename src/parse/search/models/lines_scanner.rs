@@ -5,8 +5,15 @@ use std::io::{self, BufRead, BufReader, Read, Seek};
 
 use aho_corasick::AhoCorasick;
 
-use super::SearchStyle;
+use super::{CancellationToken, SearchStyle};
 use crate::config::MAX_LINE_LENGTH;
+use crate::models::LineOffsetTable;
+
+/// Decode `bytes` lossily and trim trailing whitespace, so a line containing invalid
+/// UTF-8 is still resolved instead of aborting the search with an [`io::Error`].
+fn trim_end_lossy(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).trim_end().to_owned()
+}
 
 /// A scanner for searching for a key in an index.
 ///
@@ -15,16 +22,40 @@ use crate::config::MAX_LINE_LENGTH;
 pub struct LinesScanner<R: Seek + Read + 'static> {
     reader: BufReader<R>,
     ranges: <Vec<aho_corasick::Match> as IntoIterator>::IntoIter,
+    /// The line-start sidecar table for the underlying file, if one was found; used
+    /// to resolve a match's byte offset to the exact line that contains it.
+    offsets: Option<LineOffsetTable>,
+    /// Checked before resolving each remaining match to its line; once cancelled, the
+    /// scanner stops early rather than resolving matches nobody wants any more.
+    cancellation: Option<CancellationToken>,
 }
 
 impl<R: Seek + Read + 'static> LinesScanner<R> {
     /// Create a new scanner.
     ///
+    /// `offsets`, if provided, is used to resolve a match to its containing line
+    /// exactly; without it, the scanner falls back to seeking back
+    /// [`MAX_LINE_LENGTH`] bytes and scanning forward.
+    ///
+    /// `cancellation`, if provided, is checked by [`Iterator::next`] before resolving
+    /// each remaining match, so a cancelled search stops yielding further lines. The
+    /// matches themselves are already found by the time this returns, so cancellation
+    /// cannot interrupt that initial scan, only the line resolution that follows it.
+    ///
+    /// `max_results`, if provided, stops the underlying Aho-Corasick stream after that
+    /// many matches, so unlike `cancellation` it does cut the initial scan short
+    /// rather than merely skipping the line resolution that follows it - useful for a
+    /// broad query where the caller only wants the first handful of lines and does
+    /// not care about the rest of a large file.
+    ///
     /// [`aho_corasick`] errors will be coerced into [`std::io::Error`].
     pub fn new(
         reader_factory: impl Fn() -> io::Result<BufReader<R>>,
         query: &[&str],
         search_style: SearchStyle,
+        offsets: Option<LineOffsetTable>,
+        cancellation: Option<CancellationToken>,
+        max_results: Option<usize>,
     ) -> io::Result<Self> {
         let transformed_query = search_style.transform_query()(query);
         crate::debug!("Transformed query: {:?}", transformed_query);
@@ -58,22 +89,54 @@ impl<R: Seek + Read + 'static> LinesScanner<R> {
                         ),
                     )
                 })
-            })
-            .collect::<io::Result<Vec<_>>>()?;
+            });
+
+        let ranges = match max_results {
+            Some(max_results) => ranges.take(max_results).collect::<io::Result<Vec<_>>>(),
+            None => ranges.collect::<io::Result<Vec<_>>>(),
+        }?;
 
         Ok(Self {
             reader: reader_factory()?,
             ranges: ranges.into_iter(),
+            offsets,
+            cancellation,
         })
     }
 
-    /// Find the line that contains the key.
+    /// Find the line that contains the key, using the offset table if one is
+    /// available.
     fn line_of_range(&mut self, range: aho_corasick::Match) -> io::Result<String> {
+        if let Some(offsets) = &self.offsets {
+            if let Some(line_start) = offsets.line_start_containing(range.start() as u64) {
+                self.reader.seek(io::SeekFrom::Start(line_start))?;
+
+                let mut buffer = Vec::with_capacity(MAX_LINE_LENGTH);
+                self.reader.read_until(b'\n', &mut buffer)?;
+
+                let line = trim_end_lossy(&buffer);
+                if !line.is_empty() {
+                    return Ok(line);
+                }
+                // Fall through to the legacy approach below; this should only happen
+                // if the offset table is stale.
+            }
+        }
+
+        self.line_of_range_by_scanning_back(range)
+    }
+
+    /// Find the line that contains the key by seeking back [`MAX_LINE_LENGTH`] bytes
+    /// and scanning forward.
+    ///
+    /// This is the fallback used when no offset table is available for the
+    /// underlying file.
+    fn line_of_range_by_scanning_back(&mut self, range: aho_corasick::Match) -> io::Result<String> {
         // FIXME I do not know why, but the ranges from Aho-corasick is off by one.
         let range_start = range.start();
         let range_end = range.end();
 
-        let mut buffer = String::with_capacity(MAX_LINE_LENGTH);
+        let mut buffer = Vec::with_capacity(MAX_LINE_LENGTH);
         let mut pos = range_start.saturating_sub(MAX_LINE_LENGTH);
 
         self.reader.seek(io::SeekFrom::Start(pos as u64))?;
@@ -82,10 +145,10 @@ impl<R: Seek + Read + 'static> LinesScanner<R> {
         while pos < range_end {
             buffer.clear();
             _lastpos = pos;
-            pos += self.reader.read_line(&mut buffer)?;
+            pos += self.reader.read_until(b'\n', &mut buffer)?;
         }
 
-        let line = buffer.trim_end();
+        let line = trim_end_lossy(&buffer);
 
         if line.is_empty() {
             // This should not happen, but just in case.
@@ -95,7 +158,7 @@ impl<R: Seek + Read + 'static> LinesScanner<R> {
             ));
         }
 
-        Ok(line.to_owned())
+        Ok(line)
     }
 }
 
@@ -103,6 +166,10 @@ impl<R: Seek + Read> Iterator for LinesScanner<R> {
     type Item = io::Result<String>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.cancellation.as_ref().is_some_and(CancellationToken::is_cancelled) {
+            return None;
+        }
+
         self.ranges.next().map(|range| self.line_of_range(range))
     }
 }
@@ -0,0 +1,49 @@
+//! An [`Iterator`] over the lines of a reader, like [`std::io::Lines`], except a line
+//! containing invalid UTF-8 is lossily decoded instead of aborting the whole scan.
+
+use std::io::{self, BufRead, BufReader, Read};
+
+/// Like [`std::io::Lines`], but never fails on invalid UTF-8.
+///
+/// [`std::io::Lines`] returns an [`io::Error`] for a line that is not valid UTF-8,
+/// which aborts the rest of the scan; a password dump routinely contains raw,
+/// non-UTF-8 bytes, so the scanners built on top of this instead decode such a line
+/// via [`String::from_utf8_lossy`] and keep going, the same way a non-UTF-8 line is
+/// already tolerated when it is first indexed.
+pub struct LossyLines<R: Read> {
+    reader: BufReader<R>,
+    buffer: Vec<u8>,
+}
+
+impl<R: Read> LossyLines<R> {
+    /// Wrap `reader` to iterate over its lines lossily.
+    pub fn new(reader: BufReader<R>) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl<R: Read> Iterator for LossyLines<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.clear();
+
+        match self.reader.read_until(b'\n', &mut self.buffer) {
+            Ok(0) => None,
+            Ok(_) => {
+                if self.buffer.last() == Some(&b'\n') {
+                    self.buffer.pop();
+                    if self.buffer.last() == Some(&b'\r') {
+                        self.buffer.pop();
+                    }
+                }
+
+                Some(Ok(String::from_utf8_lossy(&self.buffer).into_owned()))
+            }
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
@@ -1,8 +1,47 @@
 mod lines_scanner;
 pub use lines_scanner::*;
 
+mod lossy_lines;
+pub use lossy_lines::*;
+
 mod manipulated_reader;
 pub use manipulated_reader::*;
 
+mod pagination;
+pub use pagination::*;
+
 mod search_style;
 pub use search_style::*;
+
+mod wildcard;
+pub use wildcard::*;
+
+mod exact;
+pub use exact::*;
+
+mod edit_distance;
+pub use edit_distance::*;
+
+mod search_semaphore;
+pub use search_semaphore::*;
+
+mod timed_search;
+pub use timed_search::*;
+
+mod cancellation;
+pub use cancellation::*;
+
+mod singleflight;
+pub use singleflight::*;
+
+mod relevance;
+pub use relevance::*;
+
+mod trigram;
+pub use trigram::*;
+
+mod phonetic;
+pub use phonetic::*;
+
+mod domain_filter;
+pub use domain_filter::*;
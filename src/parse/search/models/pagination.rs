@@ -0,0 +1,100 @@
+//! Stable, cursor-based pagination for [`super::super::implementations`] search results.
+//!
+
+use super::score_line;
+
+/// How to order a result set before paginating it.
+///
+/// A `HashSet` (as returned by [`crate::models::IndexCollection::find_lines_containing`])
+/// has no stable iteration order between calls, so paginating over one directly makes
+/// page N non-deterministic; sorting first, by one of these orders, is what gives
+/// [`crate::models::IndexCollection::find_lines_containing_paginated`] and
+/// [`crate::models::IndexCollection::find_lines_containing_page`] a stable notion of
+/// "page N" to begin with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResultOrder {
+    /// Sort lines lexicographically. The default, and the cheapest deterministic
+    /// order to produce.
+    #[default]
+    Lexicographic,
+
+    /// Sort lines by length, shortest first.
+    Length,
+
+    /// Sort lines by [`super::score_line`] against the query, closest match first -
+    /// the same relevance order as
+    /// [`crate::models::IndexCollection::find_lines_containing_ranked`].
+    Score,
+
+    /// Do not sort at all.
+    ///
+    /// Cheapest option, but pagination over an unsorted result is not stable between
+    /// calls: the same offset may return different lines, or the same line may appear
+    /// on more than one page.
+    Unsorted,
+}
+
+impl ResultOrder {
+    /// Sort `lines` in place according to this order, against `query` for
+    /// [`Self::Score`].
+    pub fn sort(&self, query: &str, lines: &mut [String]) {
+        match self {
+            ResultOrder::Lexicographic => lines.sort_unstable(),
+            ResultOrder::Length => lines.sort_by_key(|line| line.len()),
+            ResultOrder::Score => lines.sort_by_key(|line| score_line(query, line)),
+            ResultOrder::Unsorted => {}
+        }
+    }
+}
+
+/// A stable, opaque cursor into the deterministically-ordered result set of a query.
+///
+/// A `HashSet` (as returned by [`crate::models::IndexCollection::find_lines_containing`])
+/// has no stable iteration order between calls, so skipping/taking over it directly
+/// makes page N non-deterministic. A [`Cursor`] instead points at a position within the
+/// lexicographically sorted results, which is stable for as long as the underlying
+/// result set doesn't change.
+///
+/// Treat the value as opaque; round-trip it via [`Cursor::encode`]/[`Cursor::decode`]
+/// rather than relying on its internal representation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Cursor(usize);
+
+impl Cursor {
+    /// The cursor pointing at the very first result.
+    pub const START: Cursor = Cursor(0);
+
+    /// Encode this cursor as an opaque token suitable for passing to a client.
+    pub fn encode(&self) -> String {
+        format!("{:x}", self.0)
+    }
+
+    /// Decode a token previously produced by [`Cursor::encode`].
+    ///
+    /// Returns `None` if the token is not a cursor this crate produced.
+    pub fn decode(token: &str) -> Option<Self> {
+        usize::from_str_radix(token, 16).ok().map(Cursor)
+    }
+}
+
+impl From<usize> for Cursor {
+    fn from(offset: usize) -> Self {
+        Cursor(offset)
+    }
+}
+
+impl From<Cursor> for usize {
+    fn from(cursor: Cursor) -> Self {
+        cursor.0
+    }
+}
+
+/// A stable, ordered page of search results.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Page {
+    /// The lines found for this page, in a deterministic (lexicographic) order.
+    pub items: Vec<String>,
+
+    /// A cursor for the next page, or `None` if this was the last page.
+    pub next_cursor: Option<Cursor>,
+}
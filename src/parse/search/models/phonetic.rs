@@ -0,0 +1,46 @@
+//! Scan an index file's lines for a Soundex match against a query.
+
+use std::io::{self, BufReader, Read};
+
+use crate::string::soundex;
+
+use super::LossyLines;
+
+/// A scanner that yields every line whose Soundex code matches one of a set of keys.
+///
+/// Like [`super::ExactScanner`] and [`super::WildcardScanner`], Soundex folds a whole
+/// word down to a code with no positional correspondence to the original characters,
+/// so this reads and tests each line in turn rather than relying on
+/// [`super::LinesScanner`]'s Aho-Corasick substring matching.
+pub struct PhoneticScanner<R: Read> {
+    lines: LossyLines<R>,
+    codes: Vec<String>,
+}
+
+impl<R: Read> PhoneticScanner<R> {
+    /// Create a new scanner that matches lines whose Soundex code equals the Soundex
+    /// code of any of `keys`.
+    pub fn new(reader: BufReader<R>, keys: &[&str]) -> Self {
+        Self {
+            lines: LossyLines::new(reader),
+            codes: keys.iter().map(|key| soundex(key)).collect(),
+        }
+    }
+}
+
+impl<R: Read> Iterator for PhoneticScanner<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(error) => return Some(Err(error)),
+            };
+
+            if self.codes.iter().any(|code| code == &soundex(&line)) {
+                return Some(Ok(line));
+            }
+        }
+    }
+}
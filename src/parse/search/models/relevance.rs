@@ -0,0 +1,95 @@
+//! Ranking a matched line by how closely it resembles the query that found it.
+
+use super::edit_distance::bounded_levenshtein_distance;
+
+/// How closely a line matched a query, used to sort search results by relevance.
+///
+/// Ordered so that the most relevant kind of match sorts first: an exact match, then
+/// a prefix match, then any other substring match, then a fuzzy match ranked by how
+/// many edits separate it from the query - the derived [`Ord`] compares variants in
+/// declaration order, and compares [`Self::Fuzzy`] distances numerically, so sorting
+/// a `Vec<MatchScore>` ascending already yields the desired relevance order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchScore {
+    /// The line is equal to the query - the strongest possible match.
+    Exact,
+    /// The line starts with the query.
+    Prefix,
+    /// The query appears somewhere within the line, but not at its start.
+    Substring,
+    /// Neither the line nor the query contain one another; ranked by the Levenshtein
+    /// distance between them, closest first.
+    Fuzzy(usize),
+}
+
+/// A line found by a search, together with how closely it matched the query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoredLine {
+    /// The line itself.
+    pub line: String,
+
+    /// How closely `line` matched the query that found it.
+    pub score: MatchScore,
+}
+
+/// Score `line` against `query`.
+///
+/// This looks only at the raw strings, regardless of the [`super::SearchStyle`] that
+/// found the match - a line pulled in by [`super::SearchStyle::Fuzzy`] or
+/// [`super::SearchStyle::CaseInsensitive`] will usually fall back to
+/// [`MatchScore::Fuzzy`], since it need not literally contain `query`.
+pub fn score_line(query: &str, line: &str) -> MatchScore {
+    if line == query {
+        return MatchScore::Exact;
+    }
+
+    if line.starts_with(query) {
+        return MatchScore::Prefix;
+    }
+
+    if line.contains(query) {
+        return MatchScore::Substring;
+    }
+
+    // Every edit distance between two strings is at most the length of the longer
+    // one, so this cap always yields a real distance rather than `None`.
+    let cap = query.chars().count().max(line.chars().count());
+    MatchScore::Fuzzy(bounded_levenshtein_distance(query, line, cap).unwrap_or(cap))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_strings_score_as_exact() {
+        assert_eq!(score_line("password", "password"), MatchScore::Exact);
+    }
+
+    #[test]
+    fn a_line_starting_with_the_query_scores_as_prefix() {
+        assert_eq!(score_line("password", "password123"), MatchScore::Prefix);
+    }
+
+    #[test]
+    fn a_line_containing_the_query_mid_string_scores_as_substring() {
+        assert_eq!(score_line("password", "mypassword"), MatchScore::Substring);
+    }
+
+    #[test]
+    fn an_unrelated_line_scores_as_fuzzy_with_a_real_distance() {
+        assert_eq!(score_line("password", "pa5sword"), MatchScore::Fuzzy(1));
+    }
+
+    #[test]
+    fn exact_outranks_prefix_outranks_substring_outranks_fuzzy() {
+        assert!(MatchScore::Exact < MatchScore::Prefix);
+        assert!(MatchScore::Prefix < MatchScore::Substring);
+        assert!(MatchScore::Substring < MatchScore::Fuzzy(0));
+    }
+
+    #[test]
+    fn closer_fuzzy_matches_outrank_further_ones() {
+        assert!(MatchScore::Fuzzy(1) < MatchScore::Fuzzy(2));
+    }
+}
@@ -0,0 +1,96 @@
+//! A counting semaphore used to bound how many searches run concurrently against an
+//! [`crate::models::IndexCollection`].
+//!
+
+use std::sync::{Condvar, Mutex};
+
+/// A counting semaphore: [`Self::acquire`] blocks the calling thread - queueing it -
+/// until a permit is available, and the returned [`SearchPermit`] releases the permit
+/// back to the semaphore when dropped.
+pub struct SearchSemaphore {
+    /// The number of permits this semaphore was created with, exposed via
+    /// [`Self::capacity`] so callers can report the configured limit.
+    capacity: usize,
+    available: Mutex<usize>,
+    condvar: Condvar,
+}
+
+impl SearchSemaphore {
+    /// Create a semaphore with `permits` concurrent slots.
+    pub fn new(permits: usize) -> Self {
+        Self {
+            capacity: permits,
+            available: Mutex::new(permits),
+            condvar: Condvar::new(),
+        }
+    }
+
+    /// The number of permits this semaphore was created with.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Block until a permit is available, then take it.
+    pub fn acquire(&self) -> SearchPermit<'_> {
+        let mut available = self
+            .available
+            .lock()
+            .expect("Failed to acquire lock on search semaphore; semaphore might be poisoned.");
+
+        while *available == 0 {
+            available = self.condvar.wait(available).expect(
+                "Failed to wait on search semaphore condvar; semaphore might be poisoned.",
+            );
+        }
+
+        *available -= 1;
+
+        SearchPermit { semaphore: self }
+    }
+}
+
+/// A permit held against a [`SearchSemaphore`], releasing it back to the semaphore on
+/// [`Drop`].
+pub struct SearchPermit<'semaphore> {
+    semaphore: &'semaphore SearchSemaphore,
+}
+
+impl Drop for SearchPermit<'_> {
+    fn drop(&mut self) {
+        *self
+            .semaphore
+            .available
+            .lock()
+            .expect("Failed to acquire lock on search semaphore; semaphore might be poisoned.") +=
+            1;
+        self.semaphore.condvar.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn acquire_blocks_until_a_permit_is_released() {
+        let semaphore = Arc::new(SearchSemaphore::new(1));
+        assert_eq!(semaphore.capacity(), 1);
+
+        let first_permit = semaphore.acquire();
+
+        let semaphore_clone = Arc::clone(&semaphore);
+        let handle = std::thread::spawn(move || {
+            // This blocks until `first_permit` is dropped below.
+            let _second_permit = semaphore_clone.acquire();
+        });
+
+        // Give the spawned thread a chance to actually block on `acquire`.
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!handle.is_finished());
+
+        drop(first_permit);
+        handle.join().expect("Failed to join the waiting thread.");
+    }
+}
@@ -6,25 +6,127 @@ use std::io::Read;
 use super::ManipulatedReader;
 use crate::string;
 
+/// A function transforming the raw query strings into the form actually searched for,
+/// as returned by [`SearchStyle::transform_query`].
+type QueryTransform<'s> = Box<dyn Fn(&[&'s str]) -> Vec<String>>;
+
+/// The largest `max_distance` [`SearchStyle::edit_distance`] will accept.
+///
+/// [`super::expand_by_edit_distance`]'s candidate frontier grows by roughly
+/// `O(len * |alphabet|)` per round of distance, so an unbounded caller-supplied
+/// distance is enough to exhaust memory/CPU on a modest-length query; every site
+/// that turns untrusted input into a distance (the gRPC/UDS/wasm/FFI front ends)
+/// should build the variant through [`SearchStyle::edit_distance`] rather than the
+/// struct literal, so this cap always applies.
+pub const MAX_EDIT_DISTANCE: usize = 2;
+
 /// The style of search to perform.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SearchStyle {
     Strict,
-    CaseInsensitive,
-    Fuzzy,
+    /// Match lines equal to the query once both are lowercased.
+    CaseInsensitive {
+        /// Fold the query and every line through [`string::unicode_case_fold`]
+        /// (NFKC normalisation followed by full Unicode lowercasing) instead of
+        /// [`str::to_ascii_lowercase`], so that non-ASCII letters compare
+        /// case-insensitively too (e.g. `"PELÉ"` matching `"pelé"`), and differently
+        /// composed representations of the same accented letter compare equal.
+        unicode: bool,
+    },
+    /// Match lines that are equal to the query once leet-speak substitutions (and,
+    /// optionally, keyboard-adjacent substitutions) have been folded away on both
+    /// sides.
+    Fuzzy {
+        /// Also fold keyboard-adjacent characters together (e.g. `q`/`w`, `1`/`!`),
+        /// on top of the usual leet-speak folding, to catch typo-shifted variants of
+        /// the query.
+        keyboard_adjacent: bool,
+    },
+    /// Match a whole line against a `*`/`?` glob pattern, e.g. `pass*123` or
+    /// `p?ssword`.
+    ///
+    /// This does not go through [`Self::transform_query`] or
+    /// [`Self::transform_reader`]; instead it is dispatched to a dedicated scanner in
+    /// [`super::wildcard`], since a glob pattern needs whole-line matching rather than
+    /// the Aho-Corasick substring search the other styles share.
+    Wildcard,
+    /// Only match lines equal to the query in full, case-sensitively, rather than
+    /// containing it as a substring - the common "is this exact password in the
+    /// dump?" case.
+    ///
+    /// Like [`Self::Wildcard`], this is dispatched to a dedicated scanner in
+    /// [`super::exact`] instead of going through [`Self::transform_query`] or
+    /// [`Self::transform_reader`].
+    Exact,
+    /// Match lines within `max_distance` deletions/substitutions of the query, e.g.
+    /// to find near-variants of a password.
+    ///
+    /// Candidate index keys are expanded by [`super::expand_by_edit_distance`] before
+    /// scanning, since a near-variant of the query may fall into a different bucket
+    /// than the query itself; like [`Self::Wildcard`] and [`Self::Exact`], this is
+    /// dispatched to a dedicated scanner rather than going through
+    /// [`Self::transform_query`] or [`Self::transform_reader`].
+    EditDistance {
+        /// The maximum number of deletions/substitutions a line may differ from the
+        /// query by and still match.
+        max_distance: usize,
+    },
+    /// Match lines that sound like the query, via a Soundex code comparison, e.g. to
+    /// find "jhonny123" when searching for "johnny123".
+    ///
+    /// A Soundex code folds many differently-spelled words down to the same short
+    /// code with no positional correspondence to the original characters, so like
+    /// [`Self::Wildcard`], [`Self::Exact`] and [`Self::EditDistance`], this is
+    /// dispatched to a dedicated scanner rather than going through
+    /// [`Self::transform_query`] or [`Self::transform_reader`].
+    Phonetic,
 }
 
 impl SearchStyle {
+    /// Build [`Self::EditDistance`], clamping `max_distance` to
+    /// [`MAX_EDIT_DISTANCE`] so a caller-supplied distance can't blow up the
+    /// candidate expansion in [`super::expand_by_edit_distance`].
+    pub fn edit_distance(max_distance: usize) -> Self {
+        Self::EditDistance {
+            max_distance: max_distance.min(MAX_EDIT_DISTANCE),
+        }
+    }
+
     /// Transform a query string into the desired format.
-    pub fn transform_query<'s>(&self) -> fn(&[&'s str]) -> Vec<String> {
+    pub fn transform_query<'s>(&self) -> QueryTransform<'s> {
         match self {
-            SearchStyle::Strict => |s| s.iter().map(|s| s.to_string()).collect(),
-            SearchStyle::CaseInsensitive => |s| s.iter().map(|s| s.to_ascii_lowercase()).collect(),
-            SearchStyle::Fuzzy => |s| {
+            SearchStyle::Strict
+            | SearchStyle::Wildcard
+            | SearchStyle::Exact
+            | SearchStyle::EditDistance { .. }
+            | SearchStyle::Phonetic => {
+                Box::new(|s| s.iter().map(|s| s.to_string()).collect())
+            }
+            SearchStyle::CaseInsensitive { unicode: false } => {
+                Box::new(|s| s.iter().map(|s| s.to_ascii_lowercase()).collect())
+            }
+            SearchStyle::CaseInsensitive { unicode: true } => {
+                Box::new(|s| s.iter().map(|s| string::unicode_case_fold(s)).collect())
+            }
+            SearchStyle::Fuzzy {
+                keyboard_adjacent: false,
+            } => Box::new(|s| {
                 s.iter()
                     .map(|s| string::convert_to_fuzzy_string(s).collect::<String>())
                     .collect()
-            },
+            }),
+            SearchStyle::Fuzzy {
+                keyboard_adjacent: true,
+            } => Box::new(|s| {
+                s.iter()
+                    .map(|s| {
+                        string::map_characters_to_fuzzy_keyboard_adjacent(
+                            string::convert_extended_to_ascii(s),
+                        )
+                        .collect::<String>()
+                    })
+                    .collect()
+            }),
         }
     }
 
@@ -32,16 +134,60 @@ impl SearchStyle {
     pub fn transform_reader<R: Read + 'static>(&self, reader: R) -> Box<dyn Read> {
         // Is this efficient?
         match self {
-            SearchStyle::Strict => Box::new(reader),
-            SearchStyle::CaseInsensitive => Box::new(ManipulatedReader::new(reader, |buffer| {
-                buffer.to_ascii_lowercase()
-            })),
-            SearchStyle::Fuzzy => Box::new(ManipulatedReader::new(reader, |buffer| {
+            SearchStyle::Strict
+            | SearchStyle::Wildcard
+            | SearchStyle::Exact
+            | SearchStyle::EditDistance { .. }
+            | SearchStyle::Phonetic => Box::new(reader),
+            SearchStyle::CaseInsensitive { unicode: false } => {
+                Box::new(ManipulatedReader::new(reader, |buffer| buffer.to_ascii_lowercase()))
+            }
+            SearchStyle::CaseInsensitive { unicode: true } => {
+                Box::new(ManipulatedReader::new(reader, |buffer| {
+                    string::unicode_case_fold(&String::from_utf8_lossy(buffer)).into_bytes()
+                }))
+            }
+            SearchStyle::Fuzzy {
+                keyboard_adjacent: false,
+            } => Box::new(ManipulatedReader::new(reader, |buffer| {
                 string::map_characters_to_fuzzy(String::from_utf8_lossy(buffer).chars())
                     .collect::<String>()
                     .as_bytes()
                     .to_vec()
             })),
+            SearchStyle::Fuzzy {
+                keyboard_adjacent: true,
+            } => Box::new(ManipulatedReader::new(reader, |buffer| {
+                string::map_characters_to_fuzzy_keyboard_adjacent(
+                    String::from_utf8_lossy(buffer).chars(),
+                )
+                .collect::<String>()
+                .as_bytes()
+                .to_vec()
+            })),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance_clamps_to_the_maximum() {
+        assert_eq!(
+            SearchStyle::edit_distance(MAX_EDIT_DISTANCE + 100),
+            SearchStyle::EditDistance {
+                max_distance: MAX_EDIT_DISTANCE
+            }
+        );
+    }
+
+    #[test]
+    fn edit_distance_leaves_values_within_the_maximum_alone() {
+        assert_eq!(
+            SearchStyle::edit_distance(1),
+            SearchStyle::EditDistance { max_distance: 1 }
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Coalesces concurrent calls for the same key into a single execution, so that N
+//! callers asking for the same thing at the same time only do the work once.
+//!
+
+use std::hash::Hash;
+use std::sync::{Arc, Condvar, Mutex};
+
+use hashbrown::hash_map::Entry;
+use hashbrown::HashMap;
+
+/// The state of an in-flight call, shared by every caller waiting on it.
+enum CallState<V> {
+    /// The leader is still running `f`.
+    Pending,
+    /// The leader finished and every waiter can clone this value out.
+    Done(V),
+    /// The leader panicked; every waiter should panic too rather than wait forever
+    /// on a condvar that will never be notified again by the (now-unwound) leader.
+    Panicked,
+}
+
+/// The result of an in-flight call, shared by every caller waiting on it.
+struct InFlight<V> {
+    state: Mutex<CallState<V>>,
+    done: Condvar,
+}
+
+/// Clears `key` out of `in_flight` when dropped, and - unless [`Self::complete`] was
+/// called first - marks the call [`CallState::Panicked`] and wakes every follower.
+///
+/// This runs on every exit from [`Singleflight::run`]'s leader branch, including
+/// unwinding past a panicking `f`, so a panic can never leave the key registered
+/// forever or leave followers parked on a condvar nobody will ever notify again.
+struct LeaderGuard<'a, K: Eq + Hash + Clone, V> {
+    singleflight: &'a Singleflight<K, V>,
+    key: K,
+    call: Arc<InFlight<V>>,
+    completed: bool,
+}
+
+impl<K: Eq + Hash + Clone, V> LeaderGuard<'_, K, V> {
+    /// Record that the leader finished normally, so `Drop` does not mark the call as
+    /// panicked.
+    fn complete(&mut self) {
+        self.completed = true;
+    }
+}
+
+impl<K: Eq + Hash + Clone, V> Drop for LeaderGuard<'_, K, V> {
+    fn drop(&mut self) {
+        if !self.completed {
+            *self
+                .call
+                .state
+                .lock()
+                .expect("Failed to acquire lock on singleflight call; it might be poisoned.") =
+                CallState::Panicked;
+            self.call.done.notify_all();
+        }
+
+        self.singleflight
+            .in_flight
+            .lock()
+            .expect("Failed to acquire lock on singleflight registry; it might be poisoned.")
+            .remove(&self.key);
+    }
+}
+
+/// A registry of in-flight calls, keyed by `K`.
+///
+/// The first caller for a given key runs the closure passed to [`Self::run`] and
+/// shares its result with every other caller that asks for the same key while it is
+/// still running; once it finishes, the key is forgotten, so a later call for the
+/// same key runs again from scratch.
+pub struct Singleflight<K, V> {
+    in_flight: Mutex<HashMap<K, Arc<InFlight<V>>>>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Default for Singleflight<K, V> {
+    fn default() -> Self {
+        Self {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Singleflight<K, V> {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `f` for `key`, or block until another thread already running it for the
+    /// same key finishes, and share its result instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` panics (the leader's own panic propagates as usual), or if this
+    /// call is a follower and the leader running `f` for `key` panicked.
+    pub fn run(&self, key: K, f: impl FnOnce() -> V) -> V {
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .expect("Failed to acquire lock on singleflight registry; it might be poisoned.");
+
+        match in_flight.entry(key.clone()) {
+            Entry::Occupied(entry) => {
+                let call = Arc::clone(entry.get());
+                drop(in_flight);
+
+                let mut state = call
+                    .state
+                    .lock()
+                    .expect("Failed to acquire lock on singleflight call; it might be poisoned.");
+                loop {
+                    match &*state {
+                        CallState::Pending => {
+                            state = call.done.wait(state).expect(
+                                "Failed to wait on singleflight call condvar; it might be \
+                                 poisoned.",
+                            );
+                        }
+                        CallState::Done(value) => return value.clone(),
+                        CallState::Panicked => {
+                            panic!(
+                                "The leader call for this singleflight key panicked; see its \
+                                 panic message above."
+                            )
+                        }
+                    }
+                }
+            }
+            Entry::Vacant(entry) => {
+                let call = Arc::new(InFlight {
+                    state: Mutex::new(CallState::Pending),
+                    done: Condvar::new(),
+                });
+                entry.insert(Arc::clone(&call));
+                drop(in_flight);
+
+                let mut guard = LeaderGuard {
+                    singleflight: self,
+                    key,
+                    call: Arc::clone(&call),
+                    completed: false,
+                };
+
+                let value = f();
+
+                *call
+                    .state
+                    .lock()
+                    .expect("Failed to acquire lock on singleflight call; it might be poisoned.") =
+                    CallState::Done(value.clone());
+                call.done.notify_all();
+                guard.complete();
+
+                value
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn concurrent_calls_for_the_same_key_share_one_execution() {
+        let singleflight: Arc<Singleflight<&'static str, usize>> = Arc::new(Singleflight::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let singleflight = Arc::clone(&singleflight);
+                let calls = Arc::clone(&calls);
+                std::thread::spawn(move || {
+                    singleflight.run("password", || {
+                        calls.fetch_add(1, Ordering::Relaxed);
+                        std::thread::sleep(Duration::from_millis(50));
+                        42
+                    })
+                })
+            })
+            .collect();
+
+        let results: Vec<usize> = handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Failed to join a singleflight thread."))
+            .collect();
+
+        assert_eq!(results, vec![42; 8]);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn a_later_call_for_the_same_key_runs_again() {
+        let singleflight: Singleflight<&'static str, usize> = Singleflight::new();
+        let calls = AtomicUsize::new(0);
+
+        let first = singleflight.run("password", || {
+            calls.fetch_add(1, Ordering::Relaxed);
+            1
+        });
+        let second = singleflight.run("password", || {
+            calls.fetch_add(1, Ordering::Relaxed);
+            2
+        });
+
+        assert_eq!((first, second), (1, 2));
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn a_panicking_leader_does_not_wedge_a_waiting_follower_or_later_callers() {
+        let singleflight: Arc<Singleflight<&'static str, usize>> = Arc::new(Singleflight::new());
+        // Lets the main thread hold off spawning the follower until the leader has
+        // already registered itself and is inside `f`, so the follower is guaranteed
+        // to observe an `Entry::Occupied` and actually wait on the condvar.
+        let leader_started = Arc::new(std::sync::Barrier::new(2));
+
+        let leader = {
+            let singleflight = Arc::clone(&singleflight);
+            let leader_started = Arc::clone(&leader_started);
+            std::thread::spawn(move || {
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    singleflight.run("password", || {
+                        leader_started.wait();
+                        std::thread::sleep(Duration::from_millis(50));
+                        panic!("the leader call panicked");
+                    })
+                }))
+            })
+        };
+
+        leader_started.wait();
+
+        let follower = {
+            let singleflight = Arc::clone(&singleflight);
+            std::thread::spawn(move || {
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    singleflight.run("password", || 42)
+                }))
+            })
+        };
+
+        assert!(
+            leader.join().expect("Failed to join the leader thread.").is_err(),
+            "The leader's own panic should propagate to its caller."
+        );
+        assert!(
+            follower.join().expect("Failed to join the follower thread.").is_err(),
+            "A follower waiting on a panicking leader should panic too, instead of hanging \
+             forever on a condvar nobody will notify again."
+        );
+
+        // The panicking call must have cleared itself out of the registry, so this
+        // does not join an occupied-but-abandoned entry and hang.
+        let after = singleflight.run("password", || 99);
+        assert_eq!(after, 99);
+    }
+}
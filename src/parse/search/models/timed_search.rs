@@ -0,0 +1,25 @@
+//! The result of a search bounded by a wall-clock deadline; see
+//! [`super::super::implementations`].
+//!
+
+use super::super::implementations::IndexCollectionReturn;
+
+/// The result of [`crate::models::IndexCollection::find_lines_containing_with_timeout`].
+#[derive(Debug, Clone)]
+pub struct TimedSearchResult {
+    /// The lines found before the search either finished or ran out of time.
+    pub lines: IndexCollectionReturn,
+
+    /// `true` if the search stopped before every candidate index file had been fully
+    /// scanned - whether because a timeout elapsed, the caller cancelled, an overall
+    /// result cap was reached, or a per-file result cap was reached in at least one
+    /// index file - meaning `lines` may be missing matches that a full search would
+    /// have found.
+    pub truncated: bool,
+
+    /// The keys of the index files whose contribution was cut short by
+    /// [`crate::models::IndexCollection::find_lines_containing_with_per_file_limit`]'s
+    /// `max_results_per_file`; empty unless that limit was set and actually reached
+    /// in at least one index file.
+    pub truncated_files: Vec<String>,
+}
@@ -0,0 +1,83 @@
+//! Trigram overlap, for ranking lines by rough textual similarity rather than an
+//! exact/fuzzy match.
+
+use hashbrown::HashSet;
+
+/// Every overlapping run of 3 characters in `value`, or `value` itself as the sole
+/// member if it is shorter than that.
+pub fn trigrams(value: &str) -> HashSet<String> {
+    let chars: Vec<char> = value.chars().collect();
+
+    if chars.len() < 3 {
+        return HashSet::from_iter([chars.into_iter().collect()]);
+    }
+
+    chars.windows(3).map(|window| window.iter().collect()).collect()
+}
+
+/// The Jaccard similarity between two trigram sets - the fraction of their combined
+/// trigrams that both share - `1.0` if both sets are empty, since two empty strings
+/// are identical.
+pub fn trigram_similarity_from_sets(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let intersection = a.intersection(b).count();
+    let union = a.len() + b.len() - intersection;
+
+    if union == 0 {
+        return 1.0;
+    }
+
+    intersection as f64 / union as f64
+}
+
+/// The Jaccard similarity between `a` and `b`'s trigram sets; see
+/// [`trigram_similarity_from_sets`].
+pub fn trigram_similarity(a: &str, b: &str) -> f64 {
+    trigram_similarity_from_sets(&trigrams(a), &trigrams(b))
+}
+
+/// A line together with how much trigram overlap it shares with a query, used by
+/// [`crate::models::IndexCollection::find_lines_similar_to`] to rank candidate
+/// passwords by rough textual similarity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrigramMatch {
+    /// The line itself.
+    pub line: String,
+
+    /// The Jaccard similarity between `line`'s trigrams and the query's, from `0.0`
+    /// (nothing in common) to `1.0` (identical trigram sets).
+    pub similarity: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_strings_have_a_similarity_of_one() {
+        assert_eq!(trigram_similarity("password", "password"), 1.0);
+    }
+
+    #[test]
+    fn completely_different_strings_have_no_overlap() {
+        assert_eq!(trigram_similarity("abc", "xyz"), 0.0);
+    }
+
+    #[test]
+    fn a_close_variant_scores_higher_than_an_unrelated_line() {
+        let close = trigram_similarity("password1", "password2");
+        let unrelated = trigram_similarity("password1", "banana");
+
+        assert!(close > unrelated);
+    }
+
+    #[test]
+    fn strings_shorter_than_a_trigram_compare_as_a_whole() {
+        assert_eq!(trigram_similarity("ab", "ab"), 1.0);
+        assert_eq!(trigram_similarity("ab", "cd"), 0.0);
+    }
+
+    #[test]
+    fn two_empty_strings_are_identical() {
+        assert_eq!(trigram_similarity("", ""), 1.0);
+    }
+}
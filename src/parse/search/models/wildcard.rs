@@ -0,0 +1,105 @@
+//! Translate a shell-glob-style wildcard query into an anchored regex, and scan an
+//! index file's lines against it.
+
+use std::io::{self, BufReader, Read};
+
+use super::LossyLines;
+
+/// Compile a wildcard query into a [`regex::Regex`] that matches an entire line.
+///
+/// `*` matches any run of characters (including none), `?` matches exactly one
+/// character; every other character is matched literally.
+pub fn compile_wildcard(query: &str) -> Result<regex::Regex, regex::Error> {
+    let mut pattern = String::with_capacity(query.len() + 2);
+    pattern.push('^');
+
+    for c in query.chars() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    pattern.push('$');
+
+    regex::Regex::new(&pattern)
+}
+
+/// A scanner that yields every line matching any of a set of wildcard patterns.
+///
+/// Unlike [`super::LinesScanner`], which relies on Aho-Corasick substring matching,
+/// glob wildcards need whole-line matching, so this reads and tests each line in turn
+/// rather than streaming matches out of the underlying reader.
+pub struct WildcardScanner<R: Read> {
+    lines: LossyLines<R>,
+    patterns: Vec<regex::Regex>,
+}
+
+impl<R: Read> WildcardScanner<R> {
+    /// Create a new scanner that matches lines against any of `keys`, each compiled
+    /// as a wildcard pattern by [`compile_wildcard`].
+    pub fn new(reader: BufReader<R>, keys: &[&str]) -> io::Result<Self> {
+        let patterns = keys
+            .iter()
+            .map(|key| compile_wildcard(key))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Failed to compile wildcard pattern: {error}"),
+                )
+            })?;
+
+        Ok(Self {
+            lines: LossyLines::new(reader),
+            patterns,
+        })
+    }
+}
+
+impl<R: Read> Iterator for WildcardScanner<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(error) => return Some(Err(error)),
+            };
+
+            if self.patterns.iter().any(|pattern| pattern.is_match(&line)) {
+                return Some(Ok(line));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn star_matches_any_run_of_characters() {
+        let re = compile_wildcard("pass*123").expect("Failed to compile the wildcard.");
+        assert!(re.is_match("pass123"));
+        assert!(re.is_match("passWORD123"));
+        assert!(!re.is_match("password1234"));
+    }
+
+    #[test]
+    fn question_mark_matches_exactly_one_character() {
+        let re = compile_wildcard("p?ssword").expect("Failed to compile the wildcard.");
+        assert!(re.is_match("password"));
+        assert!(re.is_match("p4ssword"));
+        assert!(!re.is_match("pssword"));
+        assert!(!re.is_match("paassword"));
+    }
+
+    #[test]
+    fn other_regex_metacharacters_are_escaped() {
+        let re = compile_wildcard("a.b+c").expect("Failed to compile the wildcard.");
+        assert!(re.is_match("a.b+c"));
+        assert!(!re.is_match("aXbYc"));
+    }
+}
@@ -30,3 +30,117 @@ pub fn map_characters_to_fuzzy<'c>(
         .map(|c| c.to_ascii_lowercase())
         .map(|c| *mapping.get(&c).unwrap_or(&c))
 }
+
+/// Map a character to a similar character, in the password sense, additionally
+/// folding keyboard-adjacent characters (see [`character::get_keyboard_adjacency_mapping`])
+/// together so that typo-shifted variants such as `qassword` compare equal to
+/// `password`.
+///
+/// The keyboard-adjacency mapping is consulted first, since it covers symbols such as
+/// `!` that [`character::get_fuzzy_mapping`] already maps elsewhere for the leet-speak
+/// case.
+pub fn map_characters_to_fuzzy_keyboard_adjacent<'c>(
+    chars: impl Iterator<Item = char> + 'c,
+) -> impl Iterator<Item = char> + 'c {
+    let fuzzy_mapping: &hashbrown::HashMap<char, char> = character::get_fuzzy_mapping();
+    let keyboard_mapping: &hashbrown::HashMap<char, char> =
+        character::get_keyboard_adjacency_mapping();
+
+    chars.map(|c| c.to_ascii_lowercase()).map(|c| {
+        *keyboard_mapping
+            .get(&c)
+            .or_else(|| fuzzy_mapping.get(&c))
+            .unwrap_or(&c)
+    })
+}
+
+/// Fold `input` to a form suitable for Unicode-aware case-insensitive comparison:
+/// NFKC-normalise it first, so that differently-composed representations of the same
+/// text (e.g. a precomposed accented letter versus its base letter plus a combining
+/// mark) compare equal, then lowercase the result.
+///
+/// Unlike [`convert_to_fuzzy_string`], this does not strip diacritics or fold
+/// look-alike characters - `"PELÉ"` and `"pelé"` compare equal, but `"pelé"` and
+/// `"pele"` do not; plain ASCII-only lowercasing, which only folds the ASCII
+/// letters, is used instead when Unicode case folding is not requested.
+pub fn unicode_case_fold(input: &str) -> String {
+    input.nfkc().collect::<String>().to_lowercase()
+}
+
+/// The Soundex digit a consonant is folded to, or `None` for a vowel (and `h`/`w`/`y`),
+/// which drop out of the code entirely rather than contributing a digit.
+fn soundex_digit(c: char) -> Option<char> {
+    match c.to_ascii_lowercase() {
+        'b' | 'f' | 'p' | 'v' => Some('1'),
+        'c' | 'g' | 'j' | 'k' | 'q' | 's' | 'x' | 'z' => Some('2'),
+        'd' | 't' => Some('3'),
+        'l' => Some('4'),
+        'm' | 'n' => Some('5'),
+        'r' => Some('6'),
+        _ => None,
+    }
+}
+
+/// Encode `input` as an American Soundex code: its first letter, followed by up to
+/// three digits for the consonant sounds that follow, zero-padded to always be four
+/// characters long - so that words which sound alike (`"Robert"`/`"Rupert"`,
+/// `"jhonny"`/`"johnny"`) encode identically even when spelled differently.
+///
+/// Consecutive letters that map to the same digit only contribute it once (so
+/// `"Bb"` does not double up), and non-alphabetic characters are skipped entirely.
+/// An input with no alphabetic characters at all encodes as an empty string.
+pub fn soundex(input: &str) -> String {
+    let mut letters = input.chars().filter(|c| c.is_alphabetic());
+
+    let Some(first) = letters.next() else {
+        return String::new();
+    };
+
+    let mut code = String::with_capacity(4);
+    code.push(first.to_ascii_uppercase());
+
+    let mut last_digit = soundex_digit(first);
+
+    for c in letters {
+        let digit = soundex_digit(c);
+
+        if let Some(digit) = digit.filter(|&digit| Some(digit) != last_digit) {
+            code.push(digit);
+        }
+
+        last_digit = digit;
+
+        if code.len() == 4 {
+            break;
+        }
+    }
+
+    while code.len() < 4 {
+        code.push('0');
+    }
+
+    code
+}
+
+/// Escape ASCII control bytes, `DEL`, and the Unicode replacement character as
+/// `\xNN`, leaving everything else - including non-ASCII text - untouched.
+///
+/// A dump line is not guaranteed to be printable: it may already have been
+/// lossily decoded from invalid UTF-8 (see [`super::search::LossyLines`]), or it
+/// may simply contain raw control bytes such as a stray newline pulled in by a
+/// malformed source file. Either can break a terminal or a naively-quoted JSON
+/// encoder, so this gives callers an opt-in way to render such a line safely
+/// without changing what gets stored or matched against.
+pub fn hex_escape_unprintable(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+
+    for c in input.chars() {
+        match c {
+            '\u{0000}'..='\u{001f}' | '\u{007f}' => escaped.push_str(&format!("\\x{:02x}", c as u32)),
+            '\u{fffd}' => escaped.push_str("\\xef\\xbf\\xbd"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}